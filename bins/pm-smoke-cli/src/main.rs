@@ -3,37 +3,61 @@
 //! Commands:
 //! - `market`: Subscribe to market channel and collect messages
 //! - `user`: Subscribe to user channel (requires credentials)
+//! - `replay`: Replay a recorded JSONL file through the library
 //! - `rest`: Test REST API connectivity
 //! - `resolve`: Resolve current 15-minute market for trading
+//! - `api-key`: Create or derive L2 API credentials from a wallet (L1 auth)
 //!
 //! # Usage
 //! ```bash
 //! # Market channel smoke test
 //! pm_smoke market --asset-id <ASSET_ID> --out data/ws_raw.jsonl --limit 500
+//! pm_smoke market --asset-id <ASSET_ID> --out data/ws_env.jsonl --envelope
+//! pm_smoke market --asset-id <ASSET_ID> --out data/ws/market.jsonl --limit 0 \
+//!     --rotate-secs 3600 --compress zstd
+//! pm_smoke market --asset-id <ASSET_ID> --stale-secs 30 --stale-action resubscribe
 //!
 //! # User channel (requires env vars)
 //! POLY_API_KEY=... POLY_API_SECRET=... POLY_API_PASSPHRASE=...
 //! pm_smoke user --market-id <MARKET_ID> --out data/user_raw.jsonl --limit 200
 //!
+//! # Replay a recording (as fast as possible, or paced by message timestamps)
+//! pm_smoke replay --input data/ws_raw.jsonl
+//! pm_smoke replay --input data/ws_raw.jsonl --real-time --speed 10
+//! pm_smoke replay --input data/ws/market_20250101_000000.manifest.json
+//!
 //! # REST connectivity test
 //! pm_smoke rest --asset-id <ASSET_ID>
 //!
 //! # Resolve current BTC 15-minute market
 //! pm_smoke resolve --series btc15m
 //! pm_smoke resolve --series btc15m --out resolved.json
+//! pm_smoke resolve --series btc15m --backend rsclob
+//!
+//! # Onboard a wallet: create (or derive) API credentials
+//! POLY_PRIVATE_KEY=0x... pm_smoke api-key
+//! POLY_PRIVATE_KEY=0x... pm_smoke api-key --derive --nonce 0
 //! ```
 
 use anyhow::Result;
 use chrono::{DateTime, Utc};
-use clap::{Parser, Subcommand};
-use std::path::PathBuf;
+use clap::{Args, Parser, Subcommand};
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use tracing::{error, info, warn};
 
+use polymarket_adapter::backend::{self, BackendKind};
+use polymarket_adapter::book::OrderBooks;
 use polymarket_adapter::gamma::{MarketResolver, MarketSeries, SwitchController};
-use polymarket_adapter::httpws::{ApiCredentials, MarketWsClient, RestClient, UserWsClient};
-use polymarket_adapter::types::{ResolveResult, SwitchAction, SwitchConfig};
+use polymarket_adapter::httpws::{
+    ApiCredentials, Compression, JsonlRecorder, MarketWsClient, RecordFormat, RestClient,
+    RotationConfig, StaleAction, UserWsClient, WalletSigner, WatchdogConfig,
+};
+use polymarket_adapter::order_manager::OrderManager;
+use polymarket_adapter::replay::{ReplayMode, ReplaySource};
+use polymarket_adapter::types::{MessageStats, ResolveResult, SwitchAction, SwitchConfig};
 use polymarket_adapter::{CLOB_REST_BASE, CLOB_WSS_ENDPOINT, GAMMA_API_BASE};
 
 #[derive(Parser)]
@@ -49,6 +73,44 @@ struct Cli {
     log_level: String,
 }
 
+/// Recording options shared by `market` and `user`
+#[derive(Args)]
+struct RecordArgs {
+    /// Record enveloped lines (receive time, connection id, epoch) instead of raw frames
+    #[arg(long, default_value = "false")]
+    envelope: bool,
+
+    /// Start a new segment every N seconds (0 = no time rotation)
+    #[arg(long, default_value = "0")]
+    rotate_secs: u64,
+
+    /// Start a new segment every N megabytes (0 = no size rotation)
+    #[arg(long, default_value = "0")]
+    rotate_mb: u64,
+
+    /// Compression of closed segments when rotating (none, gzip, zstd)
+    #[arg(long, default_value = "none")]
+    compress: String,
+}
+
+impl RecordArgs {
+    fn rotation(&self) -> Result<Option<RotationConfig>> {
+        let compression = Compression::from_str(&self.compress)
+            .ok_or_else(|| anyhow::anyhow!("Unknown compression: {}", self.compress))?;
+        if self.rotate_secs == 0 && self.rotate_mb == 0 {
+            if compression != Compression::None {
+                warn!("--compress only applies with --rotate-secs or --rotate-mb");
+            }
+            return Ok(None);
+        }
+        Ok(Some(RotationConfig {
+            max_age: (self.rotate_secs > 0).then(|| Duration::from_secs(self.rotate_secs)),
+            max_bytes: (self.rotate_mb > 0).then(|| self.rotate_mb * 1024 * 1024),
+            compression,
+        }))
+    }
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// Subscribe to market channel and collect messages
@@ -68,6 +130,17 @@ enum Commands {
         /// Enable feature-flagged messages (best_bid_ask, new_market, etc.)
         #[arg(long, default_value = "true")]
         enable_features: bool,
+
+        /// Flag an asset as stale after N seconds without a message (0 = off)
+        #[arg(long, default_value = "0")]
+        stale_secs: u64,
+
+        /// Reaction to stale assets (report, resubscribe, reconnect)
+        #[arg(long, default_value = "report")]
+        stale_action: String,
+
+        #[command(flatten)]
+        record: RecordArgs,
     },
 
     /// Subscribe to user channel (requires POLY_API_KEY, POLY_API_SECRET, POLY_API_PASSPHRASE)
@@ -83,6 +156,28 @@ enum Commands {
         /// Maximum messages to collect (0 = unlimited until Ctrl+C)
         #[arg(long, default_value = "200")]
         limit: u64,
+
+        #[command(flatten)]
+        record: RecordArgs,
+    },
+
+    /// Replay a recorded JSONL file (from `market` or `user`) through the library
+    Replay {
+        /// Recorded JSONL file (.gz/.zst ok) or session manifest (*.manifest.json)
+        #[arg(long)]
+        input: PathBuf,
+
+        /// Pace by message timestamps instead of as fast as possible
+        #[arg(long, default_value = "false")]
+        real_time: bool,
+
+        /// Speed multiplier for --real-time
+        #[arg(long, default_value = "1.0")]
+        speed: f64,
+
+        /// Maximum messages to replay (0 = whole file)
+        #[arg(long, default_value = "0")]
+        limit: u64,
     },
 
     /// Test REST API connectivity
@@ -109,6 +204,10 @@ enum Commands {
         /// Skip CLOB price validation
         #[arg(long, default_value = "false")]
         skip_clob_check: bool,
+
+        /// CLOB backend for price validation (httpws, rsclob)
+        #[arg(long, default_value = "httpws")]
+        backend: String,
     },
 
     /// Watch market switches with two-phase safety rails
@@ -133,6 +232,17 @@ enum Commands {
         #[arg(long, default_value = "0")]
         duration: u64,
     },
+
+    /// Create or derive L2 API credentials (requires POLY_PRIVATE_KEY)
+    ApiKey {
+        /// Only derive existing credentials, never create new ones
+        #[arg(long, default_value = "false")]
+        derive: bool,
+
+        /// Key nonce (different nonces yield different credentials)
+        #[arg(long, default_value = "0")]
+        nonce: u64,
+    },
 }
 
 #[tokio::main]
@@ -156,20 +266,34 @@ async fn main() -> Result<()> {
     });
 
     match cli.command {
-        Commands::Market { asset_id: asset_ids, out, limit, enable_features } => {
-            run_market_smoke(asset_ids, out, limit, enable_features, shutdown).await
+        Commands::Market {
+            asset_id: asset_ids,
+            out,
+            limit,
+            enable_features,
+            stale_secs,
+            stale_action,
+            record,
+        } => {
+            let watchdog = watchdog_config(stale_secs, &stale_action)?;
+            run_market_smoke(asset_ids, out, limit, enable_features, watchdog, record, shutdown)
+                .await
         }
-        Commands::User { market_id, out, limit } => {
-            run_user_smoke(market_id, out, limit, shutdown).await
+        Commands::User { market_id, out, limit, record } => {
+            run_user_smoke(market_id, out, limit, record, shutdown).await
+        }
+        Commands::Replay { input, real_time, speed, limit } => {
+            run_replay(input, real_time, speed, limit, shutdown).await
         }
         Commands::Rest { asset_id } => run_rest_smoke(asset_id).await,
-        Commands::Resolve { series, asof, out, skip_clob_check } => {
-            run_resolve(series, asof, out, skip_clob_check).await
+        Commands::Resolve { series, asof, out, skip_clob_check, backend } => {
+            run_resolve(series, asof, out, skip_clob_check, backend).await
         }
         Commands::SwitchWatch { series, lead_time, min_consecutive, poll_interval, duration } => {
             run_switch_watch(series, lead_time, min_consecutive, poll_interval, duration, shutdown)
                 .await
         }
+        Commands::ApiKey { derive, nonce } => run_api_key(derive, nonce).await,
     }
 }
 
@@ -178,6 +302,8 @@ async fn run_market_smoke(
     out: PathBuf,
     limit: u64,
     enable_features: bool,
+    watchdog: Option<WatchdogConfig>,
+    record: RecordArgs,
     shutdown: Arc<AtomicBool>,
 ) -> Result<()> {
     info!("=== Market Channel Smoke Test ===");
@@ -189,6 +315,8 @@ async fn run_market_smoke(
     info!("Output: {}", out.display());
     info!("Limit: {} (0 = unlimited)", limit);
     info!("Features enabled: {}", enable_features);
+    info!("Watchdog: {:?}", watchdog);
+    info!("Envelope: {}", record.envelope);
     info!("Press Ctrl+C to stop");
     info!("");
 
@@ -199,35 +327,44 @@ async fn run_market_smoke(
 
     let mut client = MarketWsClient::new(asset_ids);
     client.set_enable_features(enable_features);
+    if let Some(config) = watchdog {
+        client.set_watchdog(config);
+    }
+    let feed = client.watchdog();
 
-    let stats = client.run(&out, limit, shutdown).await?;
+    let recorder = create_recorder(&out, &record).await?;
+    let manifest = recorder.manifest_path().map(Path::to_path_buf);
+    let stats = client.run_with(Some(recorder), None, limit, shutdown).await?;
 
     // Print summary
     info!("");
     info!("=== Summary ===");
-    info!("Total messages: {}", stats.total_messages);
-    info!("Parsed OK: {}", stats.parsed_ok);
-    info!("Unknown type count: {}", stats.unknown_type_count);
-    info!("Snapshot arrays: {}", stats.snapshot_array_count);
-    info!("Parse errors: {}", stats.parse_error_count);
-    info!("Last message type: {:?}", stats.last_message_type);
-    info!("");
-    info!("Message type distribution:");
-    let mut types: Vec<_> = stats.type_counts.iter().collect();
-    types.sort_by(|a, b| b.1.cmp(a.1));
-    for (msg_type, count) in types {
-        info!("  {}: {}", msg_type, count);
-    }
+    log_stats(&stats);
     info!("");
-    info!("Output written to: {}", out.display());
+    info!("Stale assets: {:?}", feed.stale_assets());
+    info!("Output written to: {}", manifest.as_deref().unwrap_or(&out).display());
 
     Ok(())
 }
 
+fn watchdog_config(stale_secs: u64, action: &str) -> Result<Option<WatchdogConfig>> {
+    let action = StaleAction::from_str(action)
+        .ok_or_else(|| anyhow::anyhow!("Unknown stale action: {}", action))?;
+    if stale_secs == 0 {
+        return Ok(None);
+    }
+    Ok(Some(WatchdogConfig {
+        stale_after: Duration::from_secs(stale_secs),
+        action,
+        ..Default::default()
+    }))
+}
+
 async fn run_user_smoke(
     market_id: String,
     out: PathBuf,
     limit: u64,
+    record: RecordArgs,
     shutdown: Arc<AtomicBool>,
 ) -> Result<()> {
     info!("=== User Channel Smoke Test ===");
@@ -235,6 +372,7 @@ async fn run_user_smoke(
     info!("Market ID: {}", market_id);
     info!("Output: {}", out.display());
     info!("Limit: {} (0 = unlimited)", limit);
+    info!("Envelope: {}", record.envelope);
     info!("");
 
     // Load credentials from environment
@@ -265,11 +403,97 @@ async fn run_user_smoke(
 
     let client = UserWsClient::new(credentials, vec![market_id]);
 
-    let stats = client.run(&out, limit, shutdown).await?;
+    let recorder = create_recorder(&out, &record).await?;
+    let manifest = recorder.manifest_path().map(Path::to_path_buf);
+    let stats = client.run_with(Some(recorder), None, limit, shutdown).await?;
 
     // Print summary
     info!("");
     info!("=== Summary ===");
+    log_stats(&stats);
+    info!("");
+    info!("Output written to: {}", manifest.as_deref().unwrap_or(&out).display());
+
+    Ok(())
+}
+
+async fn run_replay(
+    input: PathBuf,
+    real_time: bool,
+    speed: f64,
+    limit: u64,
+    shutdown: Arc<AtomicBool>,
+) -> Result<()> {
+    let mode =
+        if real_time { ReplayMode::RealTime { speed } } else { ReplayMode::AsFastAsPossible };
+    info!("=== Replay ===");
+    info!("Input: {}", input.display());
+    info!("Mode: {:?}", mode);
+    info!("Limit: {} (0 = whole file)", limit);
+    info!("");
+
+    // Same consumers as a live run: local books and order/fill state
+    let (tx, mut rx) = tokio::sync::mpsc::channel(1024);
+    let source = if input.to_string_lossy().ends_with(".manifest.json") {
+        let source = ReplaySource::from_manifest(&input).await?;
+        info!("Session segments: {}", source.paths().len());
+        source
+    } else {
+        ReplaySource::new(&input)
+    };
+    let source = source.with_mode(mode);
+    let handle = tokio::spawn(async move { source.run_with(Some(tx), limit, shutdown).await });
+
+    let mut books = OrderBooks::new();
+    let mut orders = OrderManager::new();
+    while let Some(msg) = rx.recv().await {
+        books.apply(&msg);
+        orders.apply(&msg);
+    }
+    let stats = handle.await??;
+
+    info!("");
+    info!("=== Summary ===");
+    log_stats(&stats);
+    info!("");
+    info!("Books: {}", books.len());
+    let mut asset_ids: Vec<_> = books.asset_ids().collect();
+    asset_ids.sort();
+    for asset_id in asset_ids {
+        if let Some(book) = books.get(asset_id) {
+            info!(
+                "  {}: bid={:?} ask={:?}",
+                asset_id,
+                book.best_bid().map(|l| &l.price),
+                book.best_ask().map(|l| &l.price)
+            );
+        }
+    }
+    info!(
+        "Orders: {} ({} open), fills: {}",
+        orders.len(),
+        orders.open_orders(None).len(),
+        orders.fills(None).len()
+    );
+
+    Ok(())
+}
+
+/// Single file at `out`, or a rotating session in its directory named after its stem
+async fn create_recorder(out: &Path, record: &RecordArgs) -> Result<JsonlRecorder> {
+    let format = if record.envelope { RecordFormat::Envelope } else { RecordFormat::Raw };
+    match record.rotation()? {
+        Some(config) => {
+            let dir = out.parent().unwrap_or(Path::new("."));
+            let prefix = out.file_stem().map(|s| s.to_string_lossy()).unwrap_or_default();
+            info!("Rotation: {:?}", config);
+            Ok(JsonlRecorder::create_rotating(dir, &prefix, format, config).await?)
+        }
+        None => Ok(JsonlRecorder::create_with_format(out, format).await?),
+    }
+}
+
+fn log_stats(stats: &MessageStats) {
     info!("Total messages: {}", stats.total_messages);
     info!("Parsed OK: {}", stats.parsed_ok);
     info!("Unknown type count: {}", stats.unknown_type_count);
@@ -283,8 +507,35 @@ async fn run_user_smoke(
     for (msg_type, count) in types {
         info!("  {}: {}", msg_type, count);
     }
-    info!("");
-    info!("Output written to: {}", out.display());
+}
+
+async fn run_api_key(derive: bool, nonce: u64) -> Result<()> {
+    let signer = match WalletSigner::from_env() {
+        Some(signer) => signer?,
+        None => {
+            error!("Missing wallet key. Set POLY_PRIVATE_KEY");
+            anyhow::bail!("Missing POLY_PRIVATE_KEY");
+        }
+    };
+
+    info!("=== API Key ({}) ===", if derive { "derive" } else { "create or derive" });
+    info!("Base URL: {}", CLOB_REST_BASE);
+    info!("Address: {}", signer.address());
+    info!("Nonce: {}", nonce);
+
+    let client = RestClient::new()?;
+    let credentials = if derive {
+        client.derive_api_key(&signer, nonce).await?
+    } else {
+        client.create_or_derive_api_key(&signer, nonce).await?
+    };
+
+    info!("Credentials obtained: {:?}", credentials);
+
+    // Secrets go to stdout only, ready to paste into an env file
+    println!("POLY_API_KEY={}", credentials.api_key);
+    println!("POLY_API_SECRET={}", credentials.secret);
+    println!("POLY_API_PASSPHRASE={}", credentials.passphrase);
 
     Ok(())
 }
@@ -315,21 +566,13 @@ async fn run_rest_smoke(asset_id: Option<String>) -> Result<()> {
             Ok(book) => {
                 info!("Book response:");
                 // Pretty print first few levels
-                if let Some(bids) = book.get("bids") {
-                    if let Some(arr) = bids.as_array() {
-                        info!("  Bids: {} levels", arr.len());
-                        for (i, bid) in arr.iter().take(3).enumerate() {
-                            info!("    {}: {:?}", i, bid);
-                        }
-                    }
+                info!("  Bids: {} levels", book.bids.len());
+                for (i, bid) in book.bids.iter().take(3).enumerate() {
+                    info!("    {}: {} @ {}", i, bid.size, bid.price);
                 }
-                if let Some(asks) = book.get("asks") {
-                    if let Some(arr) = asks.as_array() {
-                        info!("  Asks: {} levels", arr.len());
-                        for (i, ask) in arr.iter().take(3).enumerate() {
-                            info!("    {}: {:?}", i, ask);
-                        }
-                    }
+                info!("  Asks: {} levels", book.asks.len());
+                for (i, ask) in book.asks.iter().take(3).enumerate() {
+                    info!("    {}: {} @ {}", i, ask.size, ask.price);
                 }
             }
             Err(e) => {
@@ -340,21 +583,21 @@ async fn run_rest_smoke(asset_id: Option<String>) -> Result<()> {
         info!("");
         info!("Fetching midpoint...");
         match client.get_midpoint(&asset_id).await {
-            Ok(mid) => info!("Midpoint: {:?}", mid),
+            Ok(mid) => info!("Midpoint: {}", mid.mid),
             Err(e) => error!("Failed to get midpoint: {}", e),
         }
 
         info!("");
         info!("Fetching spread...");
         match client.get_spread(&asset_id).await {
-            Ok(spread) => info!("Spread: {:?}", spread),
+            Ok(spread) => info!("Spread: {}", spread.spread),
             Err(e) => error!("Failed to get spread: {}", e),
         }
 
         info!("");
         info!("Fetching tick size...");
         match client.get_tick_size(&asset_id).await {
-            Ok(tick) => info!("Tick size: {:?}", tick),
+            Ok(tick) => info!("Tick size: {}", tick.minimum_tick_size),
             Err(e) => error!("Failed to get tick size: {}", e),
         }
     } else {
@@ -374,6 +617,7 @@ async fn run_resolve(
     asof: Option<String>,
     out: Option<PathBuf>,
     skip_clob_check: bool,
+    backend: String,
 ) -> Result<()> {
     info!("=== Market Resolver ===");
     info!("Gamma API: {}", GAMMA_API_BASE);
@@ -405,10 +649,18 @@ async fn run_resolve(
     info!("");
 
     // Create resolver
-    let mut config = polymarket_adapter::gamma::resolver::ResolverConfig::default();
-    config.clob_validation = !skip_clob_check;
+    let config = polymarket_adapter::gamma::resolver::ResolverConfig {
+        clob_validation: !skip_clob_check,
+        ..Default::default()
+    };
+
+    let backend_kind = BackendKind::from_str(&backend).ok_or_else(|| {
+        anyhow::anyhow!("Unknown backend: {}. Supported: httpws, rsclob", backend)
+    })?;
+    info!("CLOB backend: {:?}", backend_kind);
 
-    let resolver = MarketResolver::with_config(config)?;
+    let clob = backend::connect(backend_kind, CLOB_REST_BASE)?;
+    let resolver = MarketResolver::with_backend(GAMMA_API_BASE, clob, config)?;
 
     // Resolve
     info!("Resolving market...");
@@ -0,0 +1,181 @@
+//! Backend-neutral CLOB interface
+//!
+//! # Design
+//! - `ClobBackend` is implemented by `httpws::RestClient` and `rsclob::RsClobClient`
+//! - Decimals are returned as strings, like every other type in `types.rs`
+//! - Methods return `AdapterResult`; a response without the expected field
+//!   fails with `AdapterError::MissingField`, so callers can tell "no price"
+//!   apart from an API error by matching the variant
+//! - `BackendKind` picks a backend from a config value ("httpws" / "rsclob");
+//!   `connect` fails with `AdapterError::NotFound` if it is not compiled in
+//!
+//! # Usage
+//! ```ignore
+//! let backend = connect(BackendKind::from_str("rsclob").unwrap(), CLOB_REST_BASE)?;
+//! let resolver = MarketResolver::with_backend(GAMMA_API_BASE, backend, config)?;
+//! ```
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::error::{AdapterError, AdapterResult};
+use crate::types::{BookMessage, ClobMarket, OrderSide};
+
+/// Common CLOB operations shared by all backends
+#[async_trait]
+pub trait ClobBackend: Send + Sync {
+    /// Backend name for logs ("httpws" / "rsclob")
+    fn name(&self) -> &'static str;
+
+    /// Order book snapshot for a token
+    async fn book(&self, token_id: &str) -> AdapterResult<BookMessage>;
+
+    /// Best price for a token on one side
+    async fn price(&self, token_id: &str, side: OrderSide) -> AdapterResult<String>;
+
+    /// Midpoint price for a token
+    async fn midpoint(&self, token_id: &str) -> AdapterResult<String>;
+
+    /// Bid-ask spread for a token
+    async fn spread(&self, token_id: &str) -> AdapterResult<String>;
+
+    /// Minimum tick size for a token
+    async fn tick_size(&self, token_id: &str) -> AdapterResult<String>;
+
+    /// Market by condition id
+    async fn market(&self, condition_id: &str) -> AdapterResult<ClobMarket>;
+}
+
+#[async_trait]
+impl<T: ClobBackend + ?Sized> ClobBackend for Box<T> {
+    fn name(&self) -> &'static str {
+        (**self).name()
+    }
+
+    async fn book(&self, token_id: &str) -> AdapterResult<BookMessage> {
+        (**self).book(token_id).await
+    }
+
+    async fn price(&self, token_id: &str, side: OrderSide) -> AdapterResult<String> {
+        (**self).price(token_id, side).await
+    }
+
+    async fn midpoint(&self, token_id: &str) -> AdapterResult<String> {
+        (**self).midpoint(token_id).await
+    }
+
+    async fn spread(&self, token_id: &str) -> AdapterResult<String> {
+        (**self).spread(token_id).await
+    }
+
+    async fn tick_size(&self, token_id: &str) -> AdapterResult<String> {
+        (**self).tick_size(token_id).await
+    }
+
+    async fn market(&self, condition_id: &str) -> AdapterResult<ClobMarket> {
+        (**self).market(condition_id).await
+    }
+}
+
+#[async_trait]
+impl<T: ClobBackend + ?Sized> ClobBackend for Arc<T> {
+    fn name(&self) -> &'static str {
+        (**self).name()
+    }
+
+    async fn book(&self, token_id: &str) -> AdapterResult<BookMessage> {
+        (**self).book(token_id).await
+    }
+
+    async fn price(&self, token_id: &str, side: OrderSide) -> AdapterResult<String> {
+        (**self).price(token_id, side).await
+    }
+
+    async fn midpoint(&self, token_id: &str) -> AdapterResult<String> {
+        (**self).midpoint(token_id).await
+    }
+
+    async fn spread(&self, token_id: &str) -> AdapterResult<String> {
+        (**self).spread(token_id).await
+    }
+
+    async fn tick_size(&self, token_id: &str) -> AdapterResult<String> {
+        (**self).tick_size(token_id).await
+    }
+
+    async fn market(&self, condition_id: &str) -> AdapterResult<ClobMarket> {
+        (**self).market(condition_id).await
+    }
+}
+
+/// Backend selector
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BackendKind {
+    /// Custom reqwest implementation
+    Httpws,
+    /// Official polymarket-client-sdk
+    Rsclob,
+}
+
+impl BackendKind {
+    /// Parse from a config value
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "httpws" | "http" => Some(BackendKind::Httpws),
+            "rsclob" | "sdk" | "official" => Some(BackendKind::Rsclob),
+            _ => None,
+        }
+    }
+
+    /// Whether this backend was compiled in
+    pub fn is_enabled(&self) -> bool {
+        match self {
+            BackendKind::Httpws => cfg!(feature = "httpws"),
+            BackendKind::Rsclob => cfg!(feature = "rsclob"),
+        }
+    }
+
+    /// Preferred compiled-in backend (rsclob when enabled)
+    pub fn preferred() -> Self {
+        if cfg!(feature = "rsclob") {
+            BackendKind::Rsclob
+        } else {
+            BackendKind::Httpws
+        }
+    }
+}
+
+/// Create a backend of the given kind against a CLOB base URL
+#[cfg_attr(not(any(feature = "httpws", feature = "rsclob")), allow(unused_variables))]
+pub fn connect(kind: BackendKind, base_url: &str) -> AdapterResult<Arc<dyn ClobBackend>> {
+    match kind {
+        #[cfg(feature = "httpws")]
+        BackendKind::Httpws => Ok(Arc::new(crate::httpws::RestClient::with_base_url(base_url)?)),
+        #[cfg(feature = "rsclob")]
+        BackendKind::Rsclob => {
+            let client = crate::rsclob::RsClobClient::with_host(base_url)
+                .map_err(crate::rsclob::client::adapter_error)?;
+            Ok(Arc::new(client))
+        }
+        #[allow(unreachable_patterns)]
+        other => Err(AdapterError::NotFound(format!(
+            "backend {:?} not compiled in (enable the feature)",
+            other
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backend_kind_from_str() {
+        assert_eq!(BackendKind::from_str("httpws"), Some(BackendKind::Httpws));
+        assert_eq!(BackendKind::from_str("RSCLOB"), Some(BackendKind::Rsclob));
+        assert_eq!(BackendKind::from_str("grpc"), None);
+        assert!(BackendKind::preferred().is_enabled());
+    }
+}
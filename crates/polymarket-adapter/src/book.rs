@@ -0,0 +1,1196 @@
+//! Local L2 order book reconstruction from market channel messages
+//!
+//! # Message Flow
+//! - `book`: full snapshot for one asset, replaces both sides
+//! - Initial `SnapshotArray`: top-level array of `book` objects sent on subscribe
+//! - `price_change`: absolute size per price level (size "0" removes the level)
+//!
+//! # Hash Verification (experimental, off by default)
+//! `book` and `price_change` entries carry the server's book hash. When enabled,
+//! the local book is hashed after each `price_change` and compared; a mismatch
+//! marks the asset as diverged until a fresh snapshot arrives. `BookKeeper`
+//! (httpws backend) then resyncs diverged assets via `GET /book`, fetched in
+//! background tasks so a slow REST call never stalls the stream.
+//!
+//! The hashed text follows py-clob-client's `OrderBookSummary`, but the tests
+//! only check it against hashes computed by this module, not against hashes
+//! captured from the live feed. Until such a fixture exists, enabling it may
+//! flag every book as diverged; with it off (the default) books are never
+//! compared to the server and only snapshots (feed or `BookKeeper::resync`)
+//! correct them.
+//!
+//! # Disconnects
+//! A `LifecycleEvent::Disconnected` in the stream marks the books of the
+//! assets it lists stale: updates may have been missed while the socket was
+//! down. The client resubscribes on reconnect, and the fresh `book` snapshot
+//! clears the flag.
+//! Stale books are reported in `BookUpdate::invalidated`, not as diverged,
+//! so a reconnect never triggers a REST resync per asset.
+//!
+//! # Price Keys
+//! Levels keep the original price/size strings for output, but are keyed by a
+//! fixed-point integer so "0.5" and "0.50" map to the same level and ordering
+//! never depends on f64 comparison.
+//!
+//! # Source
+//! - Market Channel: https://docs.polymarket.com/developers/CLOB/websocket/market-channel
+
+#[cfg(feature = "httpws")]
+use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap};
+#[cfg(feature = "httpws")]
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+use sha1::{Digest, Sha1};
+#[cfg(feature = "httpws")]
+use tokio::sync::mpsc;
+#[cfg(feature = "httpws")]
+use tracing::info;
+use tracing::{debug, warn};
+
+#[cfg(feature = "httpws")]
+use crate::error::AdapterResult;
+#[cfg(feature = "httpws")]
+use crate::httpws::RestClient;
+use crate::types::{
+    BookMessage, LifecycleEvent, MarketMessage, PriceChangeEntry, WsInboundMessage,
+};
+
+/// Number of decimal places kept in fixed-point price keys
+const PRICE_KEY_DECIMALS: u32 = 6;
+
+/// Book side
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BookSide {
+    /// Bids (BUY orders resting on the book)
+    Bid,
+    /// Asks (SELL orders resting on the book)
+    Ask,
+}
+
+impl BookSide {
+    /// Parse from the `side` field of a price change ("BUY" / "SELL", any case)
+    pub fn from_order_side(side: &str) -> Option<Self> {
+        match side.to_ascii_uppercase().as_str() {
+            "BUY" => Some(BookSide::Bid),
+            "SELL" => Some(BookSide::Ask),
+            _ => None,
+        }
+    }
+}
+
+/// Single price level
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BookLevel {
+    /// Price as received from the API
+    pub price: String,
+    /// Resting size as received from the API
+    pub size: String,
+}
+
+impl BookLevel {
+    /// Price as f64 (for display and arithmetic, not for level matching)
+    pub fn price_f64(&self) -> f64 {
+        self.price.parse().unwrap_or(0.0)
+    }
+
+    /// Size as f64
+    pub fn size_f64(&self) -> f64 {
+        self.size.parse().unwrap_or(0.0)
+    }
+}
+
+/// Parse a non-negative decimal string into a fixed-point key
+///
+/// Digits beyond `PRICE_KEY_DECIMALS` are truncated. Returns None for
+/// anything that is not a plain decimal number.
+fn price_key(s: &str) -> Option<u64> {
+    let s = s.trim();
+    let (int_part, frac_part) = match s.split_once('.') {
+        Some((i, f)) => (i, f),
+        None => (s, ""),
+    };
+
+    if int_part.is_empty() && frac_part.is_empty() {
+        return None;
+    }
+    if !int_part.bytes().all(|b| b.is_ascii_digit())
+        || !frac_part.bytes().all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+
+    let scale = 10u64.pow(PRICE_KEY_DECIMALS);
+    let int_value: u64 = if int_part.is_empty() { 0 } else { int_part.parse().ok()? };
+
+    let mut frac_value: u64 = 0;
+    for i in 0..PRICE_KEY_DECIMALS as usize {
+        let digit = frac_part.as_bytes().get(i).map(|b| (b - b'0') as u64).unwrap_or(0);
+        frac_value = frac_value * 10 + digit;
+    }
+
+    int_value.checked_mul(scale)?.checked_add(frac_value)
+}
+
+/// Check if a size string represents zero (level removal)
+fn is_zero_size(size: &str) -> bool {
+    price_key(size) == Some(0)
+}
+
+/// L2 order book for a single asset (token)
+#[derive(Clone, Debug)]
+pub struct OrderBook {
+    asset_id: String,
+    market: String,
+    timestamp: Option<String>,
+    hash: Option<String>,
+    bids: BTreeMap<u64, BookLevel>,
+    asks: BTreeMap<u64, BookLevel>,
+    /// Snapshot metadata, kept as sent (part of the hashed summary)
+    min_order_size: Option<Value>,
+    neg_risk: Option<Value>,
+    tick_size: Option<Value>,
+    diverged: bool,
+    stale: bool,
+}
+
+impl OrderBook {
+    /// Create an empty book for an asset
+    pub fn new(asset_id: &str) -> Self {
+        Self {
+            asset_id: asset_id.to_string(),
+            market: String::new(),
+            timestamp: None,
+            hash: None,
+            bids: BTreeMap::new(),
+            asks: BTreeMap::new(),
+            min_order_size: None,
+            neg_risk: None,
+            tick_size: None,
+            diverged: false,
+            stale: false,
+        }
+    }
+
+    /// Create a book seeded from a `book` snapshot
+    pub fn from_snapshot(msg: &BookMessage) -> Self {
+        let mut book = Self::new(&msg.asset_id);
+        book.apply_snapshot(msg);
+        book
+    }
+
+    /// Replace the full book contents with a `book` snapshot
+    pub fn apply_snapshot(&mut self, msg: &BookMessage) {
+        self.market = msg.market.clone();
+        self.timestamp = Some(msg.timestamp.clone());
+        self.hash = msg.hash.clone();
+        self.min_order_size = msg.extra.get("min_order_size").cloned();
+        self.neg_risk = msg.extra.get("neg_risk").cloned();
+        self.tick_size = msg.extra.get("tick_size").cloned();
+        self.diverged = false;
+        self.stale = false;
+        self.bids.clear();
+        self.asks.clear();
+
+        for level in &msg.bids {
+            self.set_level(BookSide::Bid, &level.price, &level.size);
+        }
+        for level in &msg.asks {
+            self.set_level(BookSide::Ask, &level.price, &level.size);
+        }
+    }
+
+    /// Apply a single `price_change` entry
+    ///
+    /// Returns false if the entry could not be applied (unknown side or bad price).
+    pub fn apply_price_change(&mut self, entry: &PriceChangeEntry, timestamp: &str) -> bool {
+        let side = match BookSide::from_order_side(&entry.side) {
+            Some(s) => s,
+            None => {
+                warn!("Unknown price_change side '{}' for {}", entry.side, entry.asset_id);
+                return false;
+            }
+        };
+
+        if !self.set_level(side, &entry.price, &entry.size) {
+            return false;
+        }
+
+        self.timestamp = Some(timestamp.to_string());
+        self.hash = entry.hash.clone();
+        true
+    }
+
+    /// Set (or remove, if size is zero) one price level
+    pub(crate) fn set_level(&mut self, side: BookSide, price: &str, size: &str) -> bool {
+        let key = match price_key(price) {
+            Some(k) => k,
+            None => {
+                warn!("Invalid price '{}' for {}", price, self.asset_id);
+                return false;
+            }
+        };
+
+        let levels = match side {
+            BookSide::Bid => &mut self.bids,
+            BookSide::Ask => &mut self.asks,
+        };
+
+        if is_zero_size(size) {
+            levels.remove(&key);
+        } else {
+            levels.insert(key, BookLevel { price: price.to_string(), size: size.to_string() });
+        }
+        true
+    }
+
+    /// Asset (token) identifier
+    pub fn asset_id(&self) -> &str {
+        &self.asset_id
+    }
+
+    /// Condition ID of the market this asset belongs to
+    pub fn market(&self) -> &str {
+        &self.market
+    }
+
+    /// Server timestamp (ms, as string) of the last applied update
+    pub fn timestamp(&self) -> Option<&str> {
+        self.timestamp.as_deref()
+    }
+
+    /// Server hash carried by the last applied update
+    pub fn hash(&self) -> Option<&str> {
+        self.hash.as_deref()
+    }
+
+    /// Highest bid
+    pub fn best_bid(&self) -> Option<&BookLevel> {
+        self.bids.values().next_back()
+    }
+
+    /// Lowest ask
+    pub fn best_ask(&self) -> Option<&BookLevel> {
+        self.asks.values().next()
+    }
+
+    /// Midpoint of best bid and best ask (None if either side is empty)
+    pub fn mid(&self) -> Option<f64> {
+        let bid = self.best_bid()?.price_f64();
+        let ask = self.best_ask()?.price_f64();
+        Some((bid + ask) / 2.0)
+    }
+
+    /// Best ask minus best bid (None if either side is empty)
+    pub fn spread(&self) -> Option<f64> {
+        Some(self.best_ask()?.price_f64() - self.best_bid()?.price_f64())
+    }
+
+    /// Top `n` levels of a side, best price first
+    pub fn depth(&self, side: BookSide, n: usize) -> Vec<&BookLevel> {
+        match side {
+            BookSide::Bid => self.bids.values().rev().take(n).collect(),
+            BookSide::Ask => self.asks.values().take(n).collect(),
+        }
+    }
+
+    /// Number of price levels on a side
+    pub fn level_count(&self, side: BookSide) -> usize {
+        match side {
+            BookSide::Bid => self.bids.len(),
+            BookSide::Ask => self.asks.len(),
+        }
+    }
+
+    /// Total resting size on a side
+    pub fn total_size(&self, side: BookSide) -> f64 {
+        let levels = match side {
+            BookSide::Bid => &self.bids,
+            BookSide::Ask => &self.asks,
+        };
+        levels.values().map(BookLevel::size_f64).sum()
+    }
+
+    /// Check if both sides are empty
+    pub fn is_empty(&self) -> bool {
+        self.bids.is_empty() && self.asks.is_empty()
+    }
+
+    /// Check if the book failed hash verification since the last snapshot
+    pub fn is_diverged(&self) -> bool {
+        self.diverged
+    }
+
+    /// Check if the feed disconnected since the last snapshot
+    pub fn is_stale(&self) -> bool {
+        self.stale
+    }
+
+    /// Compute the book hash the way the server does
+    ///
+    /// SHA-1 (hex) of `summary_json`, as in `generate_orderbook_summary_hash`
+    /// of the official py-clob-client.
+    pub fn compute_hash(&self) -> String {
+        let digest = Sha1::digest(self.summary_json().as_bytes());
+        digest.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// Compact JSON of the book as py-clob-client's `OrderBookSummary`
+    ///
+    /// Fields in dataclass order (market, asset_id, timestamp, bids, asks,
+    /// min_order_size, neg_risk, tick_size, hash) with an empty `hash`; bids
+    /// ascending and asks descending (best level last); missing metadata is null.
+    fn summary_json(&self) -> String {
+        fn push_levels<'a>(out: &mut String, levels: impl Iterator<Item = &'a BookLevel>) {
+            out.push('[');
+            for (i, level) in levels.enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&format!(
+                    "{{\"price\":{},\"size\":{}}}",
+                    json_str(&level.price),
+                    json_str(&level.size)
+                ));
+            }
+            out.push(']');
+        }
+        fn json_value(value: &Option<Value>) -> String {
+            value.as_ref().map(Value::to_string).unwrap_or_else(|| "null".to_string())
+        }
+
+        let mut summary = format!(
+            "{{\"market\":{},\"asset_id\":{},\"timestamp\":{},\"bids\":",
+            json_str(&self.market),
+            json_str(&self.asset_id),
+            json_str(self.timestamp.as_deref().unwrap_or(""))
+        );
+        push_levels(&mut summary, self.bids.values());
+        summary.push_str(",\"asks\":");
+        push_levels(&mut summary, self.asks.values().rev());
+        summary.push_str(&format!(
+            ",\"min_order_size\":{},\"neg_risk\":{},\"tick_size\":{},\"hash\":\"\"}}",
+            json_value(&self.min_order_size),
+            json_value(&self.neg_risk),
+            json_value(&self.tick_size)
+        ));
+        summary
+    }
+
+    /// Compare the server hash against the local book, marking divergence
+    ///
+    /// Returns true if the hash matched (or no server hash was given).
+    fn verify_hash(&mut self, server_hash: Option<&str>) -> bool {
+        let server_hash = match server_hash {
+            Some(h) if !h.is_empty() => h,
+            _ => return true,
+        };
+
+        let local_hash = self.compute_hash();
+        if local_hash != server_hash {
+            warn!(
+                "Book hash mismatch for {}: local={} server={}",
+                self.asset_id, local_hash, server_hash
+            );
+            self.diverged = true;
+            return false;
+        }
+        true
+    }
+}
+
+/// Serialize a string as a JSON string literal
+fn json_str(s: &str) -> String {
+    serde_json::to_string(s).unwrap_or_else(|_| "\"\"".to_string())
+}
+
+/// Result of applying a message to `OrderBooks`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BookUpdate {
+    /// Assets whose book changed
+    pub updated: Vec<String>,
+    /// Assets that failed hash verification (need a fresh snapshot)
+    pub diverged: Vec<String>,
+    /// Assets re-seeded from a REST snapshot since the last call (set by `BookKeeper` only)
+    pub resynced: Vec<String>,
+    /// Assets marked stale by a disconnect (valid again after the next snapshot)
+    pub invalidated: Vec<String>,
+}
+
+/// Order books for all subscribed assets, keyed by asset_id
+///
+/// Books are only created from a snapshot; `price_change` entries for an
+/// asset without a snapshot are ignored, since the book would be incomplete.
+#[derive(Clone, Debug, Default)]
+pub struct OrderBooks {
+    books: HashMap<String, OrderBook>,
+    verify_hashes: bool,
+}
+
+impl OrderBooks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enable or disable hash verification after `price_change` updates
+    ///
+    /// Experimental: see "Hash Verification" in the module docs.
+    pub fn set_verify_hashes(&mut self, enable: bool) {
+        self.verify_hashes = enable;
+    }
+
+    /// Apply an inbound market channel message
+    ///
+    /// Messages that don't affect the book (trades, tick size, user channel,
+    /// unknown, lifecycle events other than `Disconnected`) return an empty update.
+    pub fn apply(&mut self, msg: &WsInboundMessage) -> BookUpdate {
+        let mut update = BookUpdate::default();
+
+        match msg {
+            WsInboundMessage::Market(MarketMessage::Book(book)) => {
+                self.apply_snapshot(book);
+                update.updated.push(book.asset_id.clone());
+            }
+            WsInboundMessage::Market(MarketMessage::PriceChange(change)) => {
+                // Server hash of the last entry per asset reflects the book after the message
+                let mut last_hash: HashMap<&str, Option<&str>> = HashMap::new();
+
+                for entry in &change.price_changes {
+                    let book = match self.books.get_mut(&entry.asset_id) {
+                        Some(b) => b,
+                        None => {
+                            debug!("price_change for {} before snapshot, ignoring", entry.asset_id);
+                            continue;
+                        }
+                    };
+                    if book.apply_price_change(entry, &change.timestamp) {
+                        if !update.updated.contains(&entry.asset_id) {
+                            update.updated.push(entry.asset_id.clone());
+                        }
+                        last_hash.insert(&entry.asset_id, entry.hash.as_deref());
+                    }
+                }
+
+                if self.verify_hashes {
+                    for asset_id in &update.updated {
+                        let server_hash = last_hash.get(asset_id.as_str()).copied().flatten();
+                        if let Some(book) = self.books.get_mut(asset_id) {
+                            if !book.verify_hash(server_hash) {
+                                update.diverged.push(asset_id.clone());
+                            }
+                        }
+                    }
+                }
+            }
+            WsInboundMessage::Market(MarketMessage::TickSizeChange(change)) => {
+                if let Some(book) = self.books.get_mut(&change.asset_id) {
+                    book.tick_size = Some(Value::String(change.new_tick_size.clone()));
+                }
+            }
+            WsInboundMessage::SnapshotArray(items) => {
+                for item in items {
+                    match serde_json::from_value::<BookMessage>(item.clone()) {
+                        Ok(book) => {
+                            self.apply_snapshot(&book);
+                            update.updated.push(book.asset_id);
+                        }
+                        Err(e) => {
+                            debug!("Skipping non-book snapshot item: {}", e);
+                        }
+                    }
+                }
+            }
+            WsInboundMessage::Lifecycle(LifecycleEvent::Disconnected { ids, reason, .. }) => {
+                for asset_id in ids {
+                    if let Some(book) = self.books.get_mut(asset_id).filter(|b| !b.stale) {
+                        book.stale = true;
+                        update.invalidated.push(asset_id.clone());
+                    }
+                }
+                if !update.invalidated.is_empty() {
+                    debug!(
+                        "Feed disconnected ({}), {} books stale until resync",
+                        reason,
+                        update.invalidated.len()
+                    );
+                }
+            }
+            _ => {}
+        }
+
+        update
+    }
+
+    /// Seed or replace one asset's book from a snapshot
+    pub fn apply_snapshot(&mut self, msg: &BookMessage) {
+        self.books
+            .entry(msg.asset_id.clone())
+            .or_insert_with(|| OrderBook::new(&msg.asset_id))
+            .apply_snapshot(msg);
+    }
+
+    /// Get the book for an asset
+    pub fn get(&self, asset_id: &str) -> Option<&OrderBook> {
+        self.books.get(asset_id)
+    }
+
+    /// Mutable book for an asset (local simulation)
+    pub(crate) fn get_mut(&mut self, asset_id: &str) -> Option<&mut OrderBook> {
+        self.books.get_mut(asset_id)
+    }
+
+    /// Get the book for an asset only if it has neither diverged nor gone stale
+    pub fn get_verified(&self, asset_id: &str) -> Option<&OrderBook> {
+        self.books.get(asset_id).filter(|b| !b.is_diverged() && !b.is_stale())
+    }
+
+    /// Asset IDs currently marked as diverged
+    pub fn diverged(&self) -> Vec<String> {
+        self.books.values().filter(|b| b.is_diverged()).map(|b| b.asset_id.clone()).collect()
+    }
+
+    /// Drop the book for an asset (e.g. after unsubscribe)
+    pub fn remove(&mut self, asset_id: &str) -> Option<OrderBook> {
+        self.books.remove(asset_id)
+    }
+
+    /// Asset IDs with a seeded book
+    pub fn asset_ids(&self) -> impl Iterator<Item = &str> {
+        self.books.keys().map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.books.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.books.is_empty()
+    }
+}
+
+/// Minimum interval between REST resyncs of the same asset
+#[cfg(feature = "httpws")]
+const DEFAULT_MIN_RESYNC_INTERVAL_SECS: u64 = 5;
+
+/// BookKeeper configuration
+#[cfg(feature = "httpws")]
+#[derive(Clone, Debug)]
+pub struct BookKeeperConfig {
+    /// Verify server hashes after each `price_change` (default: false; experimental,
+    /// the summary layout is not yet checked against a captured server hash)
+    pub verify_hash: bool,
+    /// Minimum interval between REST resyncs of the same asset (default: 5s)
+    pub min_resync_interval: Duration,
+}
+
+#[cfg(feature = "httpws")]
+impl Default for BookKeeperConfig {
+    fn default() -> Self {
+        Self {
+            verify_hash: false,
+            min_resync_interval: Duration::from_secs(DEFAULT_MIN_RESYNC_INTERVAL_SECS),
+        }
+    }
+}
+
+/// REST snapshot fetched by a background resync
+#[cfg(feature = "httpws")]
+type ResyncResult = (String, AdapterResult<BookMessage>);
+
+/// `price_change` entry received while a resync was in flight, with its message timestamp
+#[cfg(feature = "httpws")]
+type BufferedChange = (PriceChangeEntry, String);
+
+/// Order books with REST resync, optionally driven by (experimental) hash verification
+///
+/// Diverged assets are re-seeded from `GET /book`; until that succeeds,
+/// `get_verified` returns None so callers never act on a corrupted book.
+///
+/// `apply` never waits for REST: it starts one fetch task per diverged asset
+/// and applies finished snapshots on later calls. A caller whose stream may go
+/// quiet should also drive `next_resync` (e.g. in its `select!`).
+///
+/// `price_change` entries for an asset with a fetch in flight are buffered;
+/// those at or after the REST snapshot's timestamp are replayed on top of it.
+/// A snapshot older than a book re-seeded from the feed in the meantime is dropped.
+#[cfg(feature = "httpws")]
+pub struct BookKeeper {
+    books: OrderBooks,
+    rest: RestClient,
+    config: BookKeeperConfig,
+    last_resync: HashMap<String, Instant>,
+    in_flight: HashSet<String>,
+    buffered: HashMap<String, Vec<BufferedChange>>,
+    resync_tx: mpsc::UnboundedSender<ResyncResult>,
+    resync_rx: mpsc::UnboundedReceiver<ResyncResult>,
+    resync_count: u64,
+}
+
+#[cfg(feature = "httpws")]
+impl BookKeeper {
+    /// Create a book keeper with default configuration
+    pub fn new(rest: RestClient) -> Self {
+        Self::with_config(rest, BookKeeperConfig::default())
+    }
+
+    /// Create with custom configuration
+    pub fn with_config(rest: RestClient, config: BookKeeperConfig) -> Self {
+        let mut books = OrderBooks::new();
+        books.set_verify_hashes(config.verify_hash);
+        let (resync_tx, resync_rx) = mpsc::unbounded_channel();
+        Self {
+            books,
+            rest,
+            config,
+            last_resync: HashMap::new(),
+            in_flight: HashSet::new(),
+            buffered: HashMap::new(),
+            resync_tx,
+            resync_rx,
+            resync_count: 0,
+        }
+    }
+
+    /// Apply finished resyncs, then the message; start a resync for any asset that diverged
+    ///
+    /// Must be called inside a Tokio runtime.
+    pub fn apply(&mut self, msg: &WsInboundMessage) -> BookUpdate {
+        let resynced = self.poll_resyncs();
+        self.buffer_in_flight(msg);
+        let mut update = self.books.apply(msg);
+        update.resynced = resynced;
+
+        for asset_id in &update.diverged {
+            self.request_resync(asset_id);
+        }
+        update
+    }
+
+    /// Start resyncs for assets that are still diverged (e.g. after a failed REST call)
+    ///
+    /// Returns the assets whose fetch was started.
+    pub fn resync_diverged(&mut self) -> Vec<String> {
+        self.books.diverged().into_iter().filter(|id| self.request_resync(id)).collect()
+    }
+
+    /// Apply every snapshot fetched so far, returning the resynced assets
+    pub fn poll_resyncs(&mut self) -> Vec<String> {
+        let mut resynced = Vec::new();
+        while let Ok(result) = self.resync_rx.try_recv() {
+            if let Some(asset_id) = self.finish_resync(result) {
+                resynced.push(asset_id);
+            }
+        }
+        resynced
+    }
+
+    /// Wait for the next background fetch and apply it
+    ///
+    /// Returns the asset if its book was re-seeded, None if the fetch failed.
+    /// Never resolves while no fetch is in flight.
+    pub async fn next_resync(&mut self) -> Option<String> {
+        if self.in_flight.is_empty() {
+            return std::future::pending().await;
+        }
+        // The keeper holds a sender, so the channel never closes
+        let result = self.resync_rx.recv().await?;
+        self.finish_resync(result)
+    }
+
+    /// Re-seed one asset from a REST snapshot, waiting for the response
+    ///
+    /// Returns Ok(false) if skipped because the asset was resynced too recently.
+    pub async fn resync(&mut self, asset_id: &str) -> AdapterResult<bool> {
+        if !self.resync_allowed(asset_id) {
+            return Ok(false);
+        }
+
+        info!("Resyncing book for {} from REST snapshot", asset_id);
+        let snapshot = self.rest.get_book(asset_id).await?;
+
+        self.books.apply_snapshot(&snapshot);
+        self.resync_count += 1;
+        Ok(true)
+    }
+
+    /// Underlying books
+    pub fn books(&self) -> &OrderBooks {
+        &self.books
+    }
+
+    /// Get the book for an asset only if it has neither diverged nor gone stale
+    pub fn get_verified(&self, asset_id: &str) -> Option<&OrderBook> {
+        self.books.get_verified(asset_id)
+    }
+
+    /// Total successful resyncs
+    pub fn resync_count(&self) -> u64 {
+        self.resync_count
+    }
+
+    /// Assets with a REST fetch in flight
+    pub fn resyncs_in_flight(&self) -> usize {
+        self.in_flight.len()
+    }
+
+    /// Spawn a REST fetch unless one is in flight or the asset is rate limited
+    fn request_resync(&mut self, asset_id: &str) -> bool {
+        if self.in_flight.contains(asset_id) || !self.resync_allowed(asset_id) {
+            return false;
+        }
+        self.in_flight.insert(asset_id.to_string());
+
+        info!("Resyncing book for {} from REST snapshot", asset_id);
+        let rest = self.rest.clone();
+        let tx = self.resync_tx.clone();
+        let asset_id = asset_id.to_string();
+        tokio::spawn(async move {
+            let result = rest.get_book(&asset_id).await;
+            let _ = tx.send((asset_id, result));
+        });
+        true
+    }
+
+    /// Check and record the per-asset rate limit
+    fn resync_allowed(&mut self, asset_id: &str) -> bool {
+        if let Some(last) = self.last_resync.get(asset_id) {
+            if last.elapsed() < self.config.min_resync_interval {
+                debug!("Resync for {} skipped (rate limited)", asset_id);
+                return false;
+            }
+        }
+        self.last_resync.insert(asset_id.to_string(), Instant::now());
+        true
+    }
+
+    /// Keep `price_change` entries for assets whose REST fetch is in flight
+    fn buffer_in_flight(&mut self, msg: &WsInboundMessage) {
+        let WsInboundMessage::Market(MarketMessage::PriceChange(change)) = msg else {
+            return;
+        };
+        for entry in change.price_changes.iter().filter(|e| self.in_flight.contains(&e.asset_id)) {
+            self.buffered
+                .entry(entry.asset_id.clone())
+                .or_default()
+                .push((entry.clone(), change.timestamp.clone()));
+        }
+    }
+
+    /// Apply a fetched snapshot plus the changes buffered while it was in flight
+    ///
+    /// Returns the asset if its book is verified afterwards.
+    fn finish_resync(&mut self, (asset_id, result): ResyncResult) -> Option<String> {
+        self.in_flight.remove(&asset_id);
+        let buffered = self.buffered.remove(&asset_id).unwrap_or_default();
+        let snapshot = match result {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                warn!("Resync failed for {}: {}", asset_id, e);
+                return None;
+            }
+        };
+
+        // A feed snapshot since the divergence may already be newer than REST
+        let snapshot_ms = timestamp_ms(&snapshot.timestamp);
+        let live_ms =
+            self.books.get_verified(&asset_id).and_then(|b| b.timestamp()).and_then(timestamp_ms);
+        if let (Some(live), Some(rest)) = (live_ms, snapshot_ms) {
+            if live >= rest {
+                debug!("REST snapshot for {} is older than the live book, dropped", asset_id);
+                return None;
+            }
+        }
+
+        self.books.apply_snapshot(&snapshot);
+        self.resync_count += 1;
+
+        // Sizes are absolute, so replaying a change already in the snapshot is harmless
+        let book = self.books.get_mut(&asset_id)?;
+        let mut last_hash = None;
+        for (entry, timestamp) in &buffered {
+            let newer = match (timestamp_ms(timestamp), snapshot_ms) {
+                (Some(t), Some(s)) => t >= s,
+                _ => true,
+            };
+            if newer && book.apply_price_change(entry, timestamp) {
+                last_hash = Some(entry.hash.as_deref());
+            }
+        }
+        if let Some(server_hash) = last_hash.filter(|_| self.config.verify_hash) {
+            book.verify_hash(server_hash);
+        }
+
+        self.books.get_verified(&asset_id).map(|_| asset_id)
+    }
+}
+
+/// Parse a millisecond timestamp string
+#[cfg(feature = "httpws")]
+fn timestamp_ms(s: &str) -> Option<u64> {
+    s.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn book_json(asset_id: &str) -> String {
+        format!(
+            r#"{{
+                "event_type": "book",
+                "asset_id": "{}",
+                "market": "condition456",
+                "timestamp": "1704067200000",
+                "hash": "abc123",
+                "bids": [{{"price": "0.48", "size": "30"}}, {{"price": "0.50", "size": "100"}}],
+                "asks": [{{"price": "0.53", "size": "10"}}, {{"price": "0.51", "size": "200"}}]
+            }}"#,
+            asset_id
+        )
+    }
+
+    fn price_change_json(asset_id: &str, price: &str, size: &str, side: &str) -> String {
+        format!(
+            r#"{{
+                "event_type": "price_change",
+                "market": "condition456",
+                "timestamp": "1704067201000",
+                "price_changes": [
+                    {{"asset_id": "{}", "price": "{}", "size": "{}", "side": "{}", "hash": "def456"}}
+                ]
+            }}"#,
+            asset_id, price, size, side
+        )
+    }
+
+    #[test]
+    fn test_price_key() {
+        assert_eq!(price_key("0.5"), price_key("0.50"));
+        assert_eq!(price_key("0.5"), Some(500_000));
+        assert_eq!(price_key("1"), Some(1_000_000));
+        assert_eq!(price_key(".01"), Some(10_000));
+        assert_eq!(price_key("0"), Some(0));
+        assert_eq!(price_key("abc"), None);
+        assert_eq!(price_key("-0.5"), None);
+        assert_eq!(price_key(""), None);
+    }
+
+    #[test]
+    fn test_seed_from_book_message() {
+        let mut books = OrderBooks::new();
+        let update = books.apply(&WsInboundMessage::parse(&book_json("token123")));
+        assert_eq!(update.updated, vec!["token123"]);
+
+        let book = books.get("token123").unwrap();
+        assert_eq!(book.best_bid().unwrap().price, "0.50");
+        assert_eq!(book.best_ask().unwrap().price, "0.51");
+        assert_eq!(book.level_count(BookSide::Bid), 2);
+        assert_eq!(book.total_size(BookSide::Ask), 210.0);
+        assert_eq!(book.hash(), Some("abc123"));
+    }
+
+    #[test]
+    fn test_depth_ordering() {
+        let mut books = OrderBooks::new();
+        books.apply(&WsInboundMessage::parse(&book_json("token123")));
+        let book = books.get("token123").unwrap();
+
+        let bids: Vec<&str> =
+            book.depth(BookSide::Bid, 5).iter().map(|l| l.price.as_str()).collect();
+        let asks: Vec<&str> =
+            book.depth(BookSide::Ask, 1).iter().map(|l| l.price.as_str()).collect();
+        assert_eq!(bids, vec!["0.50", "0.48"]);
+        assert_eq!(asks, vec!["0.51"]);
+    }
+
+    #[test]
+    fn test_price_change_update_and_remove() {
+        let mut books = OrderBooks::new();
+        books.apply(&WsInboundMessage::parse(&book_json("token123")));
+
+        // New best bid
+        books.apply(&WsInboundMessage::parse(&price_change_json("token123", "0.505", "5", "BUY")));
+        assert_eq!(books.get("token123").unwrap().best_bid().unwrap().price, "0.505");
+
+        // Remove best ask using a differently formatted price string
+        let update = books
+            .apply(&WsInboundMessage::parse(&price_change_json("token123", "0.510", "0", "SELL")));
+        assert_eq!(update.updated, vec!["token123"]);
+        let book = books.get("token123").unwrap();
+        assert_eq!(book.best_ask().unwrap().price, "0.53");
+        assert_eq!(book.timestamp(), Some("1704067201000"));
+        assert_eq!(book.hash(), Some("def456"));
+    }
+
+    #[test]
+    fn test_price_change_before_snapshot_ignored() {
+        let mut books = OrderBooks::new();
+        let update = books
+            .apply(&WsInboundMessage::parse(&price_change_json("token123", "0.5", "5", "BUY")));
+        assert!(update.updated.is_empty());
+        assert!(books.get("token123").is_none());
+    }
+
+    #[test]
+    fn test_seed_from_snapshot_array() {
+        let json = format!("[{},{}]", book_json("token-a"), book_json("token-b"));
+        let msg = WsInboundMessage::parse(&json);
+        assert!(msg.is_snapshot_array());
+
+        let mut books = OrderBooks::new();
+        let update = books.apply(&msg);
+        assert_eq!(update.updated, vec!["token-a", "token-b"]);
+        assert_eq!(books.len(), 2);
+        let mid = books.get("token-b").unwrap().mid().unwrap();
+        assert!((mid - 0.505).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_disconnect_marks_books_stale_until_snapshot() {
+        let mut books = OrderBooks::new();
+        books.apply(&WsInboundMessage::parse(&book_json("token-a")));
+        books.apply(&WsInboundMessage::parse(&book_json("token-b")));
+        books.apply(&WsInboundMessage::parse(&book_json("token-c")));
+
+        // "token-c" lives on another connection; "unknown" has no book
+        let disconnected = WsInboundMessage::Lifecycle(LifecycleEvent::Disconnected {
+            conn_id: 1,
+            ids: vec!["token-a".to_string(), "token-b".to_string(), "unknown".to_string()],
+            reason: "stream ended".to_string(),
+        });
+        let update = books.apply(&disconnected);
+        assert_eq!(update.invalidated, vec!["token-a", "token-b"]);
+        assert!(books.get_verified("token-c").is_some());
+        assert!(update.diverged.is_empty());
+        assert!(books.get("token-a").unwrap().is_stale());
+        assert!(books.get_verified("token-a").is_none());
+
+        // Already stale: a second disconnect reports nothing new
+        assert!(books.apply(&disconnected).invalidated.is_empty());
+
+        books.apply(&WsInboundMessage::parse(&book_json("token-a")));
+        assert!(books.get_verified("token-a").is_some());
+        assert!(books.get_verified("token-b").is_none());
+    }
+
+    #[test]
+    fn test_summary_matches_py_clob_client_layout() {
+        // Expected values from py-clob-client's OrderBookSummary (asdict + compact json.dumps)
+        let json = book_json("token123").replacen(
+            "\"hash\": \"abc123\",",
+            "\"hash\": \"abc123\", \"min_order_size\": \"5\", \"neg_risk\": false, \"tick_size\": \"0.01\",",
+            1,
+        );
+        let mut books = OrderBooks::new();
+        books.apply(&WsInboundMessage::parse(&json));
+        let book = books.get("token123").unwrap();
+        assert_eq!(
+            book.summary_json(),
+            r#"{"market":"condition456","asset_id":"token123","timestamp":"1704067200000","bids":[{"price":"0.48","size":"30"},{"price":"0.50","size":"100"}],"asks":[{"price":"0.53","size":"10"},{"price":"0.51","size":"200"}],"min_order_size":"5","neg_risk":false,"tick_size":"0.01","hash":""}"#
+        );
+        assert_eq!(book.compute_hash(), "e3a8df78f12bbb98aae7abd6c0bf1a340471c512");
+
+        // WS snapshots without metadata hash it as null
+        books.apply(&WsInboundMessage::parse(&book_json("token123")));
+        assert_eq!(
+            books.get("token123").unwrap().compute_hash(),
+            "6fb642b7898525603aa22a80175ee8b6b4f318ee"
+        );
+    }
+
+    #[test]
+    fn test_hash_mismatch_marks_diverged() {
+        let mut books = OrderBooks::new();
+        books.set_verify_hashes(true);
+        books.apply(&WsInboundMessage::parse(&book_json("token123")));
+
+        // "def456" is not the hash of the updated book
+        let update = books
+            .apply(&WsInboundMessage::parse(&price_change_json("token123", "0.49", "7", "BUY")));
+        assert_eq!(update.diverged, vec!["token123"]);
+        assert!(books.get("token123").unwrap().is_diverged());
+        assert!(books.get_verified("token123").is_none());
+        assert_eq!(books.diverged(), vec!["token123"]);
+
+        // A fresh snapshot clears divergence
+        books.apply(&WsInboundMessage::parse(&book_json("token123")));
+        assert!(books.get_verified("token123").is_some());
+    }
+
+    #[test]
+    fn test_hash_match_keeps_book_verified() {
+        let mut expected = OrderBooks::new();
+        expected.apply(&WsInboundMessage::parse(&book_json("token123")));
+        expected
+            .apply(&WsInboundMessage::parse(&price_change_json("token123", "0.49", "7", "BUY")));
+        let server_hash = expected.get("token123").unwrap().compute_hash();
+
+        let mut books = OrderBooks::new();
+        books.set_verify_hashes(true);
+        books.apply(&WsInboundMessage::parse(&book_json("token123")));
+        let json =
+            price_change_json("token123", "0.49", "7", "BUY").replace("def456", &server_hash);
+        let update = books.apply(&WsInboundMessage::parse(&json));
+
+        assert!(update.diverged.is_empty());
+        assert!(books.get_verified("token123").is_some());
+    }
+}
+
+/// Wiremock integration tests for BookKeeper resync
+#[cfg(all(test, feature = "httpws"))]
+mod wiremock_tests {
+    use super::*;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    /// Test: hash mismatch triggers a REST resync that restores the book
+    #[tokio::test]
+    async fn test_divergence_triggers_rest_resync() {
+        let clob_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/book"))
+            .and(query_param("token_id", "token123"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "market": "condition456",
+                "asset_id": "token123",
+                "timestamp": "1704067205000",
+                "hash": "rest-hash",
+                "bids": [{"price": "0.47", "size": "10"}],
+                "asks": [{"price": "0.52", "size": "20"}],
+                "min_order_size": "5",
+                "tick_size": "0.01",
+                "neg_risk": false
+            })))
+            .expect(1)
+            .mount(&clob_server)
+            .await;
+
+        let rest = RestClient::with_base_url(&clob_server.uri()).unwrap();
+        let config = BookKeeperConfig { verify_hash: true, ..Default::default() };
+        let mut keeper = BookKeeper::with_config(rest, config);
+
+        let snapshot = WsInboundMessage::parse(
+            r#"{"event_type": "book", "asset_id": "token123", "market": "condition456",
+                "timestamp": "1704067200000", "bids": [{"price": "0.50", "size": "100"}],
+                "asks": [{"price": "0.51", "size": "200"}]}"#,
+        );
+        keeper.apply(&snapshot);
+
+        let bad_change = WsInboundMessage::parse(
+            r#"{"event_type": "price_change", "market": "condition456", "timestamp": "1704067201000",
+                "price_changes": [{"asset_id": "token123", "price": "0.49", "size": "7",
+                                   "side": "BUY", "hash": "not-the-hash"}]}"#,
+        );
+        let update = keeper.apply(&bad_change);
+
+        // The fetch runs in the background; apply returns at once
+        assert_eq!(update.diverged, vec!["token123"]);
+        assert!(update.resynced.is_empty());
+        assert_eq!(keeper.resyncs_in_flight(), 1);
+        assert!(keeper.get_verified("token123").is_none());
+
+        assert_eq!(keeper.next_resync().await.as_deref(), Some("token123"));
+        assert_eq!(keeper.resync_count(), 1);
+        assert_eq!(keeper.resyncs_in_flight(), 0);
+
+        let book = keeper.get_verified("token123").expect("book should be verified after resync");
+        assert_eq!(book.best_bid().unwrap().price, "0.47");
+        assert_eq!(book.timestamp(), Some("1704067205000"));
+
+        // Second divergence within the rate limit window is not resynced
+        let update = keeper.apply(&bad_change);
+        assert_eq!(update.diverged, vec!["token123"]);
+        assert_eq!(keeper.resyncs_in_flight(), 0);
+        assert!(keeper.resync_diverged().is_empty());
+        assert!(keeper.get_verified("token123").is_none());
+    }
+
+    /// Keeper with a diverged `token123` whose REST fetch (snapshot at 1704067205000,
+    /// bid 0.47 x 10) is delayed so the test can feed messages while it is in flight
+    async fn keeper_with_slow_resync(clob_server: &MockServer) -> BookKeeper {
+        Mock::given(method("GET"))
+            .and(path("/book"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({
+                        "market": "condition456",
+                        "asset_id": "token123",
+                        "timestamp": "1704067205000",
+                        "bids": [{"price": "0.47", "size": "10"}],
+                        "asks": [{"price": "0.52", "size": "20"}]
+                    }))
+                    .set_delay(Duration::from_millis(200)),
+            )
+            .mount(clob_server)
+            .await;
+
+        let rest = RestClient::with_base_url(&clob_server.uri()).unwrap();
+        let config = BookKeeperConfig { verify_hash: true, ..Default::default() };
+        let mut keeper = BookKeeper::with_config(rest, config);
+        keeper.apply(&WsInboundMessage::parse(
+            r#"{"event_type": "book", "asset_id": "token123", "market": "condition456",
+                "timestamp": "1704067200000", "bids": [{"price": "0.50", "size": "100"}],
+                "asks": [{"price": "0.51", "size": "200"}]}"#,
+        ));
+        let update = keeper.apply(&WsInboundMessage::parse(
+            r#"{"event_type": "price_change", "market": "condition456", "timestamp": "1704067201000",
+                "price_changes": [{"asset_id": "token123", "price": "0.49", "size": "7",
+                                   "side": "BUY", "hash": "not-the-hash"}]}"#,
+        ));
+        assert_eq!(update.diverged, vec!["token123"]);
+        assert_eq!(keeper.resyncs_in_flight(), 1);
+        keeper
+    }
+
+    fn change_at(timestamp: &str, price: &str, size: &str) -> WsInboundMessage {
+        WsInboundMessage::parse(&format!(
+            r#"{{"event_type": "price_change", "market": "condition456", "timestamp": "{}",
+                "price_changes": [{{"asset_id": "token123", "price": "{}", "size": "{}",
+                                    "side": "BUY"}}]}}"#,
+            timestamp, price, size
+        ))
+    }
+
+    /// Test: price_change entries received during the fetch are replayed if newer
+    #[tokio::test]
+    async fn test_price_change_during_resync_is_replayed() {
+        let clob_server = MockServer::start().await;
+        let mut keeper = keeper_with_slow_resync(&clob_server).await;
+
+        // Older than the REST snapshot: already reflected in it, not replayed
+        keeper.apply(&change_at("1704067203000", "0.47", "99"));
+        // Newer than the REST snapshot: must survive the resync
+        keeper.apply(&change_at("1704067206000", "0.48", "5"));
+
+        assert_eq!(keeper.next_resync().await.as_deref(), Some("token123"));
+        let book = keeper.get_verified("token123").expect("book should be verified after resync");
+        assert_eq!(book.best_bid().unwrap().price, "0.48");
+        assert_eq!(book.best_bid().unwrap().size, "5");
+        let bids = book.depth(BookSide::Bid, 2);
+        assert_eq!(bids[1].price, "0.47");
+        assert_eq!(bids[1].size, "10");
+        assert_eq!(book.timestamp(), Some("1704067206000"));
+    }
+
+    /// Test: a REST snapshot older than a feed snapshot received meanwhile is dropped
+    #[tokio::test]
+    async fn test_stale_rest_snapshot_is_dropped() {
+        let clob_server = MockServer::start().await;
+        let mut keeper = keeper_with_slow_resync(&clob_server).await;
+
+        keeper.apply(&WsInboundMessage::parse(
+            r#"{"event_type": "book", "asset_id": "token123", "market": "condition456",
+                "timestamp": "1704067207000", "bids": [{"price": "0.46", "size": "1"}],
+                "asks": []}"#,
+        ));
+
+        assert_eq!(keeper.next_resync().await, None);
+        assert_eq!(keeper.resync_count(), 0);
+        let book = keeper.get_verified("token123").unwrap();
+        assert_eq!(book.best_bid().unwrap().price, "0.46");
+        assert_eq!(book.timestamp(), Some("1704067207000"));
+    }
+}
@@ -0,0 +1,234 @@
+//! Structured adapter errors
+//!
+//! # Design
+//! - `RestClient`, `GammaClient`, every `ClobBackend` and the WebSocket clients
+//!   return `AdapterError`
+//! - Callers decide on retry/freeze by matching variants, never by error text
+//! - Higher layers that use `anyhow` can still recover the variant with
+//!   `err.downcast_ref::<AdapterError>()`
+//!
+//! # Status mapping
+//! - 401 / 403 → `Auth`
+//! - 404 → `NotFound`
+//! - 429 → `RateLimited` (with `Retry-After` when present)
+//! - any other non-2xx → `Http`
+
+use std::time::Duration;
+
+/// Result alias for adapter operations
+pub type AdapterResult<T> = std::result::Result<T, AdapterError>;
+
+/// Error returned by the REST, Gamma and WebSocket clients
+#[derive(Debug, thiserror::Error)]
+pub enum AdapterError {
+    /// Non-success HTTP status not covered by a more specific variant
+    #[error("HTTP {status} for {url}: {body}")]
+    Http { status: u16, url: String, body: String },
+
+    /// Request or read timed out
+    #[error("Timed out: {0}")]
+    Timeout(String),
+
+    /// Could not connect or the transport failed mid-request
+    #[error("Connection failed: {0}")]
+    Connection(String),
+
+    /// JSON did not match the expected shape (or could not be encoded)
+    #[error("Invalid JSON for {context}: {source}")]
+    Decode {
+        context: String,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    /// Response was valid JSON but lacked the field the endpoint exists to return
+    #[error("Response missing '{0}' field")]
+    MissingField(&'static str),
+
+    /// Resource does not exist (HTTP 404)
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    /// Server asked us to slow down (HTTP 429)
+    #[error("Rate limited: {url} (retry after {retry_after:?})")]
+    RateLimited { url: String, retry_after: Option<Duration> },
+
+    /// WebSocket connection closed
+    #[error("WebSocket closed: {0}")]
+    WsClosed(String),
+
+    /// Credentials rejected or missing (HTTP 401/403)
+    #[error("Authentication failed: {0}")]
+    Auth(String),
+
+    /// Order failed local validation (price, size, tick size)
+    #[error("Invalid order: {0}")]
+    InvalidOrder(String),
+
+    /// Exchange refused the order
+    #[error("Order rejected: {0}")]
+    OrderRejected(String),
+
+    /// Blocked by a pre-trade risk check (nothing was sent)
+    #[error("Risk check failed: {0}")]
+    RiskRejected(#[from] crate::risk::RiskViolation),
+
+    /// Local I/O (recording files)
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl AdapterError {
+    /// Decode error with context (usually the request path)
+    pub fn decode(context: impl Into<String>, source: serde_json::Error) -> Self {
+        AdapterError::Decode { context: context.into(), source }
+    }
+
+    /// Map a non-success HTTP status to the matching variant
+    pub fn from_status(
+        status: u16,
+        url: &str,
+        body: String,
+        retry_after: Option<Duration>,
+    ) -> Self {
+        match status {
+            401 | 403 => AdapterError::Auth(format!("HTTP {} for {}: {}", status, url, body)),
+            404 => AdapterError::NotFound(url.to_string()),
+            429 => AdapterError::RateLimited { url: url.to_string(), retry_after },
+            _ => AdapterError::Http { status, url: url.to_string(), body },
+        }
+    }
+
+    /// HTTP status code, if the error came from an HTTP response
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            AdapterError::Http { status, .. } => Some(*status),
+            AdapterError::NotFound(_) => Some(404),
+            AdapterError::RateLimited { .. } => Some(429),
+            _ => None,
+        }
+    }
+
+    /// HTTP 400 (the server rejected our parameters)
+    pub fn is_bad_request(&self) -> bool {
+        self.status() == Some(400)
+    }
+
+    /// Transient failure worth retrying with backoff
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            AdapterError::Timeout(_)
+            | AdapterError::Connection(_)
+            | AdapterError::RateLimited { .. }
+            | AdapterError::WsClosed(_) => true,
+            AdapterError::Http { status, .. } => *status >= 500,
+            _ => false,
+        }
+    }
+}
+
+impl From<reqwest::Error> for AdapterError {
+    fn from(e: reqwest::Error) -> Self {
+        let url = e.url().map(|u| u.to_string()).unwrap_or_default();
+        if e.is_timeout() {
+            AdapterError::Timeout(url)
+        } else if let Some(status) = e.status() {
+            AdapterError::from_status(status.as_u16(), &url, e.to_string(), None)
+        } else {
+            AdapterError::Connection(e.to_string())
+        }
+    }
+}
+
+impl From<tokio_tungstenite::tungstenite::Error> for AdapterError {
+    fn from(e: tokio_tungstenite::tungstenite::Error) -> Self {
+        use tokio_tungstenite::tungstenite::Error as WsError;
+
+        match e {
+            WsError::ConnectionClosed | WsError::AlreadyClosed => {
+                AdapterError::WsClosed(e.to_string())
+            }
+            WsError::Http(response) => {
+                let status = response.status().as_u16();
+                let body = response
+                    .body()
+                    .as_ref()
+                    .map(|b| String::from_utf8_lossy(b).into_owned())
+                    .unwrap_or_default();
+                AdapterError::from_status(status, "WebSocket handshake", body, None)
+            }
+            WsError::Io(io) if io.kind() == std::io::ErrorKind::TimedOut => {
+                AdapterError::Timeout(io.to_string())
+            }
+            other => AdapterError::Connection(other.to_string()),
+        }
+    }
+}
+
+/// Turn a non-success response into an `AdapterError`, passing successes through
+#[cfg(feature = "httpws")]
+pub(crate) async fn check_response(
+    response: reqwest::Response,
+    url: &str,
+) -> AdapterResult<reqwest::Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let retry_after = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .map(Duration::from_secs);
+    let body = response.text().await.unwrap_or_default();
+    Err(AdapterError::from_status(status.as_u16(), url, body, retry_after))
+}
+
+/// Read a response body and decode it as JSON
+#[cfg(feature = "httpws")]
+pub(crate) async fn decode_json<T: serde::de::DeserializeOwned>(
+    response: reqwest::Response,
+    context: &str,
+) -> AdapterResult<T> {
+    let bytes = response.bytes().await?;
+    serde_json::from_slice(&bytes).map_err(|e| AdapterError::decode(context, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_status() {
+        let err = AdapterError::from_status(400, "/price", "bad side".to_string(), None);
+        assert!(err.is_bad_request());
+        assert!(!err.is_retryable());
+
+        assert!(matches!(
+            AdapterError::from_status(401, "/orders", String::new(), None),
+            AdapterError::Auth(_)
+        ));
+        assert!(matches!(
+            AdapterError::from_status(404, "/book", String::new(), None),
+            AdapterError::NotFound(_)
+        ));
+
+        let err =
+            AdapterError::from_status(429, "/book", String::new(), Some(Duration::from_secs(2)));
+        assert_eq!(err.status(), Some(429));
+        assert!(err.is_retryable());
+
+        assert!(AdapterError::from_status(503, "/book", String::new(), None).is_retryable());
+    }
+
+    #[test]
+    fn test_downcast_through_anyhow() {
+        let err: anyhow::Error = AdapterError::MissingField("price").into();
+        assert!(matches!(
+            err.downcast_ref::<AdapterError>(),
+            Some(AdapterError::MissingField("price"))
+        ));
+    }
+}
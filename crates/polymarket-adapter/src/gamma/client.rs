@@ -10,10 +10,11 @@
 //! # Source
 //! - https://docs.polymarket.com/developers/gamma-markets-api/markets
 
-use anyhow::{Context, Result};
 use reqwest::Client;
+use serde::de::DeserializeOwned;
 use tracing::{debug, info};
 
+use crate::error::{check_response, decode_json, AdapterError, AdapterResult};
 use crate::types::GammaMarket;
 use crate::GAMMA_API_BASE;
 
@@ -26,83 +27,54 @@ pub struct GammaClient {
 
 impl GammaClient {
     /// Create a new Gamma client with default base URL
-    pub fn new() -> Result<Self> {
+    pub fn new() -> AdapterResult<Self> {
         Self::with_base_url(GAMMA_API_BASE)
     }
 
     /// Create a new Gamma client with custom base URL
-    pub fn with_base_url(base_url: &str) -> Result<Self> {
-        let client = Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
-            .build()
-            .context("Failed to build HTTP client")?;
+    pub fn with_base_url(base_url: &str) -> AdapterResult<Self> {
+        let client = Client::builder().timeout(std::time::Duration::from_secs(30)).build()?;
 
         Ok(Self { client, base_url: base_url.trim_end_matches('/').to_string() })
     }
 
-    /// GET /markets/slug/{slug} - Get market by slug (most reliable)
-    /// Returns None if 404, errors on other failures
-    pub async fn get_market_by_slug(&self, slug: &str) -> Result<Option<GammaMarket>> {
-        let url = format!("{}/markets/slug/{}", self.base_url, slug);
+    /// GET a path and decode the JSON body
+    async fn get_json<T: DeserializeOwned>(&self, path: &str) -> AdapterResult<T> {
+        let url = format!("{}{}", self.base_url, path);
         debug!("GET {}", url);
 
-        let response = self.client.get(&url).send().await.context("HTTP request failed")?;
-
-        let status = response.status();
-
-        // 404 = market not found (normal case for wrong slug)
-        if status == reqwest::StatusCode::NOT_FOUND {
-            debug!("Market not found for slug: {}", slug);
-            return Ok(None);
-        }
+        let response = self.client.get(&url).send().await?;
+        let response = check_response(response, &url).await?;
+        decode_json(response, path).await
+    }
 
-        if !status.is_success() {
-            let body = response.text().await.unwrap_or_default();
-            anyhow::bail!("HTTP {} for {}: {}", status, url, body);
+    /// GET /markets/slug/{slug} - Get market by slug (most reliable)
+    /// Returns None if 404, errors on other failures
+    pub async fn get_market_by_slug(&self, slug: &str) -> AdapterResult<Option<GammaMarket>> {
+        match self.get_json(&format!("/markets/slug/{}", slug)).await {
+            Ok(market) => Ok(Some(market)),
+            // 404 = market not found (normal case for wrong slug)
+            Err(AdapterError::NotFound(_)) => {
+                debug!("Market not found for slug: {}", slug);
+                Ok(None)
+            }
+            Err(e) => Err(e),
         }
-
-        let market: GammaMarket = response.json().await.context("Failed to parse GammaMarket")?;
-        Ok(Some(market))
     }
 
     /// GET /markets?slug={slug} - Fallback query by slug
     /// Returns empty vec if no matches
-    pub async fn query_markets_by_slug(&self, slug: &str) -> Result<Vec<GammaMarket>> {
-        let url = format!("{}/markets?slug={}", self.base_url, slug);
-        debug!("GET {}", url);
-
-        let response = self.client.get(&url).send().await.context("HTTP request failed")?;
-
-        let status = response.status();
-        if !status.is_success() {
-            let body = response.text().await.unwrap_or_default();
-            anyhow::bail!("HTTP {} for {}: {}", status, url, body);
-        }
-
-        let markets: Vec<GammaMarket> =
-            response.json().await.context("Failed to parse market list")?;
-        Ok(markets)
+    pub async fn query_markets_by_slug(&self, slug: &str) -> AdapterResult<Vec<GammaMarket>> {
+        self.get_json(&format!("/markets?slug={}", slug)).await
     }
 
     /// GET /markets/{id} - Get market by ID
-    pub async fn get_market_by_id(&self, id: &str) -> Result<Option<GammaMarket>> {
-        let url = format!("{}/markets/{}", self.base_url, id);
-        debug!("GET {}", url);
-
-        let response = self.client.get(&url).send().await.context("HTTP request failed")?;
-
-        let status = response.status();
-        if status == reqwest::StatusCode::NOT_FOUND {
-            return Ok(None);
+    pub async fn get_market_by_id(&self, id: &str) -> AdapterResult<Option<GammaMarket>> {
+        match self.get_json(&format!("/markets/{}", id)).await {
+            Ok(market) => Ok(Some(market)),
+            Err(AdapterError::NotFound(_)) => Ok(None),
+            Err(e) => Err(e),
         }
-
-        if !status.is_success() {
-            let body = response.text().await.unwrap_or_default();
-            anyhow::bail!("HTTP {} for {}: {}", status, url, body);
-        }
-
-        let market: GammaMarket = response.json().await.context("Failed to parse GammaMarket")?;
-        Ok(Some(market))
     }
 
     /// List active markets with filters
@@ -112,40 +84,22 @@ impl GammaClient {
         active: bool,
         closed: bool,
         limit: u32,
-    ) -> Result<Vec<GammaMarket>> {
-        let url = format!(
-            "{}/markets?active={}&closed={}&limit={}",
-            self.base_url, active, closed, limit
-        );
-        debug!("GET {}", url);
-
-        let response = self.client.get(&url).send().await.context("HTTP request failed")?;
-
-        let status = response.status();
-        if !status.is_success() {
-            let body = response.text().await.unwrap_or_default();
-            anyhow::bail!("HTTP {} for {}: {}", status, url, body);
-        }
-
-        let markets: Vec<GammaMarket> =
-            response.json().await.context("Failed to parse market list")?;
-        Ok(markets)
+    ) -> AdapterResult<Vec<GammaMarket>> {
+        let path = format!("/markets?active={}&closed={}&limit={}", active, closed, limit);
+        self.get_json(&path).await
     }
 
     /// Test connectivity to Gamma API
-    pub async fn test_connectivity(&self) -> Result<()> {
+    pub async fn test_connectivity(&self) -> AdapterResult<()> {
         info!("Testing connectivity to {}", self.base_url);
 
         let url = format!("{}/markets?limit=1", self.base_url);
-        let response = self.client.get(&url).send().await.context("Connection test failed")?;
+        let response = self.client.get(&url).send().await?;
 
         let status = response.status();
         info!("Gamma connectivity test: HTTP {}", status);
 
-        if !status.is_success() {
-            anyhow::bail!("Gamma API returned HTTP {}", status);
-        }
-
+        check_response(response, &url).await?;
         Ok(())
     }
 }
@@ -172,3 +126,40 @@ mod tests {
         assert_eq!(client.base_url, "https://example.com");
     }
 }
+
+#[cfg(test)]
+mod wiremock_tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_error_variants() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/markets/slug/missing"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/markets/slug/broken"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([1, 2])))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/markets/slug/down"))
+            .respond_with(ResponseTemplate::new(502).set_body_string("bad gateway"))
+            .mount(&server)
+            .await;
+
+        let client = GammaClient::with_base_url(&server.uri()).unwrap();
+        assert!(client.get_market_by_slug("missing").await.unwrap().is_none());
+
+        let err = client.get_market_by_slug("broken").await.unwrap_err();
+        assert!(matches!(err, AdapterError::Decode { .. }));
+
+        let err = client.get_market_by_slug("down").await.unwrap_err();
+        assert!(matches!(err, AdapterError::Http { status: 502, .. }));
+        assert!(err.is_retryable());
+    }
+}
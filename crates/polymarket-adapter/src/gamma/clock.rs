@@ -0,0 +1,143 @@
+//! Clock abstraction for time-driven market selection
+//!
+//! # Design
+//! - `MarketResolver` and `SwitchController` never read the system time directly
+//! - `SystemClock` is the production clock (wall time + monotonic instant)
+//! - `SimulatedClock` only moves when told to, so the switch state machine
+//!   can be driven across a 15-minute boundary in a unit test
+//!
+//! Both readings of a `SimulatedClock` advance together: `advance(d)` moves
+//! the wall time and the monotonic instant by exactly `d`.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+
+/// Source of wall-clock and monotonic time
+pub trait Clock: Send + Sync {
+    /// Current wall-clock time (used for bucket and boundary math)
+    fn now_utc(&self) -> DateTime<Utc>;
+
+    /// Current monotonic instant (used for elapsed-time measurements)
+    fn now_instant(&self) -> Instant;
+
+    /// Time elapsed since `earlier` (zero if `earlier` is in the future)
+    fn elapsed_since(&self, earlier: Instant) -> Duration {
+        self.now_instant().saturating_duration_since(earlier)
+    }
+}
+
+/// Shared clock handle
+pub type SharedClock = Arc<dyn Clock>;
+
+/// Production clock backed by `Utc::now()` and `Instant::now()`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl SystemClock {
+    /// Shared system clock handle
+    pub fn shared() -> SharedClock {
+        Arc::new(SystemClock)
+    }
+}
+
+impl Clock for SystemClock {
+    fn now_utc(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+
+    fn now_instant(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Manually driven clock for tests and simulations
+///
+/// Clones share the same time, so a test can keep one handle and pass
+/// another to the controller under test.
+#[derive(Clone, Debug)]
+pub struct SimulatedClock {
+    start_utc: DateTime<Utc>,
+    start_instant: Instant,
+    offset: Arc<Mutex<Duration>>,
+}
+
+impl SimulatedClock {
+    /// Create a clock frozen at `start`
+    pub fn new(start: DateTime<Utc>) -> Self {
+        Self {
+            start_utc: start,
+            start_instant: Instant::now(),
+            offset: Arc::new(Mutex::new(Duration::ZERO)),
+        }
+    }
+
+    /// Create a clock frozen at a unix timestamp (seconds)
+    pub fn at_timestamp(ts: i64) -> Self {
+        Self::new(DateTime::from_timestamp(ts, 0).unwrap_or_default())
+    }
+
+    /// Move time forward
+    pub fn advance(&self, by: Duration) {
+        *self.offset.lock().unwrap() += by;
+    }
+
+    /// Move time forward by whole seconds
+    pub fn advance_secs(&self, secs: u64) {
+        self.advance(Duration::from_secs(secs));
+    }
+
+    /// Jump forward to a wall-clock time (no-op if it is in the past)
+    pub fn set_utc(&self, to: DateTime<Utc>) {
+        if let Ok(delta) = (to - self.start_utc).to_std() {
+            let mut offset = self.offset.lock().unwrap();
+            *offset = (*offset).max(delta);
+        }
+    }
+
+    /// Shared handle to this clock
+    pub fn shared(&self) -> SharedClock {
+        Arc::new(self.clone())
+    }
+
+    fn offset(&self) -> Duration {
+        *self.offset.lock().unwrap()
+    }
+}
+
+impl Clock for SimulatedClock {
+    fn now_utc(&self) -> DateTime<Utc> {
+        self.start_utc + chrono::Duration::from_std(self.offset()).unwrap_or_default()
+    }
+
+    fn now_instant(&self) -> Instant {
+        self.start_instant + self.offset()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simulated_clock_advances_both_readings() {
+        let clock = SimulatedClock::at_timestamp(1736073000);
+        let handle = clock.shared();
+        let t0 = handle.now_instant();
+
+        assert_eq!(handle.now_utc().timestamp(), 1736073000);
+
+        clock.advance_secs(90);
+        assert_eq!(handle.now_utc().timestamp(), 1736073090);
+        assert_eq!(handle.elapsed_since(t0), Duration::from_secs(90));
+
+        // Jumping backwards is ignored
+        clock.set_utc(DateTime::from_timestamp(1736073000, 0).unwrap());
+        assert_eq!(handle.now_utc().timestamp(), 1736073090);
+
+        clock.set_utc(DateTime::from_timestamp(1736073900, 0).unwrap());
+        assert_eq!(handle.now_utc().timestamp(), 1736073900);
+        assert_eq!(handle.elapsed_since(t0), Duration::from_secs(900));
+    }
+}
@@ -1,18 +1,24 @@
 //! Gamma API client and Market Resolver
 //!
 //! # Components
+//! - `Clock`: Injectable time source (`SystemClock`, `SimulatedClock`)
 //! - `GammaClient`: REST client for Gamma API (market discovery)
 //! - `MarketResolver`: Resolves current 15-minute market with strict validation
 //! - `SwitchController`: Two-phase market switch with safety guarantees
+//! - `RollingMarketFeed`: Continuous market channel feed that applies switch actions
 //!
 //! # Source
 //! - Gamma Structure: https://docs.polymarket.com/developers/gamma-markets-api/gamma-structure
 //! - Gamma Endpoints: https://docs.polymarket.com/developers/gamma-markets-api/markets
 
 mod client;
+pub mod clock;
 pub mod resolver;
+pub mod rolling;
 pub mod switch;
 
 pub use client::GammaClient;
+pub use clock::{Clock, SharedClock, SimulatedClock, SystemClock};
 pub use resolver::{MarketResolver, MarketSeries, ResolverConfig};
+pub use rolling::{RollingMarketFeed, SlugIndex, TaggedMessage};
 pub use switch::{NextCandidate, SwitchController};
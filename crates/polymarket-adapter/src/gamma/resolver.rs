@@ -18,9 +18,12 @@ use anyhow::Result;
 use chrono::{DateTime, Utc};
 use tracing::{debug, info, warn};
 
+use super::clock::{SharedClock, SystemClock};
+use crate::backend::ClobBackend;
+use crate::error::{AdapterError, AdapterResult};
 use crate::gamma::GammaClient;
 use crate::httpws::RestClient;
-use crate::types::{GammaMarket, ResolveResult, ResolvedMarket, SelectionReason};
+use crate::types::{GammaMarket, OrderSide, ResolveResult, ResolvedMarket, SelectionReason};
 
 /// Supported market series
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -47,7 +50,16 @@ impl MarketSeries {
         }
     }
 
+    /// Canonical name ("btc15m", "eth15m")
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MarketSeries::Btc15m => "btc15m",
+            MarketSeries::Eth15m => "eth15m",
+        }
+    }
+
     /// Parse from string
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
             "btc15m" | "btc-15m" | "btc_15m" => Some(MarketSeries::Btc15m),
@@ -82,10 +94,13 @@ impl Default for ResolverConfig {
 
 /// Market Resolver
 /// Resolves the current active market for a given series
-pub struct MarketResolver {
+///
+/// Generic over the CLOB backend used for price validation (httpws by default).
+pub struct MarketResolver<B: ClobBackend = RestClient> {
     gamma: GammaClient,
-    clob: RestClient,
+    clob: B,
     config: ResolverConfig,
+    clock: SharedClock,
 }
 
 impl MarketResolver {
@@ -95,6 +110,7 @@ impl MarketResolver {
             gamma: GammaClient::new()?,
             clob: RestClient::new()?,
             config: ResolverConfig::default(),
+            clock: SystemClock::shared(),
         })
     }
 
@@ -104,6 +120,7 @@ impl MarketResolver {
             gamma: GammaClient::new()?,
             clob: RestClient::new()?,
             config,
+            clock: SystemClock::shared(),
         })
     }
 
@@ -117,11 +134,34 @@ impl MarketResolver {
             gamma: GammaClient::with_base_url(gamma_base_url)?,
             clob: RestClient::with_base_url(clob_base_url)?,
             config,
+            clock: SystemClock::shared(),
+        })
+    }
+}
+
+impl<B: ClobBackend> MarketResolver<B> {
+    /// Create with any CLOB backend
+    pub fn with_backend(gamma_base_url: &str, clob: B, config: ResolverConfig) -> Result<Self> {
+        Ok(Self {
+            gamma: GammaClient::with_base_url(gamma_base_url)?,
+            clob,
+            config,
+            clock: SystemClock::shared(),
         })
     }
 
-    /// Get reference to CLOB client (for commit-time validation)
-    pub fn clob(&self) -> &RestClient {
+    /// Replace the clock used for audit timestamps
+    pub fn set_clock(&mut self, clock: SharedClock) {
+        self.clock = clock;
+    }
+
+    /// Get the clock
+    pub fn clock(&self) -> &SharedClock {
+        &self.clock
+    }
+
+    /// Get reference to CLOB backend (for commit-time validation)
+    pub fn clob(&self) -> &B {
         &self.clob
     }
 
@@ -208,7 +248,7 @@ impl MarketResolver {
                     Ok(Some(market)) => {
                         queried_slugs.push(slug.clone());
                         // With tolerance: asof can be up to tolerance seconds after bucket_end
-                        if let Some(_) = self.validate_market(&market, asof_ts) {
+                        if self.validate_market(&market, asof_ts).is_some() {
                             debug!("Previous bucket {} found but validation failed", slug);
                             continue;
                         }
@@ -249,7 +289,7 @@ impl MarketResolver {
         bucket_start: i64,
         candidate_slugs: Vec<String>,
     ) -> ResolveResult {
-        let now_ms = Utc::now().timestamp_millis();
+        let now_ms = self.clock.now_utc().timestamp_millis();
 
         // Convert clob_token_ids to fixed array
         let clob_token_ids: [String; 2] = match market.clob_token_ids.as_slice() {
@@ -367,45 +407,17 @@ impl MarketResolver {
     }
 
     /// Validate a CLOB token by checking if we can get a price
-    /// Implements side case fallback: try "BUY" first, then "buy" if 400 error
-    async fn validate_clob_token(&self, token_id: &str) -> Result<bool> {
-        // Side variants to try (handles API documentation vs implementation differences)
-        const SIDE_VARIANTS: &[&str] = &["BUY", "buy"];
-
-        for (i, side) in SIDE_VARIANTS.iter().enumerate() {
-            debug!("Trying CLOB price check for {} with side={}", token_id, side);
-            match self.clob.get_price(token_id, side).await {
-                Ok(price_data) => {
-                    // Check if we got a valid price field
-                    if price_data.get("price").is_some() {
-                        return Ok(true);
-                    } else {
-                        debug!("CLOB price response missing 'price' field for {}", token_id);
-                        return Ok(false);
-                    }
-                }
-                Err(e) => {
-                    let error_str = e.to_string();
-                    // If it looks like a 400 error and we have more variants to try, continue
-                    let is_likely_400 = error_str.contains("400")
-                        || error_str.contains("Bad Request")
-                        || error_str.contains("invalid");
-
-                    if is_likely_400 && i + 1 < SIDE_VARIANTS.len() {
-                        debug!(
-                            "CLOB side={} failed ({}), trying next variant",
-                            side, error_str
-                        );
-                        continue;
-                    }
-                    // Last variant or non-retryable error
-                    return Err(e);
-                }
+    /// Returns Ok(false) if the response has no price, Err on API error
+    async fn validate_clob_token(&self, token_id: &str) -> AdapterResult<bool> {
+        debug!("Trying CLOB price check for {} via {}", token_id, self.clob.name());
+        match self.clob.price(token_id, OrderSide::Buy).await {
+            Ok(_) => Ok(true),
+            Err(AdapterError::MissingField(_)) => {
+                debug!("CLOB price response missing 'price' field for {}", token_id);
+                Ok(false)
             }
+            Err(e) => Err(e),
         }
-
-        // Should not reach here, but just in case
-        anyhow::bail!("All CLOB side variants exhausted for token {}", token_id);
     }
 
     /// Validate both CLOB tokens for a market
@@ -859,8 +871,7 @@ mod wiremock_tests {
         // Note: NO CLOB mocks - if CLOB validation is called, test will fail
 
         // Disable CLOB validation
-        let mut config = ResolverConfig::default();
-        config.clob_validation = false;
+        let config = ResolverConfig { clob_validation: false, ..Default::default() };
 
         let resolver =
             MarketResolver::with_base_urls(&gamma_server.uri(), &clob_server.uri(), config)
@@ -0,0 +1,562 @@
+//! Rolling Market Feed - one continuous market channel feed across bucket boundaries
+//!
+//! # Design
+//! - `SwitchController` decides *when* to switch (two-phase, with safety rails)
+//! - One `MarketWsClient` connection stays up; switches are applied with
+//!   dynamic subscribe/unsubscribe on the live socket
+//! - Overlap: `SubscribeNew` adds the next market's tokens, `UnsubscribeOld`
+//!   removes the previous market's tokens `overlap_secs` later
+//! - The controller polls Gamma/CLOB on its own task and sends actions to the
+//!   tagging loop, so slow HTTP calls never stall the socket
+//! - Every emitted message is tagged with the slug of the market it belongs to;
+//!   an unsubscribed market stays in the index for one more poll so its
+//!   already-buffered messages keep their tag
+//!
+//! # Freeze Handling
+//! `SwitchAction::Freeze` never changes subscriptions: the feed keeps
+//! streaming the current market and retries on the next poll.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use super::switch::SwitchController;
+use crate::backend::ClobBackend;
+use crate::httpws::{JsonlRecorder, MarketSubscriptions, MarketWsClient, RestClient};
+use crate::types::{ResolvedMarket, SwitchAction, SwitchStats, WsInboundMessage};
+use crate::CLOB_WSS_ENDPOINT;
+
+/// Buffer between the underlying market client and the tagging loop
+const INNER_CHANNEL_BUFFER: usize = 1024;
+
+/// Buffer between the switch controller task and the tagging loop
+const ACTION_CHANNEL_BUFFER: usize = 16;
+
+/// Market channel message tagged with the market it belongs to
+#[derive(Clone, Debug)]
+pub struct TaggedMessage {
+    /// Slug of the `ResolvedMarket` owning the message's asset (None if unmapped)
+    pub slug: Option<String>,
+    /// Parsed message
+    pub message: WsInboundMessage,
+}
+
+/// Maps asset ids and condition ids to market slugs
+#[derive(Clone, Debug, Default)]
+pub struct SlugIndex {
+    by_asset: HashMap<String, String>,
+    by_condition: HashMap<String, String>,
+}
+
+impl SlugIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a market's tokens and condition id
+    pub fn register(&mut self, market: &ResolvedMarket) {
+        for token in &market.clob_token_ids {
+            self.by_asset.insert(token.clone(), market.slug.clone());
+        }
+        self.by_condition.insert(market.condition_id.clone(), market.slug.clone());
+    }
+
+    /// Forget a market by slug
+    pub fn remove(&mut self, slug: &str) {
+        self.by_asset.retain(|_, s| s != slug);
+        self.by_condition.retain(|_, s| s != slug);
+    }
+
+    /// Find the slug a message belongs to (asset id first, then condition id)
+    pub fn slug_for(&self, msg: &WsInboundMessage) -> Option<String> {
+        msg.asset_id()
+            .and_then(|id| self.by_asset.get(id))
+            .or_else(|| msg.market_id().and_then(|id| self.by_condition.get(id)))
+            .cloned()
+    }
+
+    /// Registered slugs
+    pub fn slugs(&self) -> Vec<String> {
+        let mut slugs: Vec<String> = self.by_condition.values().cloned().collect();
+        slugs.sort();
+        slugs
+    }
+}
+
+/// Self-rolling market feed for a 15-minute series
+pub struct RollingMarketFeed<B: ClobBackend + 'static = RestClient> {
+    controller: SwitchController<B>,
+    endpoint: String,
+    enable_features: bool,
+}
+
+impl<B: ClobBackend + 'static> RollingMarketFeed<B> {
+    /// Create a feed on the default market channel endpoint
+    pub fn new(controller: SwitchController<B>) -> Self {
+        Self::with_endpoint(controller, CLOB_WSS_ENDPOINT)
+    }
+
+    /// Create with custom endpoint (for testing)
+    pub fn with_endpoint(controller: SwitchController<B>, endpoint: &str) -> Self {
+        Self { controller, endpoint: endpoint.to_string(), enable_features: true }
+    }
+
+    /// Enable or disable feature-flagged messages
+    pub fn set_enable_features(&mut self, enable: bool) {
+        self.enable_features = enable;
+    }
+
+    /// Spawn the feed and stream tagged messages through a channel
+    ///
+    /// The join handle resolves to the final switch statistics.
+    pub fn spawn(
+        self,
+        buffer: usize,
+        recorder: Option<JsonlRecorder>,
+        shutdown: Arc<AtomicBool>,
+    ) -> (mpsc::Receiver<TaggedMessage>, JoinHandle<SwitchStats>) {
+        let (tx, rx) = mpsc::channel(buffer);
+        let handle = tokio::spawn(async move { self.run(tx, recorder, shutdown).await });
+        (rx, handle)
+    }
+
+    /// Run the feed until shutdown or until the receiver is dropped
+    pub async fn run(
+        mut self,
+        tx: mpsc::Sender<TaggedMessage>,
+        recorder: Option<JsonlRecorder>,
+        shutdown: Arc<AtomicBool>,
+    ) -> SwitchStats {
+        let poll_interval = Duration::from_millis(self.controller.config().poll_interval_ms);
+        let mut index = SlugIndex::new();
+
+        // Initialize: keep retrying (on freezes and errors) until the first market resolves
+        let initial_tokens = loop {
+            if shutdown.load(Ordering::Relaxed) {
+                return self.controller.stats().clone();
+            }
+            match self.controller.init().await {
+                Ok(SwitchAction::SubscribeNew { tokens, slug }) => {
+                    info!("Rolling feed starting on {}", slug);
+                    if let Some(market) = self.controller.current() {
+                        index.register(market);
+                    }
+                    break tokens;
+                }
+                Ok(SwitchAction::Freeze { reason, message }) => {
+                    warn!("Rolling feed init FREEZE: {} - {}, retrying", reason, message);
+                }
+                Ok(other) => debug!("Unexpected init action: {:?}", other),
+                Err(e) => warn!("Rolling feed init failed: {:#}, retrying", e),
+            }
+            tokio::time::sleep(poll_interval).await;
+        };
+
+        let mut client = MarketWsClient::with_endpoint(&self.endpoint, initial_tokens.to_vec());
+        client.set_enable_features(self.enable_features);
+        let subscriptions = client.subscriptions();
+        let client_shutdown = Arc::new(AtomicBool::new(false));
+        let (mut inner_rx, client_handle) =
+            client.spawn_stream(INNER_CHANNEL_BUFFER, recorder, client_shutdown.clone());
+
+        // Gamma/CLOB calls run on their own task so forwarding never waits on them
+        let (actions_tx, mut actions) = mpsc::channel(ACTION_CHANNEL_BUFFER);
+        let controller = tokio::spawn(poll_controller(
+            self.controller,
+            poll_interval,
+            actions_tx,
+            shutdown.clone(),
+        ));
+
+        // Slugs unsubscribed on the previous poll, dropped from the index on the next
+        let mut retiring: Vec<String> = Vec::new();
+
+        loop {
+            if shutdown.load(Ordering::Relaxed) {
+                break;
+            }
+
+            tokio::select! {
+                polled = actions.recv() => {
+                    let Some(polled) = polled else {
+                        break;
+                    };
+                    for slug in retiring.drain(..) {
+                        index.remove(&slug);
+                    }
+                    apply_action(polled, &subscriptions, &mut index, &mut retiring);
+                }
+
+                msg = inner_rx.recv() => {
+                    let message = match msg {
+                        Some(m) => m,
+                        None => {
+                            info!("Market client stopped, ending rolling feed");
+                            break;
+                        }
+                    };
+                    let slug = index.slug_for(&message);
+                    if tx.send(TaggedMessage { slug, message }).await.is_err() {
+                        info!("Tagged message receiver dropped, stopping rolling feed");
+                        break;
+                    }
+                }
+            }
+        }
+
+        // Stop the market client; it may be parked on a read, so don't wait for it
+        client_shutdown.store(true, Ordering::Relaxed);
+        drop(inner_rx);
+        client_handle.abort();
+
+        // The controller task stops at its next poll once the action receiver is gone
+        drop(actions);
+        controller.await.unwrap_or_else(|e| {
+            warn!("Switch controller task failed: {}", e);
+            SwitchStats::default()
+        })
+    }
+}
+
+/// Switch action from one controller poll
+struct PolledAction {
+    action: SwitchAction,
+    /// Market now current, set for `SubscribeNew` so its tokens can be indexed
+    market: Option<ResolvedMarket>,
+}
+
+/// Poll the controller every `poll_interval` and send each action to the feed
+///
+/// Returns the final switch statistics once shutdown is set or the feed is gone.
+async fn poll_controller<B: ClobBackend + 'static>(
+    mut controller: SwitchController<B>,
+    poll_interval: Duration,
+    actions: mpsc::Sender<PolledAction>,
+    shutdown: Arc<AtomicBool>,
+) -> SwitchStats {
+    let mut poll_timer = tokio::time::interval(poll_interval);
+    loop {
+        poll_timer.tick().await;
+        if shutdown.load(Ordering::Relaxed) {
+            break;
+        }
+        let action = controller.poll().await;
+        let market = match &action {
+            SwitchAction::SubscribeNew { slug, .. } => {
+                controller.current().filter(|m| &m.slug == slug).cloned()
+            }
+            _ => None,
+        };
+        if actions.send(PolledAction { action, market }).await.is_err() {
+            break;
+        }
+    }
+    controller.stats().clone()
+}
+
+/// Apply a switch action to the live subscription
+///
+/// Unsubscribed slugs are queued in `retiring` instead of being removed from
+/// the index, so their messages still buffered in the feed keep their tag.
+fn apply_action(
+    polled: PolledAction,
+    subscriptions: &MarketSubscriptions,
+    index: &mut SlugIndex,
+    retiring: &mut Vec<String>,
+) {
+    match polled.action {
+        SwitchAction::None => {}
+        SwitchAction::SubscribeNew { tokens, slug } => {
+            info!("Rolling feed: subscribing {} (overlap starts)", slug);
+            if let Some(market) = &polled.market {
+                index.register(market);
+            }
+            subscriptions.subscribe(tokens.to_vec());
+        }
+        SwitchAction::UnsubscribeOld { tokens, slug } => {
+            info!("Rolling feed: unsubscribing {} (overlap complete)", slug);
+            subscriptions.unsubscribe(tokens.to_vec());
+            retiring.push(slug);
+        }
+        SwitchAction::SwitchComplete { from_slug, to_slug } => {
+            debug!("Rolling feed: switch complete {} -> {}", from_slug, to_slug);
+        }
+        SwitchAction::Freeze { reason, message } => {
+            warn!("Rolling feed FREEZE: {} - {} (keeping current subscription)", reason, message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SelectionReason;
+
+    fn make_market(slug: &str, condition_id: &str, tokens: [&str; 2]) -> ResolvedMarket {
+        ResolvedMarket {
+            gamma_market_id: "1".to_string(),
+            condition_id: condition_id.to_string(),
+            clob_token_ids: [tokens[0].to_string(), tokens[1].to_string()],
+            slug: slug.to_string(),
+            question: "Q".to_string(),
+            start_date: String::new(),
+            end_date: String::new(),
+            selected_at_ms: 0,
+            selection_reason: SelectionReason::UniqueMatchInWindow,
+            outcomes: ["Up".to_string(), "Down".to_string()],
+            asof_utc: String::new(),
+            candidate_slugs: vec![],
+            bucket_start_ts: 0,
+        }
+    }
+
+    #[test]
+    fn test_slug_index_tags_by_asset_and_condition() {
+        let mut index = SlugIndex::new();
+        index.register(&make_market("btc-updown-15m-900", "cond-a", ["up-a", "down-a"]));
+        index.register(&make_market("btc-updown-15m-1800", "cond-b", ["up-b", "down-b"]));
+
+        let book = WsInboundMessage::parse(
+            r#"{"event_type":"book","asset_id":"down-b","market":"cond-b","timestamp":"1"}"#,
+        );
+        assert_eq!(index.slug_for(&book).as_deref(), Some("btc-updown-15m-1800"));
+
+        // Unknown asset but known condition id
+        let trade = WsInboundMessage::parse(
+            r#"{"event_type":"last_trade_price","asset_id":"other","market":"cond-a",
+                "timestamp":"1","price":"0.5","size":"1","side":"BUY"}"#,
+        );
+        assert_eq!(index.slug_for(&trade).as_deref(), Some("btc-updown-15m-900"));
+
+        index.remove("btc-updown-15m-900");
+        assert_eq!(index.slug_for(&trade), None);
+        assert_eq!(index.slugs(), vec!["btc-updown-15m-1800"]);
+    }
+
+    #[test]
+    fn test_unsubscribed_slug_retires_after_one_poll() {
+        let client = MarketWsClient::with_endpoint(
+            "ws://127.0.0.1:1",
+            vec!["up-a".to_string(), "down-a".to_string()],
+        );
+        let subscriptions = client.subscriptions();
+        let mut index = SlugIndex::new();
+        let mut retiring = Vec::new();
+        index.register(&make_market("btc-updown-15m-900", "cond-a", ["up-a", "down-a"]));
+
+        let unsubscribe = PolledAction {
+            action: SwitchAction::UnsubscribeOld {
+                tokens: ["up-a".to_string(), "down-a".to_string()],
+                slug: "btc-updown-15m-900".to_string(),
+            },
+            market: None,
+        };
+        apply_action(unsubscribe, &subscriptions, &mut index, &mut retiring);
+        assert!(subscriptions.current().is_empty());
+
+        // Frames already buffered for the old market keep their tag
+        let book = WsInboundMessage::parse(
+            r#"{"event_type":"book","asset_id":"up-a","market":"cond-a","timestamp":"1"}"#,
+        );
+        assert_eq!(index.slug_for(&book).as_deref(), Some("btc-updown-15m-900"));
+        assert_eq!(retiring, vec!["btc-updown-15m-900"]);
+    }
+}
+
+/// One full switch through the live feed: wiremock resolver, simulated clock
+/// and a local market channel server
+#[cfg(test)]
+mod wiremock_tests {
+    use super::*;
+    use crate::gamma::{MarketResolver, MarketSeries, ResolverConfig, SimulatedClock};
+    use crate::types::SwitchConfig;
+    use chrono::{TimeZone, Utc};
+    use futures_util::{SinkExt, StreamExt};
+    use serde_json::{json, Value};
+    use tokio_tungstenite::tungstenite::Message;
+    use wiremock::matchers::{method, path, path_regex};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    /// 15-minute aligned bucket start
+    const BUCKET: i64 = 1736073000;
+    const NEXT_BUCKET: i64 = BUCKET + 900;
+
+    fn slug(bucket_start: i64) -> String {
+        format!("btc-updown-15m-{}", bucket_start)
+    }
+
+    async fn mount_market(server: &MockServer, bucket_start: i64, token_ids: &[&str]) {
+        let rfc3339 = |ts: i64| Utc.timestamp_opt(ts, 0).unwrap().to_rfc3339();
+        let body = json!({
+            "id": format!("market-{}", bucket_start),
+            "slug": slug(bucket_start),
+            "question": "Will BTC be up or down?",
+            "conditionId": format!("condition-{}", bucket_start),
+            "clobTokenIds": serde_json::to_string(&token_ids).unwrap(),
+            "outcomes": "[\"Up\",\"Down\"]",
+            "startDate": rfc3339(bucket_start),
+            "endDate": rfc3339(bucket_start + 900),
+            "active": true,
+            "closed": false,
+            "enableOrderBook": true
+        });
+        Mock::given(method("GET"))
+            .and(path(format!("/markets/slug/{}", slug(bucket_start))))
+            .respond_with(ResponseTemplate::new(200).set_body_json(body))
+            .mount(server)
+            .await;
+    }
+
+    /// Market channel server reporting every request frame and answering
+    /// subscribes with one book per asset
+    async fn spawn_server() -> (String, mpsc::UnboundedReceiver<Value>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (frames_tx, frames_rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            let Ok((stream, _)) = listener.accept().await else {
+                return;
+            };
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            while let Some(Ok(msg)) = ws.next().await {
+                let Ok(request) = serde_json::from_str::<Value>(msg.to_text().unwrap_or_default())
+                else {
+                    continue;
+                };
+                let assets = match request.get("operation").and_then(|v| v.as_str()) {
+                    None => request.get("assets_ids"),
+                    Some("subscribe") => request.get("asset_ids"),
+                    Some(_) => None,
+                };
+                let assets: Vec<String> = assets
+                    .cloned()
+                    .map(serde_json::from_value)
+                    .transpose()
+                    .unwrap()
+                    .unwrap_or_default();
+                let _ = frames_tx.send(request);
+                for id in assets {
+                    let book = format!(
+                        r#"{{"event_type":"book","asset_id":"{}","market":"cond","timestamp":"1","bids":[],"asks":[]}}"#,
+                        id
+                    );
+                    if ws.send(Message::Text(book.into())).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+        (format!("ws://{}", addr), frames_rx)
+    }
+
+    async fn next_frame(frames: &mut mpsc::UnboundedReceiver<Value>) -> Value {
+        tokio::time::timeout(Duration::from_secs(5), frames.recv())
+            .await
+            .expect("timed out waiting for a request frame")
+            .expect("server stopped")
+    }
+
+    /// Collect the slug tags of the next `count` market messages
+    async fn next_tags(
+        rx: &mut mpsc::Receiver<TaggedMessage>,
+        count: usize,
+    ) -> HashMap<String, Option<String>> {
+        let mut tags = HashMap::new();
+        while tags.len() < count {
+            let tagged = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+                .await
+                .expect("timed out waiting for a message")
+                .expect("feed ended early");
+            if let WsInboundMessage::Market(_) = &tagged.message {
+                let asset = tagged.message.asset_id().unwrap().to_string();
+                tags.insert(asset, tagged.slug);
+            }
+        }
+        tags
+    }
+
+    /// Test: the feed subscribes the next market at the boundary, tags its
+    /// messages with the new slug and unsubscribes the old one after the overlap
+    #[tokio::test]
+    async fn test_rolling_feed_full_switch() {
+        let gamma_server = MockServer::start().await;
+        let clob_server = MockServer::start().await;
+        mount_market(&gamma_server, BUCKET, &["up-1", "down-1"]).await;
+        mount_market(&gamma_server, NEXT_BUCKET, &["up-2", "down-2"]).await;
+        Mock::given(method("GET"))
+            .and(path_regex(r"/markets/slug/.*"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&gamma_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/price"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "price": "0.55" })))
+            .mount(&clob_server)
+            .await;
+
+        let resolver = MarketResolver::with_base_urls(
+            &gamma_server.uri(),
+            &clob_server.uri(),
+            ResolverConfig { clob_validation: false, ..Default::default() },
+        )
+        .expect("Failed to create resolver");
+        let config = SwitchConfig { poll_interval_ms: 10, ..Default::default() };
+        let (min_consecutive, overlap_secs) = (config.min_consecutive, config.overlap_secs);
+        let clock = SimulatedClock::at_timestamp(BUCKET + 850);
+        let mut controller =
+            SwitchController::with_resolver(MarketSeries::Btc15m, config, resolver);
+        controller.set_clock(clock.shared());
+
+        let (endpoint, mut frames) = spawn_server().await;
+        let feed = RollingMarketFeed::with_endpoint(controller, &endpoint);
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let (mut rx, handle) = feed.spawn(64, None, shutdown.clone());
+
+        // Starts on the current market
+        let subscribe = next_frame(&mut frames).await;
+        assert_eq!(subscribe["assets_ids"], json!(["up-1", "down-1"]));
+        let tags = next_tags(&mut rx, 2).await;
+        assert_eq!(tags["up-1"], Some(slug(BUCKET)));
+        assert_eq!(tags["down-1"], Some(slug(BUCKET)));
+
+        // Let the controller confirm the next market before the boundary
+        let next_path = format!("/markets/slug/{}", slug(NEXT_BUCKET));
+        loop {
+            let requests = gamma_server.received_requests().await.unwrap_or_default();
+            let polls = requests.iter().filter(|r| r.url.path() == next_path).count();
+            if polls >= min_consecutive as usize {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        // Boundary: the next market is added on the live socket
+        clock.advance_secs(50);
+        let subscribe = next_frame(&mut frames).await;
+        assert_eq!(subscribe["operation"], "subscribe");
+        assert_eq!(subscribe["asset_ids"], json!(["up-2", "down-2"]));
+        let tags = next_tags(&mut rx, 2).await;
+        assert_eq!(tags["up-2"], Some(slug(NEXT_BUCKET)));
+        assert_eq!(tags["down-2"], Some(slug(NEXT_BUCKET)));
+
+        // Overlap over: the old market is removed
+        clock.advance_secs(overlap_secs);
+        let unsubscribe = next_frame(&mut frames).await;
+        assert_eq!(unsubscribe["operation"], "unsubscribe");
+        assert_eq!(unsubscribe["asset_ids"], json!(["up-1", "down-1"]));
+
+        shutdown.store(true, Ordering::Relaxed);
+        drop(rx);
+        let stats = tokio::time::timeout(Duration::from_secs(5), handle)
+            .await
+            .expect("feed did not stop")
+            .unwrap();
+        assert_eq!(stats.switch_count, 1);
+        assert_eq!(stats.freeze_count, 0);
+    }
+}
@@ -13,6 +13,14 @@
 //! Prepare -> Ready (N consecutive matches)
 //! Ready -> Committing (boundary reached + CLOB check)
 //! Committing -> Stable (overlap complete)
+//!
+//! # Risk
+//! With `set_risk_controls`, a `Freeze` action freezes the series for new
+//! orders and the next `SubscribeNew` (init or commit) lifts it.
+//!
+//! # Time
+//! All time reads go through the injected `Clock`, so the state machine can be
+//! driven across a boundary with `SimulatedClock`.
 
 use std::time::Instant;
 
@@ -20,8 +28,15 @@ use anyhow::Result;
 use chrono::{DateTime, TimeZone, Utc};
 use tracing::{debug, error, info, warn};
 
+use super::clock::SharedClock;
 use super::resolver::{MarketResolver, MarketSeries, ResolverConfig};
-use crate::types::{ResolveResult, ResolvedMarket, SwitchAction, SwitchConfig, SwitchPhase, SwitchStats};
+use crate::backend::ClobBackend;
+use crate::error::{AdapterError, AdapterResult};
+use crate::httpws::RestClient;
+use crate::risk::RiskControls;
+use crate::types::{
+    OrderSide, ResolveResult, ResolvedMarket, SwitchAction, SwitchConfig, SwitchPhase, SwitchStats,
+};
 
 /// Bucket size in seconds (15 minutes)
 const BUCKET_SIZE_SECS: i64 = 900;
@@ -46,10 +61,11 @@ struct PendingUnsubscribe {
 }
 
 /// Switch Controller - manages market transitions with safety guarantees
-pub struct SwitchController {
-    resolver: MarketResolver,
+pub struct SwitchController<B: ClobBackend = RestClient> {
+    resolver: MarketResolver<B>,
     series: MarketSeries,
     config: SwitchConfig,
+    clock: SharedClock,
 
     // State
     phase: SwitchPhase,
@@ -61,23 +77,15 @@ pub struct SwitchController {
     stats: SwitchStats,
     last_resolve_ok_at: Option<Instant>,
     boundary_reached_at: Option<Instant>,
+
+    // Pre-trade risk hook
+    risk: Option<RiskControls>,
 }
 
 impl SwitchController {
     /// Create a new switch controller
     pub fn new(series: MarketSeries, config: SwitchConfig) -> Result<Self> {
-        Ok(Self {
-            resolver: MarketResolver::new()?,
-            series,
-            config,
-            phase: SwitchPhase::Stable,
-            current: None,
-            next_candidate: None,
-            pending_unsubscribe: None,
-            stats: SwitchStats::default(),
-            last_resolve_ok_at: None,
-            boundary_reached_at: None,
-        })
+        Ok(Self::with_resolver(series, config, MarketResolver::new()?))
     }
 
     /// Create with custom resolver config
@@ -86,10 +94,28 @@ impl SwitchController {
         switch_config: SwitchConfig,
         resolver_config: ResolverConfig,
     ) -> Result<Self> {
-        Ok(Self {
-            resolver: MarketResolver::with_config(resolver_config)?,
+        Ok(Self::with_resolver(
+            series,
+            switch_config,
+            MarketResolver::with_config(resolver_config)?,
+        ))
+    }
+}
+
+impl<B: ClobBackend> SwitchController<B> {
+    /// Create with a prepared resolver (any backend, or wiremock in tests)
+    ///
+    /// The controller adopts the resolver's clock.
+    pub fn with_resolver(
+        series: MarketSeries,
+        config: SwitchConfig,
+        resolver: MarketResolver<B>,
+    ) -> Self {
+        Self {
+            clock: resolver.clock().clone(),
+            resolver,
             series,
-            config: switch_config,
+            config,
             phase: SwitchPhase::Stable,
             current: None,
             next_candidate: None,
@@ -97,7 +123,19 @@ impl SwitchController {
             stats: SwitchStats::default(),
             last_resolve_ok_at: None,
             boundary_reached_at: None,
-        })
+            risk: None,
+        }
+    }
+
+    /// Freeze/unfreeze this series in shared risk controls on Freeze/SubscribeNew
+    pub fn set_risk_controls(&mut self, controls: RiskControls) {
+        self.risk = Some(controls);
+    }
+
+    /// Replace the clock (also used by the resolver)
+    pub fn set_clock(&mut self, clock: SharedClock) {
+        self.resolver.set_clock(clock.clone());
+        self.clock = clock;
     }
 
     /// Get current phase
@@ -115,6 +153,11 @@ impl SwitchController {
         self.next_candidate.as_ref()
     }
 
+    /// Get configuration
+    pub fn config(&self) -> &SwitchConfig {
+        &self.config
+    }
+
     /// Get statistics
     pub fn stats(&self) -> &SwitchStats {
         &self.stats
@@ -123,34 +166,60 @@ impl SwitchController {
     /// Initialize controller by resolving current market
     pub async fn init(&mut self) -> Result<SwitchAction> {
         info!("Initializing SwitchController for {:?}", self.series);
-        let now = Utc::now();
+        let now = self.clock.now_utc();
 
-        match self.resolver.resolve(&self.series, now).await {
+        let action = match self.resolver.resolve(&self.series, now).await {
             ResolveResult::Ok(market) => {
                 info!("Initialized with market: {} (bucket_start: {})", market.slug, market.bucket_start_ts);
                 let tokens = market.clob_token_ids.clone();
                 let slug = market.slug.clone();
                 self.current = Some(market);
-                self.last_resolve_ok_at = Some(Instant::now());
+                self.last_resolve_ok_at = Some(self.clock.now_instant());
                 self.phase = SwitchPhase::Stable;
-                Ok(SwitchAction::SubscribeNew { tokens, slug })
+                SwitchAction::SubscribeNew { tokens, slug }
             }
             ResolveResult::Freeze { reason, message, .. } => {
                 warn!("Init failed: {:?} - {}", reason, message);
                 self.stats.freeze_count += 1;
-                Ok(SwitchAction::Freeze {
+                SwitchAction::Freeze {
                     reason: format!("{:?}", reason),
                     message,
-                })
+                }
             }
-        }
+        };
+        self.notify_risk(&action);
+        Ok(action)
     }
 
     /// Poll for state updates - call this periodically (every poll_interval_ms)
     pub async fn poll(&mut self) -> SwitchAction {
+        let action = self.poll_phase().await;
+        self.notify_risk(&action);
+        action
+    }
+
+    /// Forward Freeze/SubscribeNew to the risk controls (if set)
+    fn notify_risk(&self, action: &SwitchAction) {
+        let Some(controls) = &self.risk else {
+            return;
+        };
+        let series = self.series.as_str();
+        match action {
+            SwitchAction::Freeze { reason, message } => {
+                controls.freeze_series(series, &format!("{}: {}", reason, message));
+            }
+            SwitchAction::SubscribeNew { tokens, .. } => {
+                controls.assign_tokens(series, tokens);
+                controls.unfreeze_series(series);
+            }
+            _ => {}
+        }
+    }
+
+    async fn poll_phase(&mut self) -> SwitchAction {
         // Check for pending unsubscribe first
         if let Some(pending) = &self.pending_unsubscribe {
-            let elapsed = pending.scheduled_at.elapsed().as_secs();
+            let elapsed = self.clock.elapsed_since(pending.scheduled_at).as_secs();
             if elapsed >= self.config.overlap_secs {
                 let pending = self.pending_unsubscribe.take().unwrap();
                 info!("Overlap complete, unsubscribing old: {}", pending.slug);
@@ -189,7 +258,7 @@ impl SwitchController {
 
         match self.resolver.resolve(&self.series, next_asof).await {
             ResolveResult::Ok(market) => {
-                self.last_resolve_ok_at = Some(Instant::now());
+                self.last_resolve_ok_at = Some(self.clock.now_instant());
 
                 // CRITICAL: Check monotonicity first
                 if !self.is_monotonic_advance(&market) {
@@ -230,7 +299,8 @@ impl SwitchController {
                         // Calculate lead time for stats
                         if let Some(current) = &self.current {
                             if let Ok(end) = DateTime::parse_from_rfc3339(&current.end_date) {
-                                let secs_to_end = end.timestamp() - Utc::now().timestamp();
+                                let secs_to_end =
+                                    end.timestamp() - self.clock.now_utc().timestamp();
                                 self.stats.last_ready_lead_secs = Some(secs_to_end);
                             }
                         }
@@ -240,7 +310,7 @@ impl SwitchController {
                     debug!("Prepare: new candidate or mismatch, resetting to: {}", market.slug);
                     self.next_candidate = Some(NextCandidate {
                         market,
-                        first_seen_at: Instant::now(),
+                        first_seen_at: self.clock.now_instant(),
                         consecutive_matches: 1,
                     });
                 }
@@ -264,7 +334,7 @@ impl SwitchController {
         }
 
         info!("Boundary reached, performing commit-time CLOB validation...");
-        self.boundary_reached_at = Some(Instant::now());
+        self.boundary_reached_at = Some(self.clock.now_instant());
 
         // Commit-time CLOB validation: re-check tokens before switching
         if let Some(candidate) = &self.next_candidate {
@@ -303,37 +373,22 @@ impl SwitchController {
 
     /// Validate tokens for commit-time check
     /// Returns Ok(true) if tokens are tradeable, Ok(false) if no price, Err on API error
-    async fn validate_tokens_for_commit(&self, tokens: &[String; 2]) -> Result<bool> {
+    async fn validate_tokens_for_commit(&self, tokens: &[String; 2]) -> AdapterResult<bool> {
         // Only check the first token (Up) - if one works, the pair is likely good
         let token = &tokens[0];
         debug!("Commit-time validation for token: {}", token);
 
-        // Use the resolver's internal CLOB client for price check
-        // Try both "BUY" and "buy" cases
-        for side in &["BUY", "buy"] {
-            match self.resolver.clob().get_price(token, side).await {
-                Ok(price_data) => {
-                    if price_data.get("price").is_some() {
-                        debug!("Commit-time CLOB check passed for {}", token);
-                        return Ok(true);
-                    } else {
-                        debug!("Commit-time CLOB check: no price field for {}", token);
-                        return Ok(false);
-                    }
-                }
-                Err(e) => {
-                    let error_str = e.to_string();
-                    // If 400 error, try next side variant
-                    if error_str.contains("400") || error_str.contains("Bad Request") {
-                        continue;
-                    }
-                    return Err(e);
-                }
+        match self.resolver.clob().price(token, OrderSide::Buy).await {
+            Ok(_) => {
+                debug!("Commit-time CLOB check passed for {}", token);
+                Ok(true)
+            }
+            Err(AdapterError::MissingField(_)) => {
+                debug!("Commit-time CLOB check: no price field for {}", token);
+                Ok(false)
             }
+            Err(e) => Err(e),
         }
-
-        // All side variants failed
-        anyhow::bail!("All CLOB side variants failed for commit-time check")
     }
 
     /// Poll in Committing phase - execute switch
@@ -357,7 +412,7 @@ impl SwitchController {
             self.pending_unsubscribe = Some(PendingUnsubscribe {
                 tokens: old_market.clob_token_ids.clone(),
                 slug: old_market.slug.clone(),
-                scheduled_at: Instant::now(),
+                scheduled_at: self.clock.now_instant(),
             });
         }
 
@@ -368,7 +423,8 @@ impl SwitchController {
 
         // Calculate switch latency
         if let Some(boundary_at) = self.boundary_reached_at.take() {
-            self.stats.last_switch_latency_ms = Some(boundary_at.elapsed().as_millis() as u64);
+            self.stats.last_switch_latency_ms =
+                Some(self.clock.elapsed_since(boundary_at).as_millis() as u64);
         }
 
         info!("SWITCH: {} -> {}", from_slug, to_slug);
@@ -393,7 +449,7 @@ impl SwitchController {
             Err(_) => return false,
         };
 
-        let now = Utc::now();
+        let now = self.clock.now_utc();
         let secs_to_end = (end.timestamp() - now.timestamp()).max(0);
 
         secs_to_end <= self.config.lead_time_secs
@@ -405,11 +461,9 @@ impl SwitchController {
             .current
             .as_ref()
             .map(|m| m.bucket_start_ts + 905) // 900 + 5s safety margin
-            .unwrap_or_else(|| Utc::now().timestamp() + 900);
+            .unwrap_or_else(|| self.clock.now_utc().timestamp() + 900);
 
-        Utc.timestamp_opt(next_bucket_ts, 0)
-            .single()
-            .unwrap_or_else(Utc::now)
+        Utc.timestamp_opt(next_bucket_ts, 0).single().unwrap_or_else(|| self.clock.now_utc())
     }
 
     /// Check if resolved market is consistent with current candidate
@@ -455,12 +509,12 @@ impl SwitchController {
             Err(_) => return false,
         };
 
-        Utc::now().timestamp() >= end.timestamp()
+        self.clock.now_utc().timestamp() >= end.timestamp()
     }
 
     /// Format status line for observability
     pub fn status_line(&self) -> String {
-        let now = Utc::now().format("%H:%M:%S");
+        let now = self.clock.now_utc().format("%H:%M:%S");
         let phase = format!("{:?}", self.phase);
         let current_slug = self.current.as_ref().map(|m| m.slug.as_str()).unwrap_or("None");
 
@@ -517,3 +571,227 @@ mod tests {
         assert!(json.contains("\"slug\":\"test-slug\""));
     }
 }
+
+/// Wiremock tests driving the full switch cycle with a simulated clock
+/// Stable -> Prepare -> Ready -> Committing -> Stable, then overlap unsubscribe
+#[cfg(test)]
+mod wiremock_tests {
+    use super::*;
+    use crate::gamma::SimulatedClock;
+    use wiremock::matchers::{method, path, path_regex};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    /// 15-minute aligned bucket start (1736073000 / 900 = 1928970)
+    const BUCKET: i64 = 1736073000;
+
+    fn rfc3339(ts: i64) -> String {
+        Utc.timestamp_opt(ts, 0).unwrap().to_rfc3339()
+    }
+
+    /// Helper: Gamma market JSON for a bucket with a real trading window
+    fn make_gamma_market_json(bucket_start: i64, token_ids: &[&str]) -> serde_json::Value {
+        serde_json::json!({
+            "id": format!("market-{}", bucket_start),
+            "slug": format!("btc-updown-15m-{}", bucket_start),
+            "question": "Will BTC be up or down?",
+            "conditionId": format!("condition-{}", bucket_start),
+            "clobTokenIds": serde_json::to_string(&token_ids).unwrap(),
+            "outcomes": "[\"Up\",\"Down\"]",
+            "startDate": rfc3339(bucket_start),
+            "endDate": rfc3339(bucket_start + BUCKET_SIZE_SECS),
+            "active": true,
+            "closed": false,
+            "enableOrderBook": true
+        })
+    }
+
+    async fn mount_market(server: &MockServer, bucket_start: i64, token_ids: &[&str]) {
+        Mock::given(method("GET"))
+            .and(path(format!("/markets/slug/btc-updown-15m-{}", bucket_start)))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(make_gamma_market_json(bucket_start, token_ids)),
+            )
+            .mount(server)
+            .await;
+    }
+
+    /// Controller on mock servers, clock frozen at `BUCKET + offset_secs`
+    async fn setup(offset_secs: i64) -> (SwitchController, SimulatedClock, MockServer, MockServer) {
+        let gamma_server = MockServer::start().await;
+        let clob_server = MockServer::start().await;
+
+        mount_market(&gamma_server, BUCKET, &["up-1", "down-1"]).await;
+        mount_market(&gamma_server, BUCKET + BUCKET_SIZE_SECS, &["up-2", "down-2"]).await;
+        Mock::given(method("GET"))
+            .and(path_regex(r"/markets/slug/.*"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&gamma_server)
+            .await;
+
+        let resolver_config = ResolverConfig { clob_validation: false, ..Default::default() };
+        let resolver = MarketResolver::with_base_urls(
+            &gamma_server.uri(),
+            &clob_server.uri(),
+            resolver_config,
+        )
+        .expect("Failed to create resolver");
+
+        let clock = SimulatedClock::at_timestamp(BUCKET + offset_secs);
+        let mut controller = SwitchController::with_resolver(
+            MarketSeries::Btc15m,
+            SwitchConfig::default(),
+            resolver,
+        );
+        controller.set_clock(clock.shared());
+
+        (controller, clock, gamma_server, clob_server)
+    }
+
+    async fn mount_clob_price(server: &MockServer, status: u16) {
+        let template = match status {
+            200 => ResponseTemplate::new(200).set_body_json(serde_json::json!({ "price": "0.55" })),
+            code => ResponseTemplate::new(code),
+        };
+        Mock::given(method("GET")).and(path("/price")).respond_with(template).mount(server).await;
+    }
+
+    #[tokio::test]
+    async fn test_full_switch_cycle() {
+        let (mut controller, clock, _gamma, clob) = setup(300).await;
+        mount_clob_price(&clob, 200).await;
+        let config = controller.config().clone();
+
+        // Init on the current bucket
+        match controller.init().await.unwrap() {
+            SwitchAction::SubscribeNew { tokens, slug } => {
+                assert_eq!(slug, format!("btc-updown-15m-{}", BUCKET));
+                assert_eq!(tokens, ["up-1".to_string(), "down-1".to_string()]);
+            }
+            other => panic!("Expected SubscribeNew, got {:?}", other),
+        }
+        assert_eq!(*controller.phase(), SwitchPhase::Stable);
+
+        // Far from the boundary: nothing happens
+        assert!(matches!(controller.poll().await, SwitchAction::None));
+        assert_eq!(*controller.phase(), SwitchPhase::Stable);
+
+        // Inside lead time: Stable -> Prepare with the first match
+        clock.advance_secs(900 - 300 - config.lead_time_secs as u64 + 10);
+        assert!(matches!(controller.poll().await, SwitchAction::None));
+        assert_eq!(*controller.phase(), SwitchPhase::Prepare);
+        assert_eq!(controller.next_candidate().unwrap().consecutive_matches, 1);
+
+        // Debounce: Prepare -> Ready after min_consecutive matches
+        for _ in 1..config.min_consecutive {
+            assert!(matches!(controller.poll().await, SwitchAction::None));
+        }
+        assert_eq!(*controller.phase(), SwitchPhase::Ready);
+        assert_eq!(controller.stats().last_ready_lead_secs, Some(config.lead_time_secs - 10));
+
+        // Ready holds until the boundary
+        clock.advance_secs(config.lead_time_secs as u64 - 11);
+        assert!(matches!(controller.poll().await, SwitchAction::None));
+        assert_eq!(*controller.phase(), SwitchPhase::Ready);
+
+        // Boundary: Ready -> Committing -> Stable on the next market
+        clock.advance_secs(1);
+        match controller.poll().await {
+            SwitchAction::SubscribeNew { tokens, slug } => {
+                assert_eq!(slug, format!("btc-updown-15m-{}", BUCKET + BUCKET_SIZE_SECS));
+                assert_eq!(tokens, ["up-2".to_string(), "down-2".to_string()]);
+            }
+            other => panic!("Expected SubscribeNew, got {:?}", other),
+        }
+        assert_eq!(*controller.phase(), SwitchPhase::Stable);
+        assert_eq!(controller.current().unwrap().bucket_start_ts, BUCKET + BUCKET_SIZE_SECS);
+        assert_eq!(controller.stats().switch_count, 1);
+        assert_eq!(controller.stats().last_switch_latency_ms, Some(0));
+
+        // Overlap: old tokens stay subscribed until overlap_secs elapse
+        clock.advance_secs(config.overlap_secs - 1);
+        assert!(matches!(controller.poll().await, SwitchAction::None));
+
+        clock.advance_secs(1);
+        match controller.poll().await {
+            SwitchAction::UnsubscribeOld { tokens, slug } => {
+                assert_eq!(slug, format!("btc-updown-15m-{}", BUCKET));
+                assert_eq!(tokens, ["up-1".to_string(), "down-1".to_string()]);
+            }
+            other => panic!("Expected UnsubscribeOld, got {:?}", other),
+        }
+        assert!(matches!(controller.poll().await, SwitchAction::None));
+        assert_eq!(*controller.phase(), SwitchPhase::Stable);
+        assert_eq!(controller.stats().freeze_count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_commit_clob_error_stays_ready() {
+        let (mut controller, clock, _gamma, clob) = setup(850).await;
+        mount_clob_price(&clob, 500).await;
+
+        controller.init().await.unwrap();
+        for _ in 0..controller.config().min_consecutive {
+            controller.poll().await;
+        }
+        assert_eq!(*controller.phase(), SwitchPhase::Ready);
+
+        clock.advance_secs(50);
+        match controller.poll().await {
+            SwitchAction::Freeze { reason, .. } => assert_eq!(reason, "CommitClobError"),
+            other => panic!("Expected Freeze, got {:?}", other),
+        }
+        assert_eq!(*controller.phase(), SwitchPhase::Ready);
+        assert_eq!(controller.current().unwrap().bucket_start_ts, BUCKET);
+        assert_eq!(controller.stats().switch_count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_freeze_blocks_orders_for_series() {
+        use crate::order_manager::OrderManager;
+        use crate::positions::PositionBook;
+        use crate::risk::{OrderIntent, RiskLimits, RiskManager, RiskViolation};
+        use crate::types::OrderSide;
+
+        let (mut controller, clock, _gamma, clob) = setup(850).await;
+        mount_clob_price(&clob, 500).await;
+        let risk = RiskManager::new(RiskLimits::default());
+        controller.set_risk_controls(risk.controls().clone());
+
+        let intent = OrderIntent {
+            market: "0xm".to_string(),
+            token_id: "up-1".to_string(),
+            side: OrderSide::Buy,
+            price: 0.5,
+            size: 10.0,
+        };
+        let check = |risk: &RiskManager| {
+            risk.check(&intent, &OrderManager::new(), &PositionBook::new(), None)
+        };
+
+        controller.init().await.unwrap();
+        assert!(check(&risk).is_ok());
+        for _ in 0..controller.config().min_consecutive {
+            controller.poll().await;
+        }
+
+        // Commit-time CLOB error: Freeze blocks the series
+        clock.advance_secs(50);
+        assert!(matches!(controller.poll().await, SwitchAction::Freeze { .. }));
+        match check(&risk) {
+            Err(RiskViolation::SeriesFrozen { series, reason }) => {
+                assert_eq!(series, "btc15m");
+                assert!(reason.starts_with("CommitClobError"));
+            }
+            other => panic!("Expected SeriesFrozen, got {:?}", other),
+        }
+
+        // CLOB recovers: the commit lifts the freeze
+        clob.reset().await;
+        mount_clob_price(&clob, 200).await;
+        assert!(matches!(controller.poll().await, SwitchAction::SubscribeNew { .. }));
+        assert!(risk.controls().frozen_reason("btc15m").is_none());
+        assert_eq!(risk.controls().series_of("up-2").as_deref(), Some("btc15m"));
+        assert!(check(&risk).is_ok());
+    }
+}
@@ -4,6 +4,19 @@
 //! - L1: Private key signs EIP-712 message (for credential creation)
 //! - L2: API credentials (apiKey, secret, passphrase) for CLOB operations
 //!
+//! # L1 Authentication
+//! The wallet signs an EIP-712 `ClobAuth` message (domain `ClobAuthDomain`, version 1,
+//! chain 137) and sends it as `POLY_ADDRESS`, `POLY_SIGNATURE`, `POLY_TIMESTAMP`,
+//! `POLY_NONCE` to `POST /auth/api-key` (create) or `GET /auth/derive-api-key` (derive).
+//! Both return the L2 `ApiCredentials`.
+//!
+//! # L2 Request Signing
+//! Authenticated REST calls carry five headers:
+//! `POLY_ADDRESS`, `POLY_SIGNATURE`, `POLY_TIMESTAMP`, `POLY_API_KEY`, `POLY_PASSPHRASE`.
+//! The signature is
+//! `base64url(HMAC_SHA256(base64url_decode(secret), timestamp + method + path + body))`,
+//! where `path` excludes the query string and `body` is the exact JSON sent (empty for none).
+//!
 //! # WSS Authentication
 //! Only `user` channel requires authentication.
 //! Market channel is public and requires no auth.
@@ -12,7 +25,43 @@
 //! - Authentication: https://docs.polymarket.com/developers/CLOB/authentication
 //! - WSS Auth: https://docs.polymarket.com/developers/CLOB/websocket/wss-auth
 
+use base64::engine::general_purpose::URL_SAFE;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use reqwest::header::{HeaderMap, HeaderValue};
 use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::error::{AdapterError, AdapterResult};
+use crate::httpws::signer::{
+    domain_separator, encode_address, encode_u256, keccak256, typed_data_hash, WalletSigner,
+};
+
+/// Signer address header
+pub const POLY_ADDRESS: &str = "POLY_ADDRESS";
+/// HMAC signature header
+pub const POLY_SIGNATURE: &str = "POLY_SIGNATURE";
+/// Unix timestamp (seconds) header
+pub const POLY_TIMESTAMP: &str = "POLY_TIMESTAMP";
+/// API key header
+pub const POLY_API_KEY: &str = "POLY_API_KEY";
+/// API passphrase header
+pub const POLY_PASSPHRASE: &str = "POLY_PASSPHRASE";
+/// L1 nonce header
+pub const POLY_NONCE: &str = "POLY_NONCE";
+
+/// Fixed message signed in the L1 `ClobAuth` struct
+pub const CLOB_AUTH_MESSAGE: &str = "This message attests that I control the given wallet";
+
+/// EIP-712 domain name for L1 auth
+const CLOB_AUTH_DOMAIN_NAME: &str = "ClobAuthDomain";
+
+/// EIP-712 domain version for L1 auth
+const CLOB_AUTH_DOMAIN_VERSION: &str = "1";
+
+/// EIP-712 type string for the L1 auth struct
+const CLOB_AUTH_TYPE: &str =
+    "ClobAuth(address address,string timestamp,uint256 nonce,string message)";
 
 /// L2 API credentials for CLOB operations
 /// These are derived from L1 authentication (private key signing)
@@ -21,6 +70,7 @@ use serde::{Deserialize, Serialize};
 #[derive(Clone, Serialize, Deserialize)]
 pub struct ApiCredentials {
     /// CLOB API key
+    #[serde(alias = "apiKey")]
     pub api_key: String,
     /// CLOB API secret (used for HMAC-SHA256 signing)
     pub secret: String,
@@ -47,6 +97,130 @@ impl ApiCredentials {
     pub fn is_valid(&self) -> bool {
         !self.api_key.is_empty() && !self.secret.is_empty() && !self.passphrase.is_empty()
     }
+
+    /// L2 signature for one request
+    ///
+    /// `request_path` must not include the query string.
+    pub fn sign(
+        &self,
+        timestamp: i64,
+        method: &str,
+        request_path: &str,
+        body: Option<&str>,
+    ) -> AdapterResult<String> {
+        build_hmac_signature(&self.secret, timestamp, method, request_path, body)
+    }
+
+    /// Full set of L2 headers for one request
+    pub fn l2_headers(
+        &self,
+        address: &str,
+        timestamp: i64,
+        method: &str,
+        request_path: &str,
+        body: Option<&str>,
+    ) -> AdapterResult<HeaderMap> {
+        let signature = self.sign(timestamp, method, request_path, body)?;
+
+        let mut headers = HeaderMap::new();
+        headers.insert(POLY_ADDRESS, header_value(address)?);
+        headers.insert(POLY_SIGNATURE, header_value(&signature)?);
+        headers.insert(POLY_TIMESTAMP, header_value(&timestamp.to_string())?);
+        headers.insert(POLY_API_KEY, header_value(&self.api_key)?);
+        headers.insert(POLY_PASSPHRASE, header_value(&self.passphrase)?);
+        Ok(headers)
+    }
+}
+
+/// EIP-712 digest of the L1 `ClobAuth` message
+pub fn clob_auth_hash(address: &[u8; 20], chain_id: u64, timestamp: i64, nonce: u64) -> [u8; 32] {
+    let mut encoded = Vec::with_capacity(32 * 5);
+    encoded.extend_from_slice(&keccak256(CLOB_AUTH_TYPE.as_bytes()));
+    encoded.extend_from_slice(&encode_address(address));
+    encoded.extend_from_slice(&keccak256(timestamp.to_string().as_bytes()));
+    encoded.extend_from_slice(&encode_u256(nonce as u128));
+    encoded.extend_from_slice(&keccak256(CLOB_AUTH_MESSAGE.as_bytes()));
+    let struct_hash = keccak256(&encoded);
+
+    let domain = domain_separator(CLOB_AUTH_DOMAIN_NAME, CLOB_AUTH_DOMAIN_VERSION, chain_id, None);
+    typed_data_hash(&domain, &struct_hash)
+}
+
+/// L1 headers for the create/derive API key endpoints
+pub fn l1_headers(
+    signer: &WalletSigner,
+    chain_id: u64,
+    timestamp: i64,
+    nonce: u64,
+) -> AdapterResult<HeaderMap> {
+    let hash = clob_auth_hash(signer.address_bytes(), chain_id, timestamp, nonce);
+    let signature = signer.sign_hash(&hash)?;
+
+    let mut headers = HeaderMap::new();
+    headers.insert(POLY_ADDRESS, header_value(&signer.address())?);
+    headers.insert(POLY_SIGNATURE, header_value(&signature)?);
+    headers.insert(POLY_TIMESTAMP, header_value(&timestamp.to_string())?);
+    headers.insert(POLY_NONCE, header_value(&nonce.to_string())?);
+    Ok(headers)
+}
+
+/// HMAC-SHA256 over `timestamp + method + path + body`, base64url encoded
+///
+/// Single quotes in the body are replaced with double quotes to match the
+/// official clients.
+pub fn build_hmac_signature(
+    secret: &str,
+    timestamp: i64,
+    method: &str,
+    request_path: &str,
+    body: Option<&str>,
+) -> AdapterResult<String> {
+    let key = URL_SAFE
+        .decode(secret)
+        .map_err(|e| AdapterError::Auth(format!("API secret is not valid base64url: {}", e)))?;
+
+    let mut message = format!("{}{}{}", timestamp, method, request_path);
+    if let Some(body) = body {
+        message.push_str(&body.replace('\'', "\""));
+    }
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(&key)
+        .map_err(|e| AdapterError::Auth(format!("Invalid HMAC key: {}", e)))?;
+    mac.update(message.as_bytes());
+    Ok(URL_SAFE.encode(mac.finalize().into_bytes()))
+}
+
+/// Header value that rejects control characters instead of panicking
+fn header_value(value: &str) -> AdapterResult<HeaderValue> {
+    HeaderValue::from_str(value)
+        .map_err(|_| AdapterError::Auth("Credential contains invalid header characters".into()))
+}
+
+/// Wallet address plus API credentials, everything needed to sign L2 requests
+#[derive(Clone, Debug)]
+pub struct L2Auth {
+    /// Address the API key was created for (0x-prefixed)
+    pub address: String,
+    /// API credentials
+    pub credentials: ApiCredentials,
+}
+
+impl L2Auth {
+    /// Create from an address and credentials
+    pub fn new(address: &str, credentials: ApiCredentials) -> Self {
+        Self { address: address.to_string(), credentials }
+    }
+
+    /// L2 headers for one request at the given timestamp
+    pub fn headers(
+        &self,
+        timestamp: i64,
+        method: &str,
+        request_path: &str,
+        body: Option<&str>,
+    ) -> AdapterResult<HeaderMap> {
+        self.credentials.l2_headers(&self.address, timestamp, method, request_path, body)
+    }
 }
 
 impl std::fmt::Debug for ApiCredentials {
@@ -104,4 +278,89 @@ mod tests {
         };
         assert!(!invalid.is_valid());
     }
+
+    fn zero_secret_credentials() -> ApiCredentials {
+        ApiCredentials {
+            api_key: "00000000-0000-0000-0000-000000000000".to_string(),
+            secret: "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=".to_string(),
+            passphrase: "pass".to_string(),
+        }
+    }
+
+    /// Known vectors shared with the official clients
+    #[test]
+    fn test_hmac_signature_vectors() {
+        let secret = "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=";
+
+        let sig = build_hmac_signature(
+            secret,
+            1000000,
+            "test-sign",
+            "/orders",
+            Some(r#"{"hash": "0x123"}"#),
+        )
+        .unwrap();
+        assert_eq!(sig, "ZwAdJKvoYRlEKDkNMwd5BuwNNtg93kNaR_oU2HrfVvc=");
+
+        let sig = build_hmac_signature(secret, 1, "GET", "/", None).unwrap();
+        assert_eq!(sig, "eHaylCwqRSOa2LFD77Nt_SaTpbsxzN8eTEI3LryhEj4=");
+
+        // Single quotes are normalized before signing
+        let sig = build_hmac_signature(
+            secret,
+            1000000,
+            "test-sign",
+            "/orders",
+            Some("{'hash': '0x123'}"),
+        )
+        .unwrap();
+        assert_eq!(sig, "ZwAdJKvoYRlEKDkNMwd5BuwNNtg93kNaR_oU2HrfVvc=");
+    }
+
+    #[test]
+    fn test_l2_headers() {
+        let auth = L2Auth::new("0xabc", zero_secret_credentials());
+        let headers = auth.headers(1, "GET", "/", None).unwrap();
+
+        assert_eq!(headers[POLY_ADDRESS], "0xabc");
+        assert_eq!(headers[POLY_SIGNATURE], "eHaylCwqRSOa2LFD77Nt_SaTpbsxzN8eTEI3LryhEj4=");
+        assert_eq!(headers[POLY_TIMESTAMP], "1");
+        assert_eq!(headers[POLY_API_KEY], "00000000-0000-0000-0000-000000000000");
+        assert_eq!(headers[POLY_PASSPHRASE], "pass");
+    }
+
+    /// Vector shared with the official Rust client (publicly known dev key, Amoy chain)
+    #[test]
+    fn test_l1_headers_vector() {
+        let signer = WalletSigner::from_hex(
+            "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+        )
+        .unwrap();
+        let headers = l1_headers(&signer, 80002, 10_000_000, 23).unwrap();
+
+        assert_eq!(headers[POLY_ADDRESS], "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266");
+        assert_eq!(headers[POLY_NONCE], "23");
+        assert_eq!(headers[POLY_TIMESTAMP], "10000000");
+        assert_eq!(
+            headers[POLY_SIGNATURE],
+            "0xf62319a987514da40e57e2f4d7529f7bac38f0355bd88bb5adbb3768d80de6c1\
+             682518e0af677d5260366425f4361e7b70c25ae232aff0ab2331e2b164a1aedc1b"
+        );
+    }
+
+    #[test]
+    fn test_credentials_from_api_response() {
+        let creds: ApiCredentials =
+            serde_json::from_str(r#"{"apiKey": "k", "secret": "s", "passphrase": "p"}"#).unwrap();
+        assert_eq!(creds.api_key, "k");
+        assert!(creds.is_valid());
+    }
+
+    #[test]
+    fn test_invalid_secret_is_auth_error() {
+        let creds =
+            ApiCredentials { secret: "not base64!".to_string(), ..zero_secret_credentials() };
+        let err = creds.sign(1, "GET", "/", None).unwrap_err();
+        assert!(matches!(err, AdapterError::Auth(_)));
+    }
 }
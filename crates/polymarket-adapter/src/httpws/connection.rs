@@ -0,0 +1,830 @@
+//! Shared WebSocket connection loop for the market and user channels
+//!
+//! `WsConnection` owns everything that must behave the same on both channels:
+//! connect, subscribe, application-level keepalive, reading, recording,
+//! delivery to the consumer and reconnect with exponential backoff. A
+//! `WsChannel` only supplies what differs: its subscribe message, optional
+//! live commands (market subscription changes) and a connect hook.
+//!
+//! # Keepalive
+//! Polymarket requires the literal text "PING" every 10 seconds on every
+//! channel (NOT a WebSocket ping frame); the server answers "PONG", which is
+//! neither recorded nor delivered. Protocol ping frames from the server are
+//! still answered with pong frames.
+//! See: https://docs.polymarket.com/developers/CLOB/websocket/wss-overview
+//!
+//! # Reconnect
+//! `ReconnectPolicy` sets the backoff (initial, max, multiplier, jitter) and
+//! when to stop. Consecutive failures (connect errors, or connections dropped
+//! before `reset_after`) drive a circuit breaker published as `FeedStatus`:
+//! - `Up` while connected, `Reconnecting` during normal backoff
+//! - `Down` once `breaker_threshold` attempts in a row failed; retries continue
+//!   every `max` (the feed must be treated as missing, not just late)
+//! - `GaveUp` after `max_attempts`; the give-up callback runs and `run` returns
+//!   `AdapterError::Connection`
+//!
+//! Channel or recorder errors on a live connection are not retried: the
+//! connection ends through the normal path (`Disconnected`, flush, `Stopped`)
+//! and `run` returns the error.
+//!
+//! # Lifecycle
+//! Every connection has its own id. Besides parsed messages the consumer
+//! receives `WsInboundMessage::Lifecycle` events:
+//! - `Connected` and `Subscribed` once the subscribe has been sent
+//! - `Resynced` per asset, right after the first snapshot on that connection
+//! - `Disconnected { ids, reason }` when the connection ends for any reason;
+//!   updates for `ids` may have been missed until each is `Resynced` again
+//! - Channel events (`WsChannel::take_events`), e.g. the market watchdog's
+//!   `Stale`/`Recovered`, after the message or check that raised them
+//!
+//! Lifecycle events are neither recorded nor counted in `MessageStats`.
+//!
+//! # Recording
+//! The recorder gets a fresh connection id on every connect, is flushed before
+//! each reconnect and closed (last segment, manifest) when the loop ends.
+
+use futures_util::stream::{SplitSink, SplitStream};
+use futures_util::{SinkExt, StreamExt};
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+use tokio::sync::{mpsc, watch};
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+use tracing::{debug, error, info, warn};
+
+use crate::error::{AdapterError, AdapterResult};
+use crate::httpws::recorder::{next_connection_id, JsonlRecorder, FLUSH_EVERY_SECS};
+use crate::types::{LifecycleEvent, MessageStats, WsInboundMessage};
+
+/// Log progress every N seconds
+const LOG_PROGRESS_EVERY_SECS: u64 = 60;
+
+/// Application-level PING interval (Polymarket requirement)
+/// Send literal "PING" text message every 10 seconds
+pub const APP_PING_INTERVAL_SECS: u64 = 10;
+
+/// Write half of a channel socket
+pub type WsWrite = SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>;
+
+/// Read half of a channel socket
+pub type WsRead = SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>;
+
+/// Connection health as seen by consumers
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FeedStatus {
+    /// First connect not completed yet
+    #[default]
+    Connecting,
+    /// Connected and subscribed
+    Up,
+    /// Disconnected, retrying with normal backoff
+    Reconnecting { failures: u32 },
+    /// Circuit open: `failures` attempts in a row failed, feed is down
+    Down { failures: u32 },
+    /// Retries exhausted, client stopped
+    GaveUp { failures: u32 },
+    /// Client stopped (shutdown, limit or dropped receiver)
+    Stopped,
+}
+
+impl FeedStatus {
+    /// Whether messages are currently flowing
+    pub fn is_up(&self) -> bool {
+        matches!(self, FeedStatus::Up)
+    }
+
+    /// Circuit open or given up
+    pub fn is_down(&self) -> bool {
+        matches!(self, FeedStatus::Down { .. } | FeedStatus::GaveUp { .. })
+    }
+}
+
+/// Callback run once when the client gives up (argument: consecutive failures)
+pub type GiveUpCallback = Arc<dyn Fn(u32) + Send + Sync>;
+
+/// Reconnect backoff and circuit breaker settings
+#[derive(Clone)]
+pub struct ReconnectPolicy {
+    /// Delay before the first retry
+    pub initial: Duration,
+    /// Delay cap (also the probe interval while the circuit is open)
+    pub max: Duration,
+    /// Growth factor per consecutive failure
+    pub multiplier: f64,
+    /// Random spread as a fraction of the delay (0.2 = ±20%)
+    pub jitter: f64,
+    /// Consecutive failures before giving up (None = retry forever)
+    pub max_attempts: Option<u32>,
+    /// Consecutive failures before the feed is reported `Down`
+    pub breaker_threshold: u32,
+    /// A connection that stays up this long resets the failure count
+    pub reset_after: Duration,
+    on_give_up: Option<GiveUpCallback>,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_secs(1),
+            max: Duration::from_secs(30),
+            multiplier: 2.0,
+            jitter: 0.2,
+            max_attempts: None,
+            breaker_threshold: 5,
+            reset_after: Duration::from_secs(APP_PING_INTERVAL_SECS),
+            on_give_up: None,
+        }
+    }
+}
+
+impl fmt::Debug for ReconnectPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReconnectPolicy")
+            .field("initial", &self.initial)
+            .field("max", &self.max)
+            .field("multiplier", &self.multiplier)
+            .field("jitter", &self.jitter)
+            .field("max_attempts", &self.max_attempts)
+            .field("breaker_threshold", &self.breaker_threshold)
+            .field("reset_after", &self.reset_after)
+            .field("on_give_up", &self.on_give_up.is_some())
+            .finish()
+    }
+}
+
+impl ReconnectPolicy {
+    /// Run `callback` when retries are exhausted
+    pub fn on_give_up(mut self, callback: impl Fn(u32) + Send + Sync + 'static) -> Self {
+        self.on_give_up = Some(Arc::new(callback));
+        self
+    }
+
+    /// Delay without jitter after `failures` consecutive failures
+    pub fn base_delay(&self, failures: u32) -> Duration {
+        if failures >= self.breaker_threshold {
+            return self.max;
+        }
+        let factor = self.multiplier.max(1.0).powi(failures.min(64) as i32);
+        self.initial.mul_f64(factor).min(self.max)
+    }
+
+    /// Delay with jitter applied
+    pub fn delay(&self, failures: u32) -> Duration {
+        self.jittered(self.base_delay(failures), random_unit())
+    }
+
+    /// Spread `base` by `sample` in [0, 1): 0.5 keeps it unchanged
+    fn jittered(&self, base: Duration, sample: f64) -> Duration {
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        base.mul_f64(1.0 + jitter * (2.0 * sample - 1.0))
+    }
+
+    fn status_after(&self, failures: u32) -> FeedStatus {
+        if self.max_attempts.is_some_and(|max| failures >= max) {
+            FeedStatus::GaveUp { failures }
+        } else if failures >= self.breaker_threshold {
+            FeedStatus::Down { failures }
+        } else {
+            FeedStatus::Reconnecting { failures }
+        }
+    }
+}
+
+/// Uniform sample in [0, 1) from the std hasher's random keys (no rand dependency)
+fn random_unit() -> f64 {
+    let bits = RandomState::new().build_hasher().finish();
+    (bits >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Outcome of a periodic channel check
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChannelAction {
+    /// Nothing to do
+    None,
+    /// Send these text frames on the live socket
+    Send(Vec<String>),
+    /// Drop the connection and reconnect (reason for the log)
+    Reconnect(String),
+}
+
+/// Channel-specific part of a WebSocket client
+pub trait WsChannel {
+    /// Live command sent on the open socket (`std::convert::Infallible` for none)
+    type Command: Send;
+
+    /// Channel name for logs ("market", "user")
+    fn name(&self) -> &'static str;
+
+    /// Subscribe message sent after every connect
+    fn subscribe_message(&self) -> AdapterResult<String>;
+
+    /// Text frame for a live command
+    fn command_message(&self, command: Self::Command) -> AdapterResult<String>;
+
+    /// Ids carried by the subscribe message (for `LifecycleEvent::Subscribed`)
+    fn subscribed_ids(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Called once the subscribe has been sent
+    fn on_connected(&self) {}
+
+    /// Called with every parsed message before it is delivered
+    ///
+    /// Returns the assets this message resynced (first snapshot on the
+    /// connection); each gets a `LifecycleEvent::Resynced` after the message.
+    fn on_message(&self, _msg: &WsInboundMessage) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Channel events raised since the last call, delivered after every
+    /// message and check (e.g. watchdog `Stale`/`Recovered`)
+    fn take_events(&self, _conn_id: u64) -> Vec<LifecycleEvent> {
+        Vec::new()
+    }
+
+    /// How often `check` runs on a live connection (None = never)
+    fn check_interval(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Periodic health check on a live connection
+    fn check(&self) -> AdapterResult<ChannelAction> {
+        Ok(ChannelAction::None)
+    }
+}
+
+/// Connect/subscribe/keepalive/reconnect engine shared by the channel clients
+pub struct WsConnection<C: WsChannel> {
+    endpoint: String,
+    channel: C,
+    policy: ReconnectPolicy,
+    status: watch::Sender<FeedStatus>,
+}
+
+impl<C: WsChannel> WsConnection<C> {
+    /// Engine for `channel` at `endpoint` with the default policy
+    pub fn new(endpoint: &str, channel: C) -> Self {
+        Self {
+            endpoint: endpoint.to_string(),
+            channel,
+            policy: ReconnectPolicy::default(),
+            status: watch::Sender::new(FeedStatus::default()),
+        }
+    }
+
+    /// Set the reconnect policy
+    pub fn with_policy(mut self, policy: ReconnectPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Publish status on an existing channel (owned by the client)
+    pub fn with_status(mut self, status: watch::Sender<FeedStatus>) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Watch connection health
+    pub fn feed_status(&self) -> watch::Receiver<FeedStatus> {
+        self.status.subscribe()
+    }
+
+    /// Run until limit, shutdown, a dropped receiver or the policy gives up
+    ///
+    /// # Arguments
+    /// * `commands` - Optional queue of live commands; queued commands are
+    ///   dropped on reconnect (the subscribe message carries the full state)
+    /// * `recorder` - Optional JSONL sink for raw messages
+    /// * `sender` - Optional channel receiving each parsed message
+    /// * `limit` - Maximum messages to collect (0 = unlimited)
+    /// * `shutdown` - Atomic flag to signal shutdown
+    pub async fn run(
+        &self,
+        mut commands: Option<&mut mpsc::UnboundedReceiver<C::Command>>,
+        mut recorder: Option<JsonlRecorder>,
+        sender: Option<mpsc::Sender<WsInboundMessage>>,
+        limit: u64,
+        shutdown: Arc<AtomicBool>,
+    ) -> AdapterResult<MessageStats> {
+        let name = self.channel.name();
+        let mut stats = MessageStats::new();
+        let mut failures: u32 = 0;
+        let mut reconnect_count: u64 = 0;
+        let mut gave_up = false;
+        // Error that ends the run after the normal disconnect path
+        let mut fatal: Option<AdapterError> = None;
+
+        // Timing for progress logging
+        let start_time = Instant::now();
+        let mut last_progress_log = Instant::now();
+
+        match &recorder {
+            Some(r) => info!("Starting {} channel client, output: {}", name, r.path().display()),
+            None => info!("Starting {} channel client (no recording)", name),
+        }
+
+        'outer: while !shutdown.load(Ordering::Relaxed) {
+            // Queued commands are superseded by the subscribe message
+            if let Some(rx) = commands.as_mut() {
+                while rx.try_recv().is_ok() {}
+            }
+
+            match self.connect_and_subscribe().await {
+                Ok((mut write, mut read)) => {
+                    info!("Connected and subscribed to {} channel", name);
+                    let connected_at = Instant::now();
+                    let conn_id = next_connection_id();
+                    self.status.send_replace(FeedStatus::Up);
+                    if let Some(r) = recorder.as_mut() {
+                        r.set_connection(conn_id, reconnect_count);
+                    }
+                    self.channel.on_connected();
+
+                    let connected = LifecycleEvent::Connected { conn_id, epoch: reconnect_count };
+                    let subscribed =
+                        LifecycleEvent::Subscribed { conn_id, ids: self.channel.subscribed_ids() };
+                    if !emit(&sender, connected).await || !emit(&sender, subscribed).await {
+                        info!("Message receiver dropped, stopping {} client", name);
+                        break 'outer;
+                    }
+
+                    // Application-level PING timer (Polymarket requirement)
+                    let mut ping_interval =
+                        tokio::time::interval(Duration::from_secs(APP_PING_INTERVAL_SECS));
+                    ping_interval.tick().await; // Skip first immediate tick
+                    let mut check_interval = self.channel.check_interval().map(|period| {
+                        tokio::time::interval_at(tokio::time::Instant::now() + period, period)
+                    });
+                    // Timed flush (and age rotation) even while the feed is idle
+                    let mut flush_interval = recorder.as_ref().map(|_| {
+                        let period = Duration::from_secs(FLUSH_EVERY_SECS);
+                        tokio::time::interval_at(tokio::time::Instant::now() + period, period)
+                    });
+
+                    let reason = loop {
+                        if shutdown.load(Ordering::Relaxed) {
+                            break "shutdown".to_string();
+                        }
+
+                        if limit > 0 && stats.total_messages >= limit {
+                            info!("Reached message limit: {}", limit);
+                            break "message limit reached".to_string();
+                        }
+
+                        if last_progress_log.elapsed()
+                            >= Duration::from_secs(LOG_PROGRESS_EVERY_SECS)
+                        {
+                            info!(
+                                "Progress: uptime={}s total={} parsed_ok={} unknown={} reconnects={}",
+                                start_time.elapsed().as_secs(),
+                                stats.total_messages,
+                                stats.parsed_ok,
+                                stats.unknown_type_count,
+                                reconnect_count
+                            );
+                            last_progress_log = Instant::now();
+                        }
+
+                        tokio::select! {
+                            _ = ping_interval.tick() => {
+                                debug!("Sending application-level PING");
+                                if let Err(e) = write.send(Message::Text("PING".into())).await {
+                                    warn!("Failed to send PING: {}", e);
+                                    break format!("PING failed: {}", e);
+                                }
+                            }
+
+                            Some(command) = next_command(&mut commands) => {
+                                let text = match self.channel.command_message(command) {
+                                    Ok(text) => text,
+                                    Err(e) => {
+                                        error!("Failed to encode {} command: {}", name, e);
+                                        let reason = format!("command encode failed: {}", e);
+                                        fatal = Some(e);
+                                        break reason;
+                                    }
+                                };
+                                debug!("Sending {} command: {}", name, text);
+                                if let Err(e) = write.send(Message::Text(text.into())).await {
+                                    // Reconnect sends the full state again
+                                    warn!("Failed to send command: {}", e);
+                                    break format!("command send failed: {}", e);
+                                }
+                            }
+
+                            Some(()) = next_tick(&mut flush_interval) => {
+                                if let Some(r) = recorder.as_mut() {
+                                    if let Err(e) = r.flush().await {
+                                        error!("Failed to flush {} recording: {}", name, e);
+                                        let reason = format!("recording failed: {}", e);
+                                        fatal = Some(e.into());
+                                        break reason;
+                                    }
+                                }
+                            }
+
+                            Some(()) = next_tick(&mut check_interval) => {
+                                let action = match self.channel.check() {
+                                    Ok(action) => action,
+                                    Err(e) => {
+                                        error!("{} channel check failed: {}", name, e);
+                                        let reason = format!("channel check failed: {}", e);
+                                        fatal = Some(e);
+                                        break reason;
+                                    }
+                                };
+                                for event in self.channel.take_events(conn_id) {
+                                    if !emit(&sender, event).await {
+                                        info!("Message receiver dropped, stopping {} client", name);
+                                        break 'outer;
+                                    }
+                                }
+                                match action {
+                                    ChannelAction::None => {}
+                                    ChannelAction::Send(frames) => {
+                                        let mut failed = None;
+                                        for text in frames {
+                                            debug!("Sending {} frame: {}", name, text);
+                                            if let Err(e) = write.send(Message::Text(text.into())).await {
+                                                warn!("Failed to send frame: {}", e);
+                                                failed = Some(format!("frame send failed: {}", e));
+                                                break;
+                                            }
+                                        }
+                                        if let Some(reason) = failed {
+                                            break reason;
+                                        }
+                                    }
+                                    ChannelAction::Reconnect(reason) => {
+                                        warn!("Reconnecting {} channel: {}", name, reason);
+                                        break reason;
+                                    }
+                                }
+                            }
+
+                            msg = read.next() => {
+                                match msg {
+                                    Some(Ok(Message::Text(text))) => {
+                                        let text: &str = text.as_ref();
+                                        if text == "PONG" {
+                                            debug!("Received PONG");
+                                            continue;
+                                        }
+
+                                        if let Some(r) = recorder.as_mut() {
+                                            if let Err(e) = r.write_line(text).await {
+                                                error!("Failed to record {} message: {}", name, e);
+                                                let reason = format!("recording failed: {}", e);
+                                                fatal = Some(e.into());
+                                                break reason;
+                                            }
+                                        }
+
+                                        let parsed = WsInboundMessage::parse(text);
+                                        stats.record(&parsed);
+                                        let resynced = self.channel.on_message(&parsed);
+
+                                        // Deliver to consumer; a dropped receiver stops the client
+                                        let mut delivered = emit_message(&sender, parsed).await;
+                                        let events = resynced
+                                            .into_iter()
+                                            .map(|asset_id| LifecycleEvent::Resynced { conn_id, asset_id })
+                                            .chain(self.channel.take_events(conn_id));
+                                        for event in events {
+                                            if !delivered {
+                                                break;
+                                            }
+                                            delivered = emit(&sender, event).await;
+                                        }
+                                        if !delivered {
+                                            info!("Message receiver dropped, stopping {} client", name);
+                                            break 'outer;
+                                        }
+
+                                        if stats.total_messages.is_multiple_of(100) {
+                                            debug!(
+                                                "Collected {} {} messages, {} unknown",
+                                                stats.total_messages, name, stats.unknown_type_count
+                                            );
+                                        }
+                                    }
+                                    Some(Ok(Message::Ping(data))) => {
+                                        // Respond to WebSocket-level ping (if server sends it)
+                                        if let Err(e) = write.send(Message::Pong(data)).await {
+                                            warn!("Failed to send pong: {}", e);
+                                        }
+                                    }
+                                    Some(Ok(Message::Close(_))) => {
+                                        info!("Server closed connection");
+                                        break "server closed connection".to_string();
+                                    }
+                                    Some(Ok(_)) => {
+                                        // Binary or other message types - ignore
+                                    }
+                                    Some(Err(e)) => {
+                                        warn!("WebSocket error: {}", e);
+                                        break format!("WebSocket error: {}", e);
+                                    }
+                                    None => {
+                                        info!("WebSocket stream ended");
+                                        break "stream ended".to_string();
+                                    }
+                                }
+                            }
+                        }
+                    };
+
+                    let ids = self.channel.subscribed_ids();
+                    let disconnected = LifecycleEvent::Disconnected { conn_id, ids, reason };
+                    let receiver_alive = emit(&sender, disconnected).await;
+
+                    // Flush file before reconnect
+                    if let Some(r) = recorder.as_mut() {
+                        if let Err(e) = r.flush().await {
+                            error!("Failed to flush {} recording: {}", name, e);
+                            fatal.get_or_insert(e.into());
+                        }
+                    }
+
+                    if fatal.is_some() {
+                        break;
+                    }
+                    if !receiver_alive {
+                        info!("Message receiver dropped, stopping {} client", name);
+                        break;
+                    }
+                    if limit > 0 && stats.total_messages >= limit {
+                        break;
+                    }
+
+                    // Only a connection that held counts as a success
+                    if connected_at.elapsed() >= self.policy.reset_after {
+                        failures = 0;
+                    } else {
+                        failures += 1;
+                    }
+                }
+                Err(e) => {
+                    error!("Connection failed: {}", e);
+                    failures += 1;
+                }
+            }
+
+            // Check shutdown before reconnect
+            if shutdown.load(Ordering::Relaxed) {
+                break;
+            }
+
+            let status = self.policy.status_after(failures);
+            self.status.send_replace(status);
+            match status {
+                FeedStatus::GaveUp { failures } => {
+                    error!("{} feed down: giving up after {} failed attempts", name, failures);
+                    if let Some(callback) = &self.policy.on_give_up {
+                        callback(failures);
+                    }
+                    gave_up = true;
+                    break;
+                }
+                FeedStatus::Down { failures } => {
+                    error!("{} feed down: {} failed attempts in a row", name, failures);
+                }
+                _ => {}
+            }
+
+            // Exponential backoff with jitter
+            reconnect_count += 1;
+            let delay = self.policy.delay(failures);
+            warn!("Reconnecting in {:?}... (reconnect #{})", delay, reconnect_count);
+            tokio::time::sleep(delay).await;
+        }
+
+        // Final flush (and manifest / last segment for rotating recorders)
+        let closed = match recorder.take() {
+            Some(r) => r.close().await,
+            None => Ok(()),
+        };
+
+        info!(
+            "{} client stopped. uptime={}s total={} parsed_ok={} unknown={} reconnects={}",
+            name,
+            start_time.elapsed().as_secs(),
+            stats.total_messages,
+            stats.parsed_ok,
+            stats.unknown_type_count,
+            reconnect_count
+        );
+
+        if gave_up {
+            return Err(AdapterError::Connection(format!(
+                "{} feed down after {} failed attempts",
+                name, failures
+            )));
+        }
+        self.status.send_replace(FeedStatus::Stopped);
+        if let Some(e) = fatal {
+            return Err(e);
+        }
+        closed?;
+        Ok(stats)
+    }
+
+    /// Connect and send the channel's subscribe message
+    async fn connect_and_subscribe(&self) -> AdapterResult<(WsWrite, WsRead)> {
+        info!("Connecting to {}", self.endpoint);
+
+        let (ws_stream, response) = match connect_async(&self.endpoint).await {
+            Ok(r) => r,
+            Err(e) => {
+                error!("WebSocket connection error: {:?}", e);
+                return Err(e.into());
+            }
+        };
+
+        debug!("WebSocket connected, status: {}", response.status());
+
+        let (mut write, read) = ws_stream.split();
+        let subscribe = self.channel.subscribe_message()?;
+        write.send(Message::Text(subscribe.into())).await?;
+
+        Ok((write, read))
+    }
+}
+
+/// Deliver a message; false once the receiver is dropped
+async fn emit_message(
+    sender: &Option<mpsc::Sender<WsInboundMessage>>,
+    msg: WsInboundMessage,
+) -> bool {
+    match sender {
+        Some(tx) => tx.send(msg).await.is_ok(),
+        None => true,
+    }
+}
+
+/// Deliver a lifecycle event; false once the receiver is dropped
+async fn emit(sender: &Option<mpsc::Sender<WsInboundMessage>>, event: LifecycleEvent) -> bool {
+    emit_message(sender, WsInboundMessage::Lifecycle(event)).await
+}
+
+/// Next check tick; never resolves without an interval
+async fn next_tick(interval: &mut Option<tokio::time::Interval>) -> Option<()> {
+    match interval {
+        Some(interval) => {
+            interval.tick().await;
+            Some(())
+        }
+        None => std::future::pending().await,
+    }
+}
+
+/// Next queued command; never resolves without a queue
+async fn next_command<T>(commands: &mut Option<&mut mpsc::UnboundedReceiver<T>>) -> Option<T> {
+    match commands {
+        Some(rx) => rx.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::Infallible;
+    use std::sync::atomic::AtomicU32;
+
+    struct TestChannel;
+
+    impl WsChannel for TestChannel {
+        type Command = Infallible;
+
+        fn name(&self) -> &'static str {
+            "test"
+        }
+
+        fn subscribe_message(&self) -> AdapterResult<String> {
+            Ok("{}".to_string())
+        }
+
+        fn command_message(&self, command: Infallible) -> AdapterResult<String> {
+            match command {}
+        }
+    }
+
+    /// Channel whose periodic check always fails
+    struct FailingCheckChannel;
+
+    impl WsChannel for FailingCheckChannel {
+        type Command = Infallible;
+
+        fn name(&self) -> &'static str {
+            "failing"
+        }
+
+        fn subscribe_message(&self) -> AdapterResult<String> {
+            Ok("{}".to_string())
+        }
+
+        fn command_message(&self, command: Infallible) -> AdapterResult<String> {
+            match command {}
+        }
+
+        fn check_interval(&self) -> Option<Duration> {
+            Some(Duration::from_millis(10))
+        }
+
+        fn check(&self) -> AdapterResult<ChannelAction> {
+            Err(AdapterError::Connection("check exploded".to_string()))
+        }
+    }
+
+    #[test]
+    fn test_backoff_and_breaker() {
+        let policy = ReconnectPolicy { jitter: 0.0, ..Default::default() };
+        let delays: Vec<_> = (0..6).map(|n| policy.delay(n).as_secs()).collect();
+        // 1, 2, 4, 8, 16, then circuit open: probe every max
+        assert_eq!(delays, [1, 2, 4, 8, 16, 30]);
+
+        let capped = ReconnectPolicy { breaker_threshold: 100, ..Default::default() };
+        assert_eq!(capped.base_delay(10), Duration::from_secs(30));
+
+        let policy = ReconnectPolicy::default();
+        assert_eq!(policy.jittered(Duration::from_secs(10), 0.0), Duration::from_secs(8));
+        assert_eq!(policy.jittered(Duration::from_secs(10), 0.5), Duration::from_secs(10));
+        for _ in 0..100 {
+            let delay = policy.delay(0);
+            assert!(delay >= Duration::from_millis(800) && delay <= Duration::from_millis(1200));
+        }
+
+        let policy = ReconnectPolicy { max_attempts: Some(7), ..Default::default() };
+        assert_eq!(policy.status_after(4), FeedStatus::Reconnecting { failures: 4 });
+        assert_eq!(policy.status_after(5), FeedStatus::Down { failures: 5 });
+        assert_eq!(policy.status_after(7), FeedStatus::GaveUp { failures: 7 });
+        assert!(policy.status_after(5).is_down() && !FeedStatus::Up.is_down());
+    }
+
+    /// Test: an error inside the loop still emits Disconnected and ends Stopped
+    #[tokio::test]
+    async fn test_loop_error_goes_through_disconnect() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            while let Some(Ok(_)) = ws.next().await {}
+        });
+
+        let connection = WsConnection::new(&format!("ws://{}", addr), FailingCheckChannel);
+        let status = connection.feed_status();
+        let (tx, mut rx) = mpsc::channel(16);
+        let result =
+            connection.run(None, None, Some(tx), 0, Arc::new(AtomicBool::new(false))).await;
+
+        assert!(matches!(result, Err(AdapterError::Connection(_))));
+        assert_eq!(*status.borrow(), FeedStatus::Stopped);
+
+        let mut events = Vec::new();
+        while let Ok(msg) = rx.try_recv() {
+            events.push(msg.lifecycle().cloned().unwrap());
+        }
+        assert_eq!(events.len(), 3);
+        assert!(matches!(
+            &events[2],
+            LifecycleEvent::Disconnected { reason, .. } if reason.starts_with("channel check failed")
+        ));
+    }
+
+    /// Test: unreachable endpoint opens the circuit, then gives up and runs the callback
+    #[tokio::test]
+    async fn test_gives_up_after_max_attempts() {
+        // Reserve a port, then close it so every connect is refused
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        let called = Arc::new(AtomicU32::new(0));
+        let called_clone = called.clone();
+        let policy = ReconnectPolicy {
+            initial: Duration::from_millis(1),
+            max: Duration::from_millis(5),
+            breaker_threshold: 2,
+            max_attempts: Some(3),
+            ..Default::default()
+        }
+        .on_give_up(move |failures| called_clone.store(failures, Ordering::SeqCst));
+
+        let connection =
+            WsConnection::new(&format!("ws://{}", addr), TestChannel).with_policy(policy);
+        let status = connection.feed_status();
+        assert_eq!(*status.borrow(), FeedStatus::Connecting);
+
+        let result = connection.run(None, None, None, 0, Arc::new(AtomicBool::new(false))).await;
+        assert!(matches!(result, Err(AdapterError::Connection(_))));
+        assert_eq!(*status.borrow(), FeedStatus::GaveUp { failures: 3 });
+        assert_eq!(called.load(Ordering::SeqCst), 3);
+    }
+}
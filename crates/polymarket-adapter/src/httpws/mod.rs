@@ -5,11 +5,23 @@
 //! reconnection logic, and message parsing.
 
 pub mod auth;
+pub mod connection;
+pub mod orders;
+pub mod pool;
+pub mod recorder;
 pub mod rest;
+pub mod signer;
+pub mod watchdog;
 pub mod ws_market;
 pub mod ws_user;
 
 pub use auth::*;
+pub use connection::{ChannelAction, FeedStatus, ReconnectPolicy, WsChannel, WsConnection};
+pub use orders::*;
+pub use pool::{MarketWsPool, PoolConfig, ShardInfo};
+pub use recorder::*;
 pub use rest::*;
+pub use signer::WalletSigner;
+pub use watchdog::{FeedWatchdog, StaleAction, StaleEvent, WatchdogConfig};
 pub use ws_market::*;
 pub use ws_user::*;
@@ -0,0 +1,395 @@
+//! Signed order construction for the CLOB exchange contracts
+//!
+//! # Design
+//! - `OrderBuilder` turns `OrderArgs` (decimal price/size strings) into a
+//!   `SignedOrder` ready for `RestClient::post_order`
+//! - Decimal math is done on scaled integers, never floats
+//! - Price is rounded to the market tick size away from the taker's favour:
+//!   BUY rounds down, SELL rounds up, so a rounded order never crosses further
+//!   than requested
+//! - Size is truncated to 2 decimals (the CLOB lot size)
+//! - Amounts are 6-decimal integers: BUY gives `price * size` USDC and receives
+//!   `size` shares; SELL is the reverse
+//!
+//! # Source
+//! - Create order: https://docs.polymarket.com/developers/CLOB/orders/create-order
+//! - Exchange contracts: https://github.com/Polymarket/ctf-exchange
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::error::{AdapterError, AdapterResult};
+use crate::httpws::signer::{
+    domain_separator, encode_address, encode_u256, encode_u256_decimal, keccak256, typed_data_hash,
+    WalletSigner,
+};
+use crate::types::{OrderSide, SignedOrder};
+
+/// CTF exchange contract (Polygon)
+pub const CTF_EXCHANGE: &str = "0x4bFb41d5B3570DeFd03C39a9A4D8dE6Bd8B8982E";
+
+/// Neg-risk CTF exchange contract (Polygon)
+pub const NEG_RISK_CTF_EXCHANGE: &str = "0xC5d563A36AE78145C45a50134d48A1215220f80a";
+
+/// Zero address (public order, any taker)
+pub const ZERO_ADDRESS: &str = "0x0000000000000000000000000000000000000000";
+
+const EXCHANGE_DOMAIN_NAME: &str = "Polymarket CTF Exchange";
+const EXCHANGE_DOMAIN_VERSION: &str = "1";
+const ORDER_TYPE: &str = "Order(uint256 salt,address maker,address signer,address taker,uint256 tokenId,uint256 makerAmount,uint256 takerAmount,uint256 expiration,uint256 nonce,uint256 feeRateBps,uint8 side,uint8 signatureType)";
+
+/// Decimals of on-chain amounts (USDC and outcome tokens)
+const AMOUNT_DECIMALS: u32 = 6;
+
+/// Decimals of order size (lot size 0.01)
+const SIZE_DECIMALS: u32 = 2;
+
+/// Largest tick precision the amount math supports (tick 0.0001)
+const MAX_TICK_DECIMALS: u32 = AMOUNT_DECIMALS - SIZE_DECIMALS;
+
+/// Salts are sent as JSON numbers, so keep them exact in an f64
+const SALT_MASK: u64 = (1 << 53) - 1;
+
+/// Who signs for the funds
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[repr(u8)]
+pub enum SignatureType {
+    /// Plain externally-owned account (maker = signer)
+    #[default]
+    Eoa = 0,
+    /// Polymarket proxy wallet (email/magic login)
+    PolyProxy = 1,
+    /// Gnosis safe (browser wallet login)
+    PolyGnosisSafe = 2,
+}
+
+/// Order parameters before rounding and signing
+#[derive(Clone, Debug)]
+pub struct OrderArgs {
+    /// Outcome token id
+    pub token_id: String,
+    /// Limit price as a decimal string (e.g. "0.55")
+    pub price: String,
+    /// Size in shares as a decimal string (e.g. "10")
+    pub size: String,
+    /// Order side
+    pub side: OrderSide,
+    /// Fee rate in basis points
+    pub fee_rate_bps: u32,
+    /// Exchange nonce (for on-chain cancellation)
+    pub nonce: u64,
+    /// Unix expiration in seconds (0 = none; required for GTD)
+    pub expiration: u64,
+}
+
+impl OrderArgs {
+    /// Limit order with no fee, nonce 0 and no expiration
+    pub fn new(token_id: &str, price: &str, size: &str, side: OrderSide) -> Self {
+        Self {
+            token_id: token_id.to_string(),
+            price: price.to_string(),
+            size: size.to_string(),
+            side,
+            fee_rate_bps: 0,
+            nonce: 0,
+            expiration: 0,
+        }
+    }
+}
+
+/// Builds and signs orders for one wallet
+#[derive(Clone, Debug)]
+pub struct OrderBuilder {
+    signer: WalletSigner,
+    chain_id: u64,
+    signature_type: SignatureType,
+    funder: Option<[u8; 20]>,
+}
+
+impl OrderBuilder {
+    /// EOA builder (the signer holds the funds)
+    pub fn new(signer: WalletSigner, chain_id: u64) -> Self {
+        Self { signer, chain_id, signature_type: SignatureType::Eoa, funder: None }
+    }
+
+    /// Use a proxy or safe wallet that holds the funds
+    pub fn with_funder(mut self, funder: [u8; 20], signature_type: SignatureType) -> Self {
+        self.funder = Some(funder);
+        self.signature_type = signature_type;
+        self
+    }
+
+    /// Signing wallet
+    pub fn signer(&self) -> &WalletSigner {
+        &self.signer
+    }
+
+    /// Round, validate and sign an order
+    ///
+    /// `tick_size` comes from `RestClient::get_tick_size`; `neg_risk` selects
+    /// the exchange contract the order is signed for.
+    pub fn build(
+        &self,
+        args: &OrderArgs,
+        tick_size: &str,
+        neg_risk: bool,
+    ) -> AdapterResult<SignedOrder> {
+        let salt = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default()
+            & SALT_MASK;
+        self.build_with_salt(args, tick_size, neg_risk, salt)
+    }
+
+    /// `build` with a fixed salt (deterministic signatures for tests)
+    pub fn build_with_salt(
+        &self,
+        args: &OrderArgs,
+        tick_size: &str,
+        neg_risk: bool,
+        salt: u64,
+    ) -> AdapterResult<SignedOrder> {
+        let (maker_amount, taker_amount) =
+            order_amounts(&args.price, &args.size, args.side, tick_size)?;
+
+        let signer = *self.signer.address_bytes();
+        let maker = self.funder.unwrap_or(signer);
+        let side = match args.side {
+            OrderSide::Buy => 0u8,
+            OrderSide::Sell => 1u8,
+        };
+
+        let mut encoded = Vec::with_capacity(32 * 13);
+        encoded.extend_from_slice(&keccak256(ORDER_TYPE.as_bytes()));
+        encoded.extend_from_slice(&encode_u256(salt as u128));
+        encoded.extend_from_slice(&encode_address(&maker));
+        encoded.extend_from_slice(&encode_address(&signer));
+        encoded.extend_from_slice(&encode_address(&[0u8; 20]));
+        encoded.extend_from_slice(&encode_u256_decimal(&args.token_id)?);
+        encoded.extend_from_slice(&encode_u256(maker_amount));
+        encoded.extend_from_slice(&encode_u256(taker_amount));
+        encoded.extend_from_slice(&encode_u256(args.expiration as u128));
+        encoded.extend_from_slice(&encode_u256(args.nonce as u128));
+        encoded.extend_from_slice(&encode_u256(args.fee_rate_bps as u128));
+        encoded.extend_from_slice(&encode_u256(side as u128));
+        encoded.extend_from_slice(&encode_u256(self.signature_type as u128));
+
+        let contract = exchange_address(neg_risk)?;
+        let domain = domain_separator(
+            EXCHANGE_DOMAIN_NAME,
+            EXCHANGE_DOMAIN_VERSION,
+            self.chain_id,
+            Some(&contract),
+        );
+        let signature = self.signer.sign_hash(&typed_data_hash(&domain, &keccak256(&encoded)))?;
+
+        Ok(SignedOrder {
+            salt,
+            maker: format!("0x{}", hex::encode(maker)),
+            signer: self.signer.address(),
+            taker: ZERO_ADDRESS.to_string(),
+            token_id: args.token_id.clone(),
+            maker_amount: maker_amount.to_string(),
+            taker_amount: taker_amount.to_string(),
+            expiration: args.expiration.to_string(),
+            nonce: args.nonce.to_string(),
+            fee_rate_bps: args.fee_rate_bps.to_string(),
+            side: args.side,
+            signature_type: self.signature_type as u8,
+            signature,
+        })
+    }
+}
+
+/// Exchange contract the order is signed for
+fn exchange_address(neg_risk: bool) -> AdapterResult<[u8; 20]> {
+    let address = if neg_risk { NEG_RISK_CTF_EXCHANGE } else { CTF_EXCHANGE };
+    crate::httpws::signer::parse_address(address)
+}
+
+/// Parse a non-negative decimal string into (mantissa, scale)
+fn parse_decimal(value: &str) -> AdapterResult<(u128, u32)> {
+    let invalid = || AdapterError::InvalidOrder(format!("Invalid decimal: {:?}", value));
+
+    let value = value.trim();
+    let (int_part, frac_part) = value.split_once('.').unwrap_or((value, ""));
+    if int_part.is_empty() && frac_part.is_empty() {
+        return Err(invalid());
+    }
+    if !int_part.bytes().chain(frac_part.bytes()).all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+
+    let frac_part = frac_part.trim_end_matches('0');
+    let digits = format!("{}{}", int_part, frac_part);
+    let mantissa = if digits.is_empty() { 0 } else { digits.parse().map_err(|_| invalid())? };
+    Ok((mantissa, frac_part.len() as u32))
+}
+
+/// Rescale a decimal to `scale` digits, rounding up or down
+fn rescale(mantissa: u128, from: u32, to: u32, round_up: bool) -> AdapterResult<u128> {
+    let overflow = || AdapterError::InvalidOrder("Decimal out of range".to_string());
+
+    if from <= to {
+        return 10u128
+            .checked_pow(to - from)
+            .and_then(|f| mantissa.checked_mul(f))
+            .ok_or_else(overflow);
+    }
+    let divisor = 10u128.checked_pow(from - to).ok_or_else(overflow)?;
+    let quotient = mantissa / divisor;
+    Ok(if round_up && !mantissa.is_multiple_of(divisor) { quotient + 1 } else { quotient })
+}
+
+/// Round a price to the tick size, returning (price in ticks' units, tick decimals)
+///
+/// BUY rounds down and SELL rounds up. The result must lie in `[tick, 1 - tick]`.
+pub fn round_price(price: &str, side: OrderSide, tick_size: &str) -> AdapterResult<(u128, u32)> {
+    let (tick, tick_decimals) = parse_decimal(tick_size)?;
+    if tick == 0 || tick_decimals > MAX_TICK_DECIMALS {
+        return Err(AdapterError::InvalidOrder(format!("Unsupported tick size {}", tick_size)));
+    }
+
+    let (mantissa, scale) = parse_decimal(price)?;
+    let round_up = side == OrderSide::Sell;
+    let units = rescale(mantissa, scale, tick_decimals, round_up)?;
+    let remainder = units % tick;
+    let units = match remainder {
+        0 => units,
+        _ if round_up => units - remainder + tick,
+        _ => units - remainder,
+    };
+
+    let one = 10u128.pow(tick_decimals);
+    if units < tick || units > one - tick {
+        return Err(AdapterError::InvalidOrder(format!(
+            "Price {} outside [{}, 1 - {}]",
+            price, tick_size, tick_size
+        )));
+    }
+    Ok((units, tick_decimals))
+}
+
+/// Maker and taker amounts (6 decimals) for a price/size pair
+pub fn order_amounts(
+    price: &str,
+    size: &str,
+    side: OrderSide,
+    tick_size: &str,
+) -> AdapterResult<(u128, u128)> {
+    let (price_units, price_decimals) = round_price(price, side, tick_size)?;
+
+    let (mantissa, scale) = parse_decimal(size)?;
+    let size_units = rescale(mantissa, scale, SIZE_DECIMALS, false)?;
+    if size_units == 0 {
+        return Err(AdapterError::InvalidOrder(format!("Size {} below lot size 0.01", size)));
+    }
+
+    let overflow = || AdapterError::InvalidOrder("Order amount out of range".to_string());
+    let shares =
+        size_units.checked_mul(10u128.pow(AMOUNT_DECIMALS - SIZE_DECIMALS)).ok_or_else(overflow)?;
+    let notional = price_units
+        .checked_mul(size_units)
+        .and_then(|n| n.checked_mul(10u128.pow(AMOUNT_DECIMALS - SIZE_DECIMALS - price_decimals)))
+        .ok_or_else(overflow)?;
+
+    Ok(match side {
+        OrderSide::Buy => (notional, shares),
+        OrderSide::Sell => (shares, notional),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Publicly known development key (anvil/hardhat account #0)
+    const TEST_PRIVATE_KEY: &str =
+        "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+    #[test]
+    fn test_parse_decimal() {
+        assert_eq!(parse_decimal("0.55").unwrap(), (55, 2));
+        assert_eq!(parse_decimal("10").unwrap(), (10, 0));
+        assert_eq!(parse_decimal("1.500").unwrap(), (15, 1));
+        assert_eq!(parse_decimal(".5").unwrap(), (5, 1));
+        assert!(parse_decimal("-1").is_err());
+        assert!(parse_decimal("1e3").is_err());
+        assert!(parse_decimal(".").is_err());
+    }
+
+    #[test]
+    fn test_round_price() {
+        assert_eq!(round_price("0.555", OrderSide::Buy, "0.01").unwrap(), (55, 2));
+        assert_eq!(round_price("0.555", OrderSide::Sell, "0.01").unwrap(), (56, 2));
+        assert_eq!(round_price("0.5", OrderSide::Buy, "0.001").unwrap(), (500, 3));
+
+        // Coarser tick than the default precision
+        assert_eq!(round_price("0.52", OrderSide::Buy, "0.05").unwrap(), (50, 2));
+        assert_eq!(round_price("0.52", OrderSide::Sell, "0.05").unwrap(), (55, 2));
+
+        for (price, side) in
+            [("0.001", OrderSide::Buy), ("0.995", OrderSide::Sell), ("1", OrderSide::Buy)]
+        {
+            assert!(matches!(round_price(price, side, "0.01"), Err(AdapterError::InvalidOrder(_))));
+        }
+        assert!(round_price("0.5", OrderSide::Buy, "0.00001").is_err());
+    }
+
+    #[test]
+    fn test_order_amounts() {
+        // BUY 10 @ 0.55: give 5.5 USDC, receive 10 shares
+        assert_eq!(
+            order_amounts("0.55", "10", OrderSide::Buy, "0.01").unwrap(),
+            (5_500_000, 10_000_000)
+        );
+        // SELL 3.219 @ 0.1234 (size truncated to 3.21)
+        assert_eq!(
+            order_amounts("0.1234", "3.219", OrderSide::Sell, "0.0001").unwrap(),
+            (3_210_000, 396_114)
+        );
+        assert!(order_amounts("0.5", "0.009", OrderSide::Buy, "0.01").is_err());
+    }
+
+    #[test]
+    fn test_build_order() {
+        let signer = WalletSigner::from_hex(TEST_PRIVATE_KEY).unwrap();
+        let builder = OrderBuilder::new(signer, crate::POLYGON_CHAIN_ID);
+        let args = OrderArgs::new("1234", "0.555", "10", OrderSide::Buy);
+
+        let order = builder.build_with_salt(&args, "0.01", false, 479249096354).unwrap();
+        assert_eq!(order.maker, "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266");
+        assert_eq!(order.maker, order.signer);
+        assert_eq!(order.taker, ZERO_ADDRESS);
+        assert_eq!(order.maker_amount, "5500000");
+        assert_eq!(order.taker_amount, "10000000");
+        assert_eq!(order.signature_type, 0);
+
+        // Cross-checked against an independent EIP-712 implementation
+        assert_eq!(
+            order.signature,
+            "0x2d9451061bcefac21312d5ba44c75d7cdbf5362f8c050a18a6efc6d5c56f7def\
+             48cfdf15ad1172bcdd76d24d86443c91ecbfbc807dd257036678106c90778f801c"
+        );
+
+        // Deterministic for a fixed salt, and bound to the exchange contract
+        let again = builder.build_with_salt(&args, "0.01", false, 479249096354).unwrap();
+        assert_eq!(order.signature, again.signature);
+        let neg_risk = builder.build_with_salt(&args, "0.01", true, 479249096354).unwrap();
+        assert_ne!(order.signature, neg_risk.signature);
+
+        let json = serde_json::to_value(&order).unwrap();
+        assert_eq!(json["salt"], 479249096354u64);
+        assert_eq!(json["side"], "BUY");
+        assert_eq!(json["tokenId"], "1234");
+        assert_eq!(json["signatureType"], 0);
+
+        let funded = builder
+            .clone()
+            .with_funder([0x11; 20], SignatureType::PolyGnosisSafe)
+            .build_with_salt(&args, "0.01", false, 1)
+            .unwrap();
+        assert_eq!(funded.maker, format!("0x{}", "11".repeat(20)));
+        assert_eq!(funded.signature_type, 2);
+    }
+}
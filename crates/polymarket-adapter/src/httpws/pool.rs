@@ -0,0 +1,611 @@
+//! Sharded market channel connections for large subscriptions
+//!
+//! `MarketWsPool` spreads asset ids over several `MarketWsClient` connections
+//! (shards) of at most `PoolConfig::max_per_connection` assets each and merges
+//! their streams into one `mpsc` channel of `WsInboundMessage`, the same type
+//! a single client produces.
+//!
+//! # Design
+//! - Every asset is owned by exactly one shard; each shard keeps its own
+//!   reconnect loop, circuit breaker and watchdog
+//! - Each shard's messages are forwarded in arrival order, so the merged
+//!   stream keeps per-asset order; messages for an asset the shard no longer
+//!   owns are dropped
+//! - `subscribe` keeps a batch on one shard when it fits (e.g. both outcome
+//!   tokens of a market), filling the least-loaded shard first and opening a
+//!   new shard when all are full
+//! - `unsubscribe` rebalances: while the remaining assets fit in fewer shards,
+//!   the least-loaded shard is closed and its assets move to the others. A
+//!   `LifecycleEvent::Disconnected` for the moved assets precedes their
+//!   subscribe on the new shard, whose snapshot then resyncs them
+//! - Lifecycle events carry the shard's connection id; `Disconnected` lists
+//!   only that shard's assets, so one dropped socket never invalidates books
+//!   fed by the others
+//! - A shard whose client stops on its own (gave up after `max_attempts`, or a
+//!   channel error) announces `Disconnected` for its assets and reopens with
+//!   the same assets after `ReconnectPolicy::max`
+//!
+//! Recording is per connection and not supported by the pool; record with a
+//! single `MarketWsClient` instead.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::httpws::connection::{FeedStatus, ReconnectPolicy};
+use crate::httpws::watchdog::{FeedWatchdog, WatchdogConfig};
+use crate::httpws::ws_market::{MarketSubscriptions, MarketWsClient};
+use crate::types::{LifecycleEvent, WsInboundMessage};
+use crate::CLOB_WSS_ENDPOINT;
+
+/// Pool settings
+#[derive(Clone, Debug)]
+pub struct PoolConfig {
+    /// Maximum assets subscribed on one connection
+    pub max_per_connection: usize,
+    /// Capacity of the merged channel and of each shard's channel
+    pub buffer: usize,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self { max_per_connection: 100, buffer: 1024 }
+    }
+}
+
+/// Snapshot of one shard
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShardInfo {
+    /// Shard id (unique within the pool, never reused)
+    pub id: usize,
+    /// Current connection id (0 before the first connect)
+    pub conn_id: u64,
+    /// Assets subscribed on this shard
+    pub asset_ids: Vec<String>,
+    /// Connection health
+    pub status: FeedStatus,
+}
+
+/// Asset id -> owning shard id
+type Owners = Arc<Mutex<HashMap<String, usize>>>;
+
+/// One `MarketWsClient` kept running by its shard task
+struct Shard {
+    id: usize,
+    subscriptions: MarketSubscriptions,
+    status: watch::Receiver<FeedStatus>,
+    watchdog: FeedWatchdog,
+    conn_id: Arc<AtomicU64>,
+    shutdown: Arc<AtomicBool>,
+    task: JoinHandle<()>,
+}
+
+impl Shard {
+    fn len(&self) -> usize {
+        self.subscriptions.current().len()
+    }
+}
+
+impl Drop for Shard {
+    fn drop(&mut self) {
+        // The client may be parked on a read, so don't wait for it
+        self.shutdown.store(true, Ordering::Relaxed);
+        self.task.abort();
+    }
+}
+
+/// Market channel client sharding its assets over several connections
+///
+/// Dropping the pool closes every connection.
+pub struct MarketWsPool {
+    endpoint: String,
+    config: PoolConfig,
+    enable_features: bool,
+    reconnect: ReconnectPolicy,
+    watchdog: Option<WatchdogConfig>,
+    shards: Vec<Shard>,
+    owners: Owners,
+    next_shard_id: usize,
+    output: mpsc::Sender<WsInboundMessage>,
+}
+
+impl MarketWsPool {
+    /// Create an empty pool and the receiver of its merged stream
+    ///
+    /// Connections open on the first `subscribe`, which must run inside a
+    /// Tokio runtime.
+    pub fn new(config: PoolConfig) -> (Self, mpsc::Receiver<WsInboundMessage>) {
+        Self::with_endpoint(CLOB_WSS_ENDPOINT, config)
+    }
+
+    /// Create with custom endpoint (for testing)
+    pub fn with_endpoint(
+        endpoint: &str,
+        config: PoolConfig,
+    ) -> (Self, mpsc::Receiver<WsInboundMessage>) {
+        let config = PoolConfig {
+            max_per_connection: config.max_per_connection.max(1),
+            buffer: config.buffer.max(1),
+        };
+        let (output, rx) = mpsc::channel(config.buffer);
+        let pool = Self {
+            endpoint: endpoint.to_string(),
+            config,
+            enable_features: true,
+            reconnect: ReconnectPolicy::default(),
+            watchdog: None,
+            shards: Vec::new(),
+            owners: Arc::new(Mutex::new(HashMap::new())),
+            next_shard_id: 0,
+            output,
+        };
+        (pool, rx)
+    }
+
+    /// Enable or disable feature-flagged messages (applies to new shards)
+    pub fn set_enable_features(&mut self, enable: bool) {
+        self.enable_features = enable;
+    }
+
+    /// Set the reconnect policy (applies to new shards)
+    pub fn set_reconnect_policy(&mut self, policy: ReconnectPolicy) {
+        self.reconnect = policy;
+    }
+
+    /// Enable the stale-feed watchdog on every shard
+    pub fn set_watchdog(&mut self, config: WatchdogConfig) {
+        for shard in &self.shards {
+            shard.watchdog.set_config(Some(config.clone()));
+        }
+        self.watchdog = Some(config);
+    }
+
+    /// Add assets (already subscribed ids are ignored)
+    pub fn subscribe(&mut self, asset_ids: Vec<String>) {
+        let added: Vec<String> = {
+            let owners = self.owners.lock().unwrap();
+            let mut added: Vec<String> = Vec::new();
+            for id in asset_ids {
+                if !owners.contains_key(&id) && !added.contains(&id) {
+                    added.push(id);
+                }
+            }
+            added
+        };
+        self.place(added);
+    }
+
+    /// Remove assets (unknown ids are ignored), then consolidate shards
+    pub async fn unsubscribe(&mut self, asset_ids: Vec<String>) {
+        let mut removed: HashMap<usize, Vec<String>> = HashMap::new();
+        {
+            let mut owners = self.owners.lock().unwrap();
+            for id in asset_ids {
+                if let Some(shard_id) = owners.remove(&id) {
+                    removed.entry(shard_id).or_default().push(id);
+                }
+            }
+        }
+        for shard in &self.shards {
+            if let Some(ids) = removed.remove(&shard.id) {
+                shard.subscriptions.unsubscribe(ids);
+            }
+        }
+        self.rebalance().await;
+    }
+
+    /// All subscribed assets, grouped by shard
+    pub fn subscribed(&self) -> Vec<String> {
+        self.shards.iter().flat_map(|s| s.subscriptions.current()).collect()
+    }
+
+    /// Shard owning an asset
+    pub fn shard_of(&self, asset_id: &str) -> Option<usize> {
+        self.owners.lock().unwrap().get(asset_id).copied()
+    }
+
+    /// Current shards, in creation order
+    pub fn shards(&self) -> Vec<ShardInfo> {
+        self.shards
+            .iter()
+            .map(|s| ShardInfo {
+                id: s.id,
+                conn_id: s.conn_id.load(Ordering::Relaxed),
+                asset_ids: s.subscriptions.current(),
+                status: *s.status.borrow(),
+            })
+            .collect()
+    }
+
+    /// Stale assets across all shards, sorted
+    pub fn stale_assets(&self) -> Vec<String> {
+        let mut ids: Vec<String> =
+            self.shards.iter().flat_map(|s| s.watchdog.stale_assets()).collect();
+        ids.sort();
+        ids
+    }
+
+    /// Distribute new assets, opening shards as needed
+    fn place(&mut self, mut ids: Vec<String>) {
+        let max = self.config.max_per_connection;
+        while !ids.is_empty() {
+            // Least-loaded shard with room; it fits the whole batch if any shard does
+            let target = self
+                .shards
+                .iter()
+                .enumerate()
+                .map(|(i, s)| (i, s.len()))
+                .filter(|(_, len)| *len < max)
+                .min_by_key(|(_, len)| *len);
+            let Some((index, len)) = target else {
+                let rest = ids.split_off(ids.len().min(max));
+                self.open_shard(ids);
+                ids = rest;
+                continue;
+            };
+
+            let rest = ids.split_off(ids.len().min(max - len));
+            let shard = &self.shards[index];
+            self.claim(shard.id, &ids);
+            debug!("Shard {}: subscribing {} assets", shard.id, ids.len());
+            shard.subscriptions.subscribe(ids);
+            ids = rest;
+        }
+    }
+
+    /// Record `shard_id` as the owner of `ids`
+    fn claim(&self, shard_id: usize, ids: &[String]) {
+        let mut owners = self.owners.lock().unwrap();
+        for id in ids {
+            owners.insert(id.clone(), shard_id);
+        }
+    }
+
+    /// Close shards while the assets fit in fewer connections
+    async fn rebalance(&mut self) {
+        loop {
+            let total: usize = self.shards.iter().map(Shard::len).sum();
+            let needed = total.div_ceil(self.config.max_per_connection);
+            if self.shards.len() <= needed {
+                return;
+            }
+
+            let index = self
+                .shards
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, s)| s.len())
+                .map(|(i, _)| i)
+                .expect("more shards than needed");
+            let shard = self.shards.remove(index);
+            let moved = shard.subscriptions.current();
+            let conn_id = shard.conn_id.load(Ordering::Relaxed);
+            info!(
+                "Closing shard {} ({} shards left), moving {} assets",
+                shard.id,
+                self.shards.len(),
+                moved.len()
+            );
+
+            // Stop forwarding before announcing the gap, then resubscribe elsewhere
+            self.owners.lock().unwrap().retain(|_, owner| *owner != shard.id);
+            drop(shard);
+            if !moved.is_empty() && conn_id != 0 {
+                let event = LifecycleEvent::Disconnected {
+                    conn_id,
+                    ids: moved.clone(),
+                    reason: "moved to another connection".to_string(),
+                };
+                // A dropped receiver stops the shards on their own
+                let _ = self.output.send(WsInboundMessage::Lifecycle(event)).await;
+            }
+            self.place(moved);
+        }
+    }
+
+    /// Start a new shard subscribed to `ids`
+    fn open_shard(&mut self, ids: Vec<String>) {
+        let id = self.next_shard_id;
+        self.next_shard_id += 1;
+        self.claim(id, &ids);
+
+        info!("Opening shard {} with {} assets", id, ids.len());
+        let mut client = MarketWsClient::with_endpoint(&self.endpoint, ids);
+        client.set_enable_features(self.enable_features);
+        client.set_reconnect_policy(self.reconnect.clone());
+        if let Some(config) = &self.watchdog {
+            client.set_watchdog(config.clone());
+        }
+        let subscriptions = client.subscriptions();
+        let status = client.feed_status();
+        let watchdog = client.watchdog();
+
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let conn_id = Arc::new(AtomicU64::new(0));
+        let task = ShardTask {
+            id,
+            buffer: self.config.buffer,
+            reopen_after: self.reconnect.max,
+            output: self.output.clone(),
+            owners: self.owners.clone(),
+            conn_id: conn_id.clone(),
+            shutdown: shutdown.clone(),
+        };
+        let task = tokio::spawn(task.run(client));
+
+        self.shards.push(Shard { id, subscriptions, status, watchdog, conn_id, shutdown, task });
+    }
+}
+
+/// State shared by a shard's task across client restarts
+struct ShardTask {
+    id: usize,
+    buffer: usize,
+    /// Wait before reopening a client that stopped on its own
+    reopen_after: Duration,
+    output: mpsc::Sender<WsInboundMessage>,
+    owners: Owners,
+    conn_id: Arc<AtomicU64>,
+    shutdown: Arc<AtomicBool>,
+}
+
+impl ShardTask {
+    /// Run the client, reopening it whenever it stops on its own
+    async fn run(self, client: MarketWsClient) {
+        loop {
+            let (tx, rx) = mpsc::channel(self.buffer);
+            let (result, receiver_alive) = tokio::join!(
+                client.run_with(None, Some(tx), 0, self.shutdown.clone()),
+                self.forward(rx),
+            );
+            if self.shutdown.load(Ordering::Relaxed) || !receiver_alive {
+                return;
+            }
+
+            let reason = match result {
+                Ok(_) => "client stopped".to_string(),
+                Err(e) => format!("client stopped: {}", e),
+            };
+            warn!("Shard {} {}, reopening in {:?}", self.id, reason, self.reopen_after);
+            let conn_id = self.conn_id.load(Ordering::Relaxed);
+            let ids = client.subscriptions().current();
+            if conn_id != 0 && !ids.is_empty() {
+                let event = LifecycleEvent::Disconnected { conn_id, ids, reason };
+                if self.output.send(WsInboundMessage::Lifecycle(event)).await.is_err() {
+                    return;
+                }
+            }
+            tokio::time::sleep(self.reopen_after).await;
+        }
+    }
+
+    /// Forward one client run into the merged output
+    ///
+    /// Returns false once the pool receiver is dropped.
+    async fn forward(&self, mut rx: mpsc::Receiver<WsInboundMessage>) -> bool {
+        while let Some(msg) = rx.recv().await {
+            if let Some(LifecycleEvent::Connected { conn_id, .. }) = msg.lifecycle() {
+                self.conn_id.store(*conn_id, Ordering::Relaxed);
+            }
+
+            // Trailing messages for assets moved or removed since
+            let ids = msg.asset_ids();
+            if !ids.is_empty() {
+                let owners = self.owners.lock().unwrap();
+                if !ids.iter().any(|id| owners.get(*id) == Some(&self.id)) {
+                    continue;
+                }
+            }
+
+            if self.output.send(msg).await.is_err() {
+                debug!("Pool receiver dropped, stopping shard {}", self.id);
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::{SinkExt, StreamExt};
+    use std::net::SocketAddr;
+    use std::time::Duration;
+    use tokio_tungstenite::tungstenite::Message;
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|s| s.to_string()).collect()
+    }
+
+    /// Market channel server answering every subscribe with one book per asset
+    ///
+    /// With `close_first`, the first connection is closed after its books.
+    async fn spawn_server(close_first: bool) -> SocketAddr {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut accepted = 0;
+            while let Ok((stream, _)) = listener.accept().await {
+                accepted += 1;
+                let close = close_first && accepted == 1;
+                tokio::spawn(async move {
+                    let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+                    while let Some(Ok(msg)) = ws.next().await {
+                        let Ok(request) = serde_json::from_str::<serde_json::Value>(
+                            msg.to_text().unwrap_or_default(),
+                        ) else {
+                            continue;
+                        };
+                        let assets = match request.get("operation").and_then(|v| v.as_str()) {
+                            None => request.get("assets_ids"),
+                            Some("subscribe") => request.get("asset_ids"),
+                            Some(_) => None,
+                        };
+                        let assets: Vec<String> = assets
+                            .cloned()
+                            .map(serde_json::from_value)
+                            .transpose()
+                            .unwrap()
+                            .unwrap_or_default();
+                        for id in assets {
+                            let book = format!(
+                                r#"{{"event_type":"book","asset_id":"{}","market":"cond","timestamp":"1","bids":[],"asks":[]}}"#,
+                                id
+                            );
+                            if ws.send(Message::Text(book.into())).await.is_err() {
+                                return;
+                            }
+                        }
+                        if close {
+                            let _ = ws.close(None).await;
+                            return;
+                        }
+                    }
+                });
+            }
+        });
+        addr
+    }
+
+    async fn recv(rx: &mut mpsc::Receiver<WsInboundMessage>) -> WsInboundMessage {
+        tokio::time::timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .expect("timed out waiting for message")
+            .expect("stream ended early")
+    }
+
+    fn layout(pool: &MarketWsPool) -> Vec<Vec<String>> {
+        pool.shards().into_iter().map(|s| s.asset_ids).collect()
+    }
+
+    /// Test: assets are sharded, merged into one stream and consolidated on unsubscribe
+    #[tokio::test]
+    async fn test_shards_merge_and_rebalance() {
+        let addr = spawn_server(false).await;
+        let (mut pool, mut rx) = MarketWsPool::with_endpoint(
+            &format!("ws://{}", addr),
+            PoolConfig { max_per_connection: 2, buffer: 64 },
+        );
+
+        pool.subscribe(ids(&["a", "b", "c"]));
+        pool.subscribe(ids(&["d", "a"]));
+        pool.subscribe(ids(&["e", "f"]));
+        assert_eq!(layout(&pool), [ids(&["a", "b"]), ids(&["c", "d"]), ids(&["e", "f"])]);
+
+        // Every asset resyncs on the connection of its own shard
+        let mut resynced: HashMap<String, u64> = HashMap::new();
+        while resynced.len() < 6 {
+            if let Some(LifecycleEvent::Resynced { conn_id, asset_id }) =
+                recv(&mut rx).await.lifecycle()
+            {
+                resynced.insert(asset_id.clone(), *conn_id);
+            }
+        }
+        let shards = pool.shards();
+        for shard in &shards {
+            assert_ne!(shard.conn_id, 0);
+            for id in &shard.asset_ids {
+                assert_eq!(resynced[id], shard.conn_id, "asset {}", id);
+            }
+        }
+
+        // The emptied shard is closed
+        pool.unsubscribe(ids(&["a", "e", "f"])).await;
+        assert_eq!(layout(&pool), [ids(&["b"]), ids(&["c", "d"])]);
+
+        // Everything fits on one connection: "b" moves to shard 1
+        pool.unsubscribe(ids(&["c"])).await;
+        let remaining = pool.shards();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].id, 1);
+        assert_eq!(remaining[0].asset_ids, ids(&["d", "b"]));
+        assert_eq!(pool.shard_of("b"), Some(1));
+        assert_eq!(pool.shard_of("a"), None);
+
+        // Gap announced for the old connection, then resync on the new one
+        loop {
+            let msg = recv(&mut rx).await;
+            if let Some(LifecycleEvent::Disconnected { conn_id, ids: moved, reason }) =
+                msg.lifecycle()
+            {
+                assert_eq!(*conn_id, shards[0].conn_id);
+                assert_eq!(moved, &ids(&["b"]));
+                assert_eq!(reason, "moved to another connection");
+                break;
+            }
+        }
+        loop {
+            let msg = recv(&mut rx).await;
+            assert_ne!(msg.asset_id(), Some("a"));
+            if let Some(LifecycleEvent::Resynced { conn_id, asset_id }) = msg.lifecycle() {
+                assert_eq!(asset_id, "b");
+                assert_eq!(*conn_id, remaining[0].conn_id);
+                break;
+            }
+        }
+    }
+
+    /// Test: a shard whose client gives up announces the gap and reopens
+    #[tokio::test]
+    async fn test_shard_reopens_after_give_up() {
+        let addr = spawn_server(true).await;
+        let (mut pool, mut rx) = MarketWsPool::with_endpoint(
+            &format!("ws://{}", addr),
+            PoolConfig { max_per_connection: 2, buffer: 64 },
+        );
+        let mut policy = ReconnectPolicy::default();
+        policy.initial = Duration::from_millis(10);
+        policy.max = Duration::from_millis(50);
+        policy.max_attempts = Some(1);
+        pool.set_reconnect_policy(policy);
+        pool.subscribe(ids(&["a"]));
+
+        let mut lifecycle = Vec::new();
+        while lifecycle.len() < 7 {
+            if let Some(event) = recv(&mut rx).await.lifecycle() {
+                lifecycle.push(event.clone());
+            }
+        }
+        let kinds: Vec<&str> = lifecycle.iter().map(LifecycleEvent::as_str).collect();
+        assert_eq!(
+            kinds,
+            [
+                "connected",
+                "subscribed",
+                "resynced",
+                "disconnected",
+                "disconnected",
+                "connected",
+                "subscribed"
+            ]
+        );
+        let first = lifecycle[0].conn_id();
+        assert!(matches!(
+            &lifecycle[4],
+            LifecycleEvent::Disconnected { conn_id, ids: gone, reason }
+                if *conn_id == first && gone == &ids(&["a"]) && reason.contains("failed attempts")
+        ));
+        assert_ne!(lifecycle[5].conn_id(), first);
+
+        // Same shard, new connection
+        loop {
+            if let Some(LifecycleEvent::Resynced { conn_id, asset_id }) =
+                recv(&mut rx).await.lifecycle()
+            {
+                assert_eq!(asset_id, "a");
+                assert_ne!(*conn_id, first);
+                break;
+            }
+        }
+        let shards = pool.shards();
+        assert_eq!(shards.len(), 1);
+        assert_eq!(shards[0].id, 0);
+        assert_eq!(shards[0].status, FeedStatus::Up);
+    }
+}
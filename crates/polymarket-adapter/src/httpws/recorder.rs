@@ -0,0 +1,692 @@
+//! JSONL recording sink for WebSocket clients
+//!
+//! Writes each raw text frame as one line, flushing every `FLUSH_EVERY_MSGS`
+//! messages or `FLUSH_EVERY_SECS` seconds, whichever comes first. The clients
+//! also call `flush` every `FLUSH_EVERY_SECS` on a timer, so a quiet feed is
+//! flushed too.
+//!
+//! # Formats
+//! - `Raw`: the frame verbatim (default)
+//! - `Envelope`: `RecordEnvelope` JSON with local receive time (unix ns),
+//!   connection id and reconnect epoch around the frame
+//!
+//! `replay::RecordReader` reads both formats.
+//!
+//! # Rotation
+//! `create_rotating` writes a session of segment files into a directory
+//! instead of one ever-growing file:
+//! - `<prefix>_<ts>.<seq>.jsonl`, new segment after `max_age` and/or `max_bytes`;
+//!   age is checked on every write and flush, so an idle feed still rotates
+//! - Closed segments are compressed in the background (gzip / zstd) and the
+//!   uncompressed file removed
+//! - `<prefix>_<ts>.manifest.json` (`SessionManifest`) lists the segments and
+//!   is rewritten on every rotation; `close` compresses the last segment and
+//!   records the session end
+//! - A session started in the same second as an existing one in the directory
+//!   gets a `-<n>` suffix (`<prefix>_<ts>-2`), so sessions never overwrite each other
+//!
+//! `replay::ReplaySource::from_manifest` replays a whole session.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use async_compression::tokio::write::{GzipEncoder, ZstdEncoder};
+use chrono::Utc;
+use tokio::fs::File;
+use tokio::io::AsyncWriteExt;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::httpws::ws_market::generate_output_filename;
+use crate::types::{RecordEnvelope, SegmentInfo, SessionManifest};
+
+/// Flush file every N messages
+const FLUSH_EVERY_MSGS: u64 = 200;
+
+/// Flush file at least every N seconds
+pub(crate) const FLUSH_EVERY_SECS: u64 = 5;
+
+/// Process-wide WebSocket connection id source
+static NEXT_CONNECTION_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Allocate a connection id (unique within the process)
+pub fn next_connection_id() -> u64 {
+    NEXT_CONNECTION_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Line format of a recording
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RecordFormat {
+    /// Frame text verbatim
+    #[default]
+    Raw,
+    /// `RecordEnvelope` with receive metadata
+    Envelope,
+}
+
+impl RecordFormat {
+    /// Name used in manifests
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RecordFormat::Raw => "raw",
+            RecordFormat::Envelope => "envelope",
+        }
+    }
+}
+
+/// Compression of closed segments
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Compression {
+    #[default]
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    /// File extension appended to compressed segments
+    pub fn extension(&self) -> Option<&'static str> {
+        match self {
+            Compression::None => None,
+            Compression::Gzip => Some("gz"),
+            Compression::Zstd => Some("zst"),
+        }
+    }
+
+    /// Parse from a CLI value ("none", "gzip"/"gz", "zstd"/"zst")
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "none" => Some(Compression::None),
+            "gzip" | "gz" => Some(Compression::Gzip),
+            "zstd" | "zst" => Some(Compression::Zstd),
+            _ => None,
+        }
+    }
+}
+
+/// Segment rotation settings
+#[derive(Clone, Debug, Default)]
+pub struct RotationConfig {
+    /// Start a new segment once the current one is this old (None = never)
+    pub max_age: Option<Duration>,
+    /// Start a new segment once the current one reaches this many bytes (None = unlimited)
+    pub max_bytes: Option<u64>,
+    /// Compression applied to closed segments
+    pub compression: Compression,
+}
+
+impl RotationConfig {
+    /// One segment per hour
+    pub fn hourly() -> Self {
+        Self { max_age: Some(Duration::from_secs(3600)), ..Default::default() }
+    }
+}
+
+/// Rotation state of a session
+struct Rotation {
+    dir: PathBuf,
+    prefix: String,
+    config: RotationConfig,
+    manifest: SessionManifest,
+    manifest_path: PathBuf,
+    opened_at: Instant,
+    segment_lines: u64,
+    segment_bytes: u64,
+    /// Background compressions: (segment index, compressed path)
+    pending: Vec<JoinHandle<(usize, io::Result<PathBuf>)>>,
+}
+
+/// Raw JSONL recorder
+pub struct JsonlRecorder {
+    file: File,
+    path: PathBuf,
+    format: RecordFormat,
+    conn_id: u64,
+    epoch: u64,
+    lines_written: u64,
+    last_flush: Instant,
+    last_flush_count: u64,
+    rotation: Option<Rotation>,
+}
+
+impl JsonlRecorder {
+    /// Create (or truncate) the output file
+    pub async fn create(path: &Path) -> io::Result<Self> {
+        Self::create_with_format(path, RecordFormat::Raw).await
+    }
+
+    /// Create (or truncate) the output file with a line format
+    pub async fn create_with_format(path: &Path, format: RecordFormat) -> io::Result<Self> {
+        let file = File::create(path).await.map_err(|e| {
+            io::Error::new(e.kind(), format!("Failed to create {}: {}", path.display(), e))
+        })?;
+        Ok(Self {
+            file,
+            path: path.to_path_buf(),
+            format,
+            conn_id: 0,
+            epoch: 0,
+            lines_written: 0,
+            last_flush: Instant::now(),
+            last_flush_count: 0,
+            rotation: None,
+        })
+    }
+
+    /// Start a rotating session in `dir` (created if missing)
+    pub async fn create_rotating(
+        dir: &Path,
+        prefix: &str,
+        format: RecordFormat,
+        config: RotationConfig,
+    ) -> io::Result<Self> {
+        tokio::fs::create_dir_all(dir).await?;
+        let (session, manifest_path) = reserve_session(dir, prefix).await?;
+        let manifest = SessionManifest {
+            session: session.clone(),
+            format: format.as_str().to_string(),
+            started_at: Utc::now().to_rfc3339(),
+            ended_at: None,
+            segments: Vec::new(),
+        };
+        info!("Recording session {} in {}", session, dir.display());
+
+        let path = dir.join(format!("{}.{:04}.jsonl", session, 1));
+        let mut recorder = Self::create_with_format(&path, format).await?;
+        recorder.rotation = Some(Rotation {
+            dir: dir.to_path_buf(),
+            prefix: session,
+            config,
+            manifest,
+            manifest_path,
+            opened_at: Instant::now(),
+            segment_lines: 0,
+            segment_bytes: 0,
+            pending: Vec::new(),
+        });
+        recorder.open_segment_entry();
+        recorder.write_manifest().await?;
+        Ok(recorder)
+    }
+
+    /// Set the connection the following lines were received on (called by the clients on connect)
+    pub fn set_connection(&mut self, conn_id: u64, epoch: u64) {
+        self.conn_id = conn_id;
+        self.epoch = epoch;
+    }
+
+    /// Write one raw message as a line, flushing periodically
+    ///
+    /// In `Envelope` format the receive time is taken now, so call this as
+    /// soon as the frame arrives.
+    pub async fn write_line(&mut self, text: &str) -> io::Result<()> {
+        let recv_ts_ns = unix_nanos();
+        if self.should_rotate() {
+            self.rotate().await?;
+        }
+
+        let bytes = match self.format {
+            RecordFormat::Raw => {
+                self.file.write_all(text.as_bytes()).await?;
+                text.len()
+            }
+            RecordFormat::Envelope => {
+                let envelope = RecordEnvelope {
+                    recv_ts_ns,
+                    conn_id: self.conn_id,
+                    epoch: self.epoch,
+                    raw: text.to_string(),
+                };
+                let line = serde_json::to_string(&envelope).map_err(io::Error::other)?;
+                self.file.write_all(line.as_bytes()).await?;
+                line.len()
+            }
+        };
+        self.file.write_all(b"\n").await?;
+        self.lines_written += 1;
+        if let Some(rotation) = self.rotation.as_mut() {
+            rotation.segment_lines += 1;
+            rotation.segment_bytes += bytes as u64 + 1;
+        }
+
+        // Periodic flush: every N messages or every T seconds
+        let lines_since_flush = self.lines_written - self.last_flush_count;
+        if lines_since_flush >= FLUSH_EVERY_MSGS
+            || self.last_flush.elapsed() >= Duration::from_secs(FLUSH_EVERY_SECS)
+        {
+            self.flush().await?;
+        }
+
+        Ok(())
+    }
+
+    /// Flush buffered data to disk, rotating first if the segment is due
+    pub async fn flush(&mut self) -> io::Result<()> {
+        if self.should_rotate() {
+            self.rotate().await?;
+        }
+        self.file.flush().await?;
+        self.last_flush = Instant::now();
+        self.last_flush_count = self.lines_written;
+        if self.rotation.is_some() {
+            self.reap_compressions(false).await;
+            self.write_manifest().await?;
+        }
+        Ok(())
+    }
+
+    /// Flush and finish the recording
+    ///
+    /// For a rotating session this also compresses the last segment, waits for
+    /// all compressions and writes the final manifest.
+    pub async fn close(mut self) -> io::Result<()> {
+        self.file.flush().await?;
+        if self.rotation.is_none() {
+            return Ok(());
+        }
+        self.close_segment();
+        self.reap_compressions(true).await;
+        if let Some(rotation) = self.rotation.as_mut() {
+            rotation.manifest.ended_at = Some(Utc::now().to_rfc3339());
+        }
+        self.write_manifest().await
+    }
+
+    /// Session manifest (rotating recorders only)
+    pub fn manifest(&self) -> Option<&SessionManifest> {
+        self.rotation.as_ref().map(|r| &r.manifest)
+    }
+
+    /// Manifest path (rotating recorders only)
+    pub fn manifest_path(&self) -> Option<&Path> {
+        self.rotation.as_ref().map(|r| r.manifest_path.as_path())
+    }
+
+    fn should_rotate(&self) -> bool {
+        let Some(rotation) = &self.rotation else {
+            return false;
+        };
+        rotation.segment_lines > 0
+            && (rotation.config.max_age.is_some_and(|age| rotation.opened_at.elapsed() >= age)
+                || rotation.config.max_bytes.is_some_and(|max| rotation.segment_bytes >= max))
+    }
+
+    /// Close the current segment and open the next one
+    async fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush().await?;
+        self.close_segment();
+
+        let Some(rotation) = self.rotation.as_mut() else {
+            return Ok(());
+        };
+        let seq = rotation.manifest.segments.len() + 1;
+        let path = rotation.dir.join(format!("{}.{:04}.jsonl", rotation.prefix, seq));
+        self.file = File::create(&path).await.map_err(|e| {
+            io::Error::new(e.kind(), format!("Failed to create {}: {}", path.display(), e))
+        })?;
+        info!("Rotated recording to {}", path.display());
+        self.path = path;
+        rotation.opened_at = Instant::now();
+        rotation.segment_lines = 0;
+        rotation.segment_bytes = 0;
+        self.open_segment_entry();
+
+        self.reap_compressions(false).await;
+        self.write_manifest().await
+    }
+
+    fn open_segment_entry(&mut self) {
+        let file = file_name(&self.path);
+        if let Some(rotation) = self.rotation.as_mut() {
+            rotation.manifest.segments.push(SegmentInfo {
+                file,
+                opened_at: Utc::now().to_rfc3339(),
+                ..Default::default()
+            });
+        }
+    }
+
+    /// Record the current segment as closed and start its compression
+    fn close_segment(&mut self) {
+        let Some(rotation) = self.rotation.as_mut() else {
+            return;
+        };
+        let index = rotation.manifest.segments.len() - 1;
+        let segment = &mut rotation.manifest.segments[index];
+        segment.closed_at = Some(Utc::now().to_rfc3339());
+        segment.lines = rotation.segment_lines;
+        segment.bytes = rotation.segment_bytes;
+
+        let compression = rotation.config.compression;
+        if compression != Compression::None {
+            let path = self.path.clone();
+            rotation.pending.push(tokio::spawn(async move {
+                (index, compress_file(&path, compression).await)
+            }));
+        }
+    }
+
+    /// Apply finished compressions to the manifest (all of them if `wait`)
+    async fn reap_compressions(&mut self, wait: bool) {
+        let Some(rotation) = self.rotation.as_mut() else {
+            return;
+        };
+        let mut still_pending = Vec::new();
+        for handle in std::mem::take(&mut rotation.pending) {
+            if !wait && !handle.is_finished() {
+                still_pending.push(handle);
+                continue;
+            }
+            match handle.await {
+                Ok((index, Ok(path))) => rotation.manifest.segments[index].file = file_name(&path),
+                Ok((index, Err(e))) => {
+                    let file = &rotation.manifest.segments[index].file;
+                    warn!("Failed to compress {}: {} (kept uncompressed)", file, e);
+                }
+                Err(e) => warn!("Compression task failed: {}", e),
+            }
+        }
+        rotation.pending = still_pending;
+    }
+
+    /// Rewrite the manifest atomically (temp file + rename)
+    async fn write_manifest(&mut self) -> io::Result<()> {
+        let Some(rotation) = self.rotation.as_mut() else {
+            return Ok(());
+        };
+        if let Some(segment) = rotation.manifest.segments.last_mut() {
+            if segment.closed_at.is_none() {
+                segment.lines = rotation.segment_lines;
+                segment.bytes = rotation.segment_bytes;
+            }
+        }
+        let json = serde_json::to_vec_pretty(&rotation.manifest).map_err(io::Error::other)?;
+        let tmp = rotation.manifest_path.with_extension("json.tmp");
+        tokio::fs::write(&tmp, json).await?;
+        tokio::fs::rename(&tmp, &rotation.manifest_path).await
+    }
+
+    /// Line format
+    pub fn format(&self) -> RecordFormat {
+        self.format
+    }
+
+    /// Output file path
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Total lines written
+    pub fn lines_written(&self) -> u64 {
+        self.lines_written
+    }
+}
+
+/// Claim an unused session name by creating its manifest exclusively
+async fn reserve_session(dir: &Path, prefix: &str) -> io::Result<(String, PathBuf)> {
+    let base = generate_output_filename(prefix, "manifest.json");
+    let base = base.trim_end_matches(".manifest.json");
+    for n in 1u32.. {
+        let session = if n == 1 { base.to_string() } else { format!("{}-{}", base, n) };
+        let path = dir.join(format!("{}.manifest.json", session));
+        match tokio::fs::OpenOptions::new().write(true).create_new(true).open(&path).await {
+            Ok(_) => return Ok((session, path)),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => {
+                return Err(io::Error::new(
+                    e.kind(),
+                    format!("Failed to create {}: {}", path.display(), e),
+                ))
+            }
+        }
+    }
+    unreachable!("session suffixes exhausted")
+}
+
+/// Compress a closed segment next to it and remove the original
+async fn compress_file(path: &Path, compression: Compression) -> io::Result<PathBuf> {
+    let Some(extension) = compression.extension() else {
+        return Ok(path.to_path_buf());
+    };
+    let out_path = PathBuf::from(format!("{}.{}", path.display(), extension));
+    let mut input = File::open(path).await?;
+    let output = File::create(&out_path).await?;
+
+    match compression {
+        Compression::Gzip => {
+            let mut encoder = GzipEncoder::new(output);
+            tokio::io::copy(&mut input, &mut encoder).await?;
+            encoder.shutdown().await?;
+        }
+        Compression::Zstd => {
+            let mut encoder = ZstdEncoder::new(output);
+            tokio::io::copy(&mut input, &mut encoder).await?;
+            encoder.shutdown().await?;
+        }
+        Compression::None => unreachable!("no extension for uncompressed segments"),
+    }
+
+    tokio::fs::remove_file(path).await?;
+    Ok(out_path)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default()
+}
+
+/// Current wall clock in unix nanoseconds
+fn unix_nanos() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos() as u64).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_write_and_flush() {
+        let path = std::env::temp_dir().join(format!("recorder_test_{}.jsonl", std::process::id()));
+
+        let mut recorder = JsonlRecorder::create(&path).await.unwrap();
+        recorder.write_line(r#"{"event_type":"book"}"#).await.unwrap();
+        recorder.write_line(r#"{"event_type":"price_change"}"#).await.unwrap();
+        recorder.flush().await.unwrap();
+        assert_eq!(recorder.lines_written(), 2);
+
+        let content = tokio::fs::read_to_string(&path).await.unwrap();
+        assert_eq!(content.lines().count(), 2);
+        assert!(content.ends_with('\n'));
+
+        tokio::fs::remove_file(&path).await.ok();
+    }
+
+    #[tokio::test]
+    async fn test_envelope_format() {
+        let path =
+            std::env::temp_dir().join(format!("recorder_envelope_{}.jsonl", std::process::id()));
+
+        let mut recorder =
+            JsonlRecorder::create_with_format(&path, RecordFormat::Envelope).await.unwrap();
+        let conn_id = next_connection_id();
+        recorder.set_connection(conn_id, 2);
+        recorder.write_line(r#"{"event_type":"book"}"#).await.unwrap();
+        recorder.flush().await.unwrap();
+
+        let content = tokio::fs::read_to_string(&path).await.unwrap();
+        let envelope = RecordEnvelope::parse_line(content.trim_end()).unwrap();
+        assert_eq!(envelope.raw, r#"{"event_type":"book"}"#);
+        assert_eq!((envelope.conn_id, envelope.epoch), (conn_id, 2));
+        assert!(envelope.recv_ts_ns > 1_600_000_000_000_000_000);
+        assert!(next_connection_id() > conn_id);
+
+        tokio::fs::remove_file(&path).await.ok();
+    }
+
+    #[tokio::test]
+    async fn test_rotation_by_size_with_gzip() {
+        use async_compression::tokio::bufread::GzipDecoder;
+        use tokio::io::{AsyncReadExt, BufReader};
+
+        let dir = std::env::temp_dir().join(format!("recorder_rotate_{}", std::process::id()));
+        let config = RotationConfig {
+            max_bytes: Some(40),
+            compression: Compression::Gzip,
+            ..Default::default()
+        };
+        let mut recorder =
+            JsonlRecorder::create_rotating(&dir, "ws_market", RecordFormat::Raw, config)
+                .await
+                .unwrap();
+        let manifest_path = recorder.manifest_path().unwrap().to_path_buf();
+
+        // 23 bytes per line -> two lines per segment
+        for i in 0..5 {
+            recorder.write_line(&format!(r#"{{"event_type":"b{:04}"}}"#, i)).await.unwrap();
+        }
+        assert_eq!(recorder.lines_written(), 5);
+        recorder.close().await.unwrap();
+
+        let manifest: SessionManifest =
+            serde_json::from_slice(&tokio::fs::read(&manifest_path).await.unwrap()).unwrap();
+        assert_eq!(manifest.format, "raw");
+        assert!(manifest.session.starts_with("ws_market_"));
+        assert!(manifest.ended_at.is_some());
+        assert_eq!(manifest.segments.len(), 3);
+        assert_eq!(manifest.segments.iter().map(|s| s.lines).collect::<Vec<_>>(), vec![2, 2, 1]);
+
+        let mut lines = Vec::new();
+        for (i, segment) in manifest.segments.iter().enumerate() {
+            assert!(segment.file.ends_with(&format!(".{:04}.jsonl.gz", i + 1)));
+            assert!(segment.closed_at.is_some());
+            let plain = dir.join(segment.file.trim_end_matches(".gz"));
+            assert!(!plain.exists(), "uncompressed segment should be removed");
+
+            let file = File::open(dir.join(&segment.file)).await.unwrap();
+            let mut decoder = GzipDecoder::new(BufReader::new(file));
+            let mut text = String::new();
+            decoder.read_to_string(&mut text).await.unwrap();
+            assert_eq!(text.len() as u64, segment.bytes);
+            lines.extend(text.lines().map(str::to_string));
+        }
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[4], r#"{"event_type":"b0004"}"#);
+
+        tokio::fs::remove_dir_all(&dir).await.ok();
+    }
+
+    #[tokio::test]
+    async fn test_rotation_manifest_tracks_open_segment() {
+        let dir = std::env::temp_dir().join(format!("recorder_manifest_{}", std::process::id()));
+        let mut recorder = JsonlRecorder::create_rotating(
+            &dir,
+            "ws_user",
+            RecordFormat::Envelope,
+            RotationConfig::hourly(),
+        )
+        .await
+        .unwrap();
+        recorder.write_line(r#"{"event_type":"order"}"#).await.unwrap();
+        recorder.flush().await.unwrap();
+
+        let path = recorder.manifest_path().unwrap().to_path_buf();
+        let manifest: SessionManifest =
+            serde_json::from_slice(&tokio::fs::read(&path).await.unwrap()).unwrap();
+        assert_eq!(manifest.format, "envelope");
+        assert!(manifest.ended_at.is_none());
+        assert_eq!(manifest.segments.len(), 1);
+        assert_eq!(manifest.segments[0].lines, 1);
+        assert!(manifest.segments[0].closed_at.is_none());
+        assert_eq!(recorder.manifest(), Some(&manifest));
+
+        recorder.close().await.unwrap();
+        let manifest: SessionManifest =
+            serde_json::from_slice(&tokio::fs::read(&path).await.unwrap()).unwrap();
+        assert!(manifest.segments[0].file.ends_with(".0001.jsonl"));
+        assert!(dir.join(&manifest.segments[0].file).exists());
+
+        tokio::fs::remove_dir_all(&dir).await.ok();
+    }
+
+    #[tokio::test]
+    async fn test_sessions_in_same_second_do_not_collide() {
+        let dir = std::env::temp_dir().join(format!("recorder_sessions_{}", std::process::id()));
+        let create = || {
+            JsonlRecorder::create_rotating(
+                &dir,
+                "ws_market",
+                RecordFormat::Raw,
+                RotationConfig::hourly(),
+            )
+        };
+        let mut first = create().await.unwrap();
+        first.write_line(r#"{"event_type":"book"}"#).await.unwrap();
+        first.flush().await.unwrap();
+        let second = create().await.unwrap();
+        let third = create().await.unwrap();
+
+        let sessions: Vec<&str> = [&first, &second, &third]
+            .iter()
+            .map(|r| r.manifest().unwrap().session.as_str())
+            .collect();
+        assert_ne!(sessions[0], sessions[1]);
+        assert_ne!(sessions[1], sessions[2]);
+        assert_ne!(sessions[0], sessions[2]);
+        assert_ne!(first.path(), second.path());
+        assert_eq!(first.manifest_path().map(Path::exists), Some(true));
+
+        // The first session's segment is not truncated
+        let text = tokio::fs::read_to_string(first.path()).await.unwrap();
+        assert_eq!(text, "{\"event_type\":\"book\"}\n");
+
+        for recorder in [first, second, third] {
+            recorder.close().await.unwrap();
+        }
+
+        // Same second: numbered suffix (retry if the clock ticked in between)
+        for attempt in 0.. {
+            let prefix = format!("retry{}", attempt);
+            let (a, _) = reserve_session(&dir, &prefix).await.unwrap();
+            let (b, path) = reserve_session(&dir, &prefix).await.unwrap();
+            if b.starts_with(&a) {
+                assert_eq!(b, format!("{}-2", a));
+                assert_eq!(path, dir.join(format!("{}-2.manifest.json", a)));
+                break;
+            }
+        }
+        tokio::fs::remove_dir_all(&dir).await.ok();
+    }
+
+    #[tokio::test]
+    async fn test_idle_segment_rotates_on_flush() {
+        let dir = std::env::temp_dir().join(format!("recorder_idle_{}", std::process::id()));
+        let config = RotationConfig {
+            max_age: Some(Duration::from_millis(50)),
+            compression: Compression::Gzip,
+            ..Default::default()
+        };
+        let mut recorder =
+            JsonlRecorder::create_rotating(&dir, "ws_market", RecordFormat::Raw, config)
+                .await
+                .unwrap();
+        recorder.write_line(r#"{"event_type":"book"}"#).await.unwrap();
+
+        // No more messages: the timed flush closes and compresses the old segment
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        recorder.flush().await.unwrap();
+        let manifest = recorder.manifest().unwrap();
+        assert_eq!(manifest.segments.len(), 2);
+        assert!(manifest.segments[0].closed_at.is_some());
+        assert_eq!(manifest.segments[1].lines, 0);
+
+        // An empty segment is never rotated
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        recorder.flush().await.unwrap();
+        assert_eq!(recorder.manifest().unwrap().segments.len(), 2);
+
+        recorder.close().await.unwrap();
+        tokio::fs::remove_dir_all(&dir).await.ok();
+    }
+}
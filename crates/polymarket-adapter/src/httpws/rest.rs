@@ -7,113 +7,403 @@
 //! - GET /price - Get price for a token
 //! - GET /markets - Get market info
 //!
+//! # L1 Endpoints (wallet signature)
+//! - POST /auth/api-key - Create API credentials
+//! - GET /auth/derive-api-key - Derive existing API credentials
+//!
+//! # Authenticated Endpoints (L2 headers)
+//! Set credentials with `set_l2_auth`, then use `get_raw_authed` /
+//! `post_authed` / `delete_authed`. See `auth` for the signing scheme.
+//! - POST /order - Post a signed order (see `orders::OrderBuilder`)
+//! - DELETE /order, /orders, /cancel-all, /cancel-market-orders - Cancel orders
+//! - GET /data/orders - Open orders (paginated)
+//!
 //! # Source
 //! - Endpoints: https://docs.polymarket.com/quickstart/reference/endpoints
 
-use anyhow::{Context, Result};
-use reqwest::Client;
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::{Client, Method};
+use serde::de::DeserializeOwned;
 use serde_json::Value;
 use tracing::{debug, info};
 
-use crate::CLOB_REST_BASE;
+use crate::backend::ClobBackend;
+use crate::error::{check_response, decode_json, AdapterError, AdapterResult};
+use crate::httpws::auth::{l1_headers, ApiCredentials, L2Auth};
+use crate::httpws::orders::{OrderArgs, OrderBuilder};
+use crate::httpws::signer::WalletSigner;
+use crate::types::{
+    BookMessage, CancelResponse, ClobMarket, MidpointResponse, NegRiskResponse, OpenOrder,
+    OpenOrdersPage, OrderSide, OrderType, PostOrderRequest, PostOrderResponse, PriceResponse,
+    SignedOrder, SpreadResponse, TickSizeResponse,
+};
+use crate::{CLOB_REST_BASE, POLYGON_CHAIN_ID};
+
+/// First page cursor for paginated endpoints
+const INITIAL_CURSOR: &str = "MA==";
+
+/// Cursor returned after the last page
+const END_CURSOR: &str = "LTE=";
 
 /// REST client for CLOB API
 #[derive(Clone)]
 pub struct RestClient {
     client: Client,
     base_url: String,
+    auth: Option<L2Auth>,
 }
 
 impl RestClient {
     /// Create a new REST client with default base URL
-    pub fn new() -> Result<Self> {
+    pub fn new() -> AdapterResult<Self> {
         Self::with_base_url(CLOB_REST_BASE)
     }
 
     /// Create a new REST client with custom base URL
-    pub fn with_base_url(base_url: &str) -> Result<Self> {
-        let client = Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
-            .build()
-            .context("Failed to build HTTP client")?;
+    pub fn with_base_url(base_url: &str) -> AdapterResult<Self> {
+        let client = Client::builder().timeout(std::time::Duration::from_secs(30)).build()?;
+
+        Ok(Self { client, base_url: base_url.trim_end_matches('/').to_string(), auth: None })
+    }
+
+    /// Attach L2 credentials for authenticated endpoints
+    pub fn set_l2_auth(&mut self, auth: L2Auth) {
+        self.auth = Some(auth);
+    }
 
-        Ok(Self { client, base_url: base_url.trim_end_matches('/').to_string() })
+    /// Whether L2 credentials are attached
+    pub fn is_authenticated(&self) -> bool {
+        self.auth.is_some()
     }
 
     /// GET request returning raw JSON
-    pub async fn get_raw(&self, path: &str) -> Result<Value> {
+    pub async fn get_raw(&self, path: &str) -> AdapterResult<Value> {
         let url = format!("{}{}", self.base_url, path);
         debug!("GET {}", url);
 
-        let response = self.client.get(&url).send().await.context("HTTP request failed")?;
+        let response = self.client.get(&url).send().await?;
+        let response = check_response(response, &url).await?;
+        decode_json(response, path).await
+    }
 
-        let status = response.status();
-        if !status.is_success() {
-            let body = response.text().await.unwrap_or_default();
-            anyhow::bail!("HTTP {} for {}: {}", status, url, body);
+    /// Request signed with L1 headers, decoded as API credentials
+    async fn request_l1(
+        &self,
+        method: Method,
+        path: &str,
+        signer: &WalletSigner,
+        nonce: u64,
+    ) -> AdapterResult<ApiCredentials> {
+        let url = format!("{}{}", self.base_url, path);
+        let headers = l1_headers(signer, POLYGON_CHAIN_ID, Utc::now().timestamp(), nonce)?;
+        debug!("{} {} (L1, address {})", method, url, signer.address());
+
+        let response = self.client.request(method, &url).headers(headers).send().await?;
+        let response = check_response(response, &url).await?;
+        decode_json(response, path).await
+    }
+
+    /// Create new API credentials for the signer's wallet
+    ///
+    /// Endpoint: POST /auth/api-key
+    pub async fn create_api_key(
+        &self,
+        signer: &WalletSigner,
+        nonce: u64,
+    ) -> AdapterResult<ApiCredentials> {
+        self.request_l1(Method::POST, "/auth/api-key", signer, nonce).await
+    }
+
+    /// Derive the existing API credentials for a wallet and nonce
+    ///
+    /// Endpoint: GET /auth/derive-api-key
+    pub async fn derive_api_key(
+        &self,
+        signer: &WalletSigner,
+        nonce: u64,
+    ) -> AdapterResult<ApiCredentials> {
+        self.request_l1(Method::GET, "/auth/derive-api-key", signer, nonce).await
+    }
+
+    /// Create API credentials, deriving them instead if the key already exists
+    pub async fn create_or_derive_api_key(
+        &self,
+        signer: &WalletSigner,
+        nonce: u64,
+    ) -> AdapterResult<ApiCredentials> {
+        match self.create_api_key(signer, nonce).await {
+            Ok(credentials) => Ok(credentials),
+            Err(e @ (AdapterError::Http { .. } | AdapterError::Decode { .. })) => {
+                info!("Create API key failed ({}), deriving existing key", e);
+                self.derive_api_key(signer, nonce).await
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Authenticated request returning raw JSON (`Null` for an empty body)
+    ///
+    /// The body is serialized once and the same bytes are signed and sent.
+    /// Only the path (without query string) is part of the signature.
+    pub async fn request_authed(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&Value>,
+    ) -> AdapterResult<Value> {
+        let auth = self
+            .auth
+            .as_ref()
+            .ok_or_else(|| AdapterError::Auth("No L2 credentials configured".to_string()))?;
+
+        let url = format!("{}{}", self.base_url, path);
+        let body_json = body
+            .map(serde_json::to_string)
+            .transpose()
+            .map_err(|e| AdapterError::decode(path, e))?;
+        let sign_path = path.split('?').next().unwrap_or(path);
+        let headers =
+            auth.headers(Utc::now().timestamp(), method.as_str(), sign_path, body_json.as_deref())?;
+        debug!("{} {} (L2)", method, url);
+
+        let mut request = self.client.request(method, &url).headers(headers);
+        if let Some(body_json) = body_json {
+            request = request.header(CONTENT_TYPE, "application/json").body(body_json);
         }
 
-        let json: Value = response.json().await.context("Failed to parse JSON")?;
-        Ok(json)
+        let response = check_response(request.send().await?, &url).await?;
+        let bytes = response.bytes().await?;
+        if bytes.is_empty() {
+            return Ok(Value::Null);
+        }
+        serde_json::from_slice(&bytes).map_err(|e| AdapterError::decode(path, e))
+    }
+
+    /// Authenticated GET returning raw JSON
+    pub async fn get_raw_authed(&self, path: &str) -> AdapterResult<Value> {
+        self.request_authed(Method::GET, path, None).await
+    }
+
+    /// Authenticated POST with a JSON body
+    pub async fn post_authed(&self, path: &str, body: &Value) -> AdapterResult<Value> {
+        self.request_authed(Method::POST, path, Some(body)).await
+    }
+
+    /// Authenticated DELETE with an optional JSON body
+    pub async fn delete_authed(&self, path: &str, body: Option<&Value>) -> AdapterResult<Value> {
+        self.request_authed(Method::DELETE, path, body).await
+    }
+
+    /// GET request decoded into a typed response
+    ///
+    /// `required` is the field the endpoint exists to return; if it is absent
+    /// the error is `MissingField`, any other shape mismatch is a decode error.
+    async fn get_typed<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        required: &'static str,
+    ) -> AdapterResult<T> {
+        let raw = self.get_raw(path).await?;
+        decode_response(raw, required, path)
     }
 
     /// Get orderbook for a token (asset_id)
     ///
     /// Endpoint: GET /book?token_id={asset_id}
-    pub async fn get_book(&self, asset_id: &str) -> Result<Value> {
+    pub async fn get_book(&self, asset_id: &str) -> AdapterResult<BookMessage> {
         let path = format!("/book?token_id={}", asset_id);
-        self.get_raw(&path).await
+        self.get_typed(&path, "asset_id").await
     }
 
     /// Get price for a token
     ///
     /// Endpoint: GET /price?token_id={asset_id}&side={side}
-    pub async fn get_price(&self, asset_id: &str, side: &str) -> Result<Value> {
+    pub async fn get_price(&self, asset_id: &str, side: &str) -> AdapterResult<PriceResponse> {
         let path = format!("/price?token_id={}&side={}", asset_id, side);
-        self.get_raw(&path).await
+        self.get_typed(&path, "price").await
     }
 
     /// Get midpoint for a token
     ///
     /// Endpoint: GET /midpoint?token_id={asset_id}
-    pub async fn get_midpoint(&self, asset_id: &str) -> Result<Value> {
+    pub async fn get_midpoint(&self, asset_id: &str) -> AdapterResult<MidpointResponse> {
         let path = format!("/midpoint?token_id={}", asset_id);
-        self.get_raw(&path).await
+        self.get_typed(&path, "mid").await
     }
 
     /// Get spread for a token
     ///
     /// Endpoint: GET /spread?token_id={asset_id}
-    pub async fn get_spread(&self, asset_id: &str) -> Result<Value> {
+    pub async fn get_spread(&self, asset_id: &str) -> AdapterResult<SpreadResponse> {
         let path = format!("/spread?token_id={}", asset_id);
-        self.get_raw(&path).await
+        self.get_typed(&path, "spread").await
     }
 
     /// Get market info by condition_id
     ///
     /// Endpoint: GET /markets/{condition_id}
-    pub async fn get_market(&self, condition_id: &str) -> Result<Value> {
+    pub async fn get_market(&self, condition_id: &str) -> AdapterResult<ClobMarket> {
         let path = format!("/markets/{}", condition_id);
-        self.get_raw(&path).await
+        self.get_typed(&path, "condition_id").await
     }
 
     /// Get tick size for a token
     ///
     /// Endpoint: GET /tick-size?token_id={asset_id}
-    pub async fn get_tick_size(&self, asset_id: &str) -> Result<Value> {
+    pub async fn get_tick_size(&self, asset_id: &str) -> AdapterResult<TickSizeResponse> {
         let path = format!("/tick-size?token_id={}", asset_id);
-        self.get_raw(&path).await
+        self.get_typed(&path, "minimum_tick_size").await
+    }
+
+    /// Whether a token trades on the neg-risk exchange
+    ///
+    /// Endpoint: GET /neg-risk?token_id={asset_id}
+    pub async fn get_neg_risk(&self, asset_id: &str) -> AdapterResult<NegRiskResponse> {
+        let path = format!("/neg-risk?token_id={}", asset_id);
+        self.get_typed(&path, "neg_risk").await
+    }
+
+    /// Authenticated request decoded into a typed response
+    async fn request_authed_typed<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&Value>,
+    ) -> AdapterResult<T> {
+        let raw = self.request_authed(method, path, body).await?;
+        serde_json::from_value(raw).map_err(|e| AdapterError::decode(path, e))
+    }
+
+    /// Post a signed order
+    ///
+    /// Endpoint: POST /order (L2). The owner is the attached API key.
+    /// A 400 or a `success: false` body is returned as `OrderRejected`.
+    pub async fn post_order(
+        &self,
+        order: SignedOrder,
+        order_type: OrderType,
+    ) -> AdapterResult<PostOrderResponse> {
+        let owner = self
+            .auth
+            .as_ref()
+            .map(|auth| auth.credentials.api_key.clone())
+            .ok_or_else(|| AdapterError::Auth("No L2 credentials configured".to_string()))?;
+        let request = PostOrderRequest { order, owner, order_type };
+        let body = serde_json::to_value(&request).map_err(|e| AdapterError::decode("/order", e))?;
+
+        let response: PostOrderResponse =
+            match self.request_authed_typed(Method::POST, "/order", Some(&body)).await {
+                Ok(response) => response,
+                Err(AdapterError::Http { status: 400, body, .. }) => {
+                    return Err(AdapterError::OrderRejected(body))
+                }
+                Err(e) => return Err(e),
+            };
+        if !response.success {
+            return Err(AdapterError::OrderRejected(response.error_msg));
+        }
+        info!("Order {} posted: {}", response.order_id, response.status);
+        Ok(response)
+    }
+
+    /// Build, sign and post an order, fetching tick size and neg-risk flag first
+    pub async fn create_and_post_order(
+        &self,
+        builder: &OrderBuilder,
+        args: &OrderArgs,
+        order_type: OrderType,
+    ) -> AdapterResult<PostOrderResponse> {
+        if order_type == OrderType::Gtd && args.expiration == 0 {
+            return Err(AdapterError::InvalidOrder("GTD order needs an expiration".to_string()));
+        }
+
+        let tick_size = self.get_tick_size(&args.token_id).await?.minimum_tick_size;
+        let neg_risk = self.get_neg_risk(&args.token_id).await?.neg_risk;
+        let order = builder.build(args, &tick_size, neg_risk)?;
+        self.post_order(order, order_type).await
+    }
+
+    /// All open orders of the API key, optionally filtered by market and/or token
+    ///
+    /// Endpoint: GET /data/orders (L2), following `next_cursor` until the end marker
+    pub async fn get_open_orders(
+        &self,
+        market: Option<&str>,
+        asset_id: Option<&str>,
+    ) -> AdapterResult<Vec<OpenOrder>> {
+        let mut orders = Vec::new();
+        let mut cursor = INITIAL_CURSOR.to_string();
+
+        while cursor != END_CURSOR {
+            let mut path = format!("/data/orders?next_cursor={}", cursor);
+            if let Some(market) = market {
+                path.push_str(&format!("&market={}", market));
+            }
+            if let Some(asset_id) = asset_id {
+                path.push_str(&format!("&asset_id={}", asset_id));
+            }
+
+            let page: OpenOrdersPage = self.request_authed_typed(Method::GET, &path, None).await?;
+            orders.extend(page.data);
+            if page.next_cursor.is_empty() || page.next_cursor == cursor {
+                break;
+            }
+            cursor = page.next_cursor;
+        }
+
+        Ok(orders)
+    }
+
+    /// Cancel one order
+    ///
+    /// Endpoint: DELETE /order (L2)
+    pub async fn cancel_order(&self, order_id: &str) -> AdapterResult<CancelResponse> {
+        let body = serde_json::json!({ "orderID": order_id });
+        self.request_authed_typed(Method::DELETE, "/order", Some(&body)).await
+    }
+
+    /// Cancel several orders
+    ///
+    /// Endpoint: DELETE /orders (L2)
+    pub async fn cancel_orders(&self, order_ids: &[String]) -> AdapterResult<CancelResponse> {
+        let body = serde_json::json!(order_ids);
+        self.request_authed_typed(Method::DELETE, "/orders", Some(&body)).await
+    }
+
+    /// Cancel every open order of the API key
+    ///
+    /// Endpoint: DELETE /cancel-all (L2)
+    pub async fn cancel_all(&self) -> AdapterResult<CancelResponse> {
+        self.request_authed_typed(Method::DELETE, "/cancel-all", None).await
+    }
+
+    /// Cancel open orders for a market (condition id) and/or a token
+    ///
+    /// Endpoint: DELETE /cancel-market-orders (L2)
+    pub async fn cancel_market_orders(
+        &self,
+        market: Option<&str>,
+        asset_id: Option<&str>,
+    ) -> AdapterResult<CancelResponse> {
+        let body = serde_json::json!({
+            "market": market.unwrap_or_default(),
+            "asset_id": asset_id.unwrap_or_default(),
+        });
+        self.request_authed_typed(Method::DELETE, "/cancel-market-orders", Some(&body)).await
     }
 
     /// Simple connectivity test - try to hit a public endpoint
-    pub async fn test_connectivity(&self) -> Result<()> {
+    pub async fn test_connectivity(&self) -> AdapterResult<()> {
         info!("Testing connectivity to {}", self.base_url);
 
         // Try to get server time or any simple endpoint
         // If no dedicated health endpoint, we'll just verify we can connect
         let url = format!("{}/", self.base_url);
 
-        let response = self.client.get(&url).send().await.context("Connection test failed")?;
+        let response = self.client.get(&url).send().await?;
 
         let status = response.status();
         info!("Connectivity test: HTTP {}", status);
@@ -123,6 +413,58 @@ impl RestClient {
     }
 }
 
+/// Decode a JSON response, reporting an absent `required` field as `MissingField`
+fn decode_response<T: DeserializeOwned>(
+    raw: Value,
+    required: &'static str,
+    path: &str,
+) -> AdapterResult<T> {
+    if raw.get(required).is_none_or(Value::is_null) {
+        return Err(AdapterError::MissingField(required));
+    }
+    serde_json::from_value(raw).map_err(|e| AdapterError::decode(path, e))
+}
+
+#[async_trait]
+impl ClobBackend for RestClient {
+    fn name(&self) -> &'static str {
+        "httpws"
+    }
+
+    async fn book(&self, token_id: &str) -> AdapterResult<BookMessage> {
+        self.get_book(token_id).await
+    }
+
+    /// Tries the documented uppercase side first, then lowercase on a 400
+    async fn price(&self, token_id: &str, side: OrderSide) -> AdapterResult<String> {
+        match self.get_price(token_id, side.as_str()).await {
+            Ok(response) => Ok(response.price),
+            Err(e) if e.is_bad_request() => {
+                let lower = side.as_str().to_lowercase();
+                debug!("CLOB side={} failed ({}), trying side={}", side.as_str(), e, lower);
+                Ok(self.get_price(token_id, &lower).await?.price)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn midpoint(&self, token_id: &str) -> AdapterResult<String> {
+        Ok(self.get_midpoint(token_id).await?.mid)
+    }
+
+    async fn spread(&self, token_id: &str) -> AdapterResult<String> {
+        Ok(self.get_spread(token_id).await?.spread)
+    }
+
+    async fn tick_size(&self, token_id: &str) -> AdapterResult<String> {
+        Ok(self.get_tick_size(token_id).await?.minimum_tick_size)
+    }
+
+    async fn market(&self, condition_id: &str) -> AdapterResult<ClobMarket> {
+        self.get_market(condition_id).await
+    }
+}
+
 impl Default for RestClient {
     fn default() -> Self {
         Self::new().expect("Failed to create default RestClient")
@@ -143,6 +485,21 @@ pub async fn smoke_test_rest() -> Result<()> {
     Ok(())
 }
 
+/// Client on `uri` with fixed L2 test credentials
+#[cfg(test)]
+pub(crate) fn authed_client(uri: &str) -> RestClient {
+    let mut client = RestClient::with_base_url(uri).unwrap();
+    client.set_l2_auth(L2Auth::new(
+        "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266",
+        ApiCredentials {
+            api_key: "key".to_string(),
+            secret: "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=".to_string(),
+            passphrase: "pass".to_string(),
+        },
+    ));
+    client
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -158,4 +515,382 @@ mod tests {
         let client = RestClient::with_base_url("https://example.com/").unwrap();
         assert_eq!(client.base_url, "https://example.com");
     }
+
+    #[test]
+    fn test_decode_response() {
+        let ok: PriceResponse =
+            decode_response(serde_json::json!({"price": 0.55}), "price", "/price").unwrap();
+        assert_eq!(ok.price, "0.55");
+
+        let err =
+            decode_response::<PriceResponse>(serde_json::json!({}), "price", "/price").unwrap_err();
+        assert!(matches!(err, AdapterError::MissingField("price")));
+
+        // Present but malformed is a decode error, not MissingField
+        let err =
+            decode_response::<PriceResponse>(serde_json::json!({"price": [1]}), "price", "/price")
+                .unwrap_err();
+        assert!(matches!(err, AdapterError::Decode { .. }));
+    }
+}
+
+#[cfg(test)]
+mod wiremock_tests {
+    use super::*;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_backend_decimals_and_market() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/midpoint"))
+            .and(query_param("token_id", "123"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"mid": "0.5"})),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/spread"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/markets/0xabc"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "condition_id": "0xabc",
+                "tokens": [{"token_id": "123", "outcome": "Up", "price": 0.5}],
+                "minimum_tick_size": 0.01
+            })))
+            .mount(&server)
+            .await;
+
+        let backend: Box<dyn ClobBackend> =
+            Box::new(RestClient::with_base_url(&server.uri()).unwrap());
+        assert_eq!(backend.name(), "httpws");
+        assert_eq!(backend.midpoint("123").await.unwrap(), "0.5");
+
+        let err = backend.spread("123").await.unwrap_err();
+        assert!(matches!(err, AdapterError::MissingField("spread")));
+
+        let market = backend.market("0xabc").await.unwrap();
+        assert_eq!(market.tokens[0].token_id, "123");
+        assert_eq!(market.minimum_tick_size.as_deref(), Some("0.01"));
+    }
+
+    #[tokio::test]
+    async fn test_status_mapping() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/book"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/midpoint"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "3"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/spread"))
+            .respond_with(ResponseTemplate::new(503).set_body_string("maintenance"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/tick-size"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("not json"))
+            .mount(&server)
+            .await;
+
+        let client = RestClient::with_base_url(&server.uri()).unwrap();
+
+        let err = client.get_book("1").await.unwrap_err();
+        assert!(matches!(err, AdapterError::NotFound(_)));
+
+        let err = client.get_midpoint("1").await.unwrap_err();
+        assert!(matches!(
+            err,
+            AdapterError::RateLimited { retry_after: Some(d), .. } if d.as_secs() == 3
+        ));
+
+        let err = client.get_spread("1").await.unwrap_err();
+        assert!(
+            matches!(err, AdapterError::Http { status: 503, ref body, .. } if body == "maintenance")
+        );
+        assert!(err.is_retryable());
+
+        let err = client.get_tick_size("1").await.unwrap_err();
+        assert!(matches!(err, AdapterError::Decode { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_authed_request_signs_path_and_body() {
+        use crate::httpws::auth::{
+            build_hmac_signature, ApiCredentials, POLY_ADDRESS, POLY_API_KEY, POLY_SIGNATURE,
+            POLY_TIMESTAMP,
+        };
+        use wiremock::matchers::header_exists;
+
+        let secret = "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=";
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/order"))
+            .and(header_exists(POLY_SIGNATURE))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"success": true})),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/data/orders"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+            .mount(&server)
+            .await;
+
+        let mut client = RestClient::with_base_url(&server.uri()).unwrap();
+        let err = client.get_raw_authed("/data/orders").await.unwrap_err();
+        assert!(matches!(err, AdapterError::Auth(_)));
+
+        client.set_l2_auth(L2Auth::new(
+            "0xabc",
+            ApiCredentials {
+                api_key: "key".to_string(),
+                secret: secret.to_string(),
+                passphrase: "pass".to_string(),
+            },
+        ));
+        assert!(client.is_authenticated());
+
+        let body = serde_json::json!({"orderType": "GTC"});
+        let response = client.post_authed("/order", &body).await.unwrap();
+        assert_eq!(response["success"], true);
+        client.get_raw_authed("/data/orders?market=0x1").await.unwrap();
+
+        let requests = server.received_requests().await.unwrap();
+        assert_eq!(requests.len(), 2);
+        for (request, sign_path) in requests.iter().zip(["/order", "/data/orders"]) {
+            let header = |name: &str| request.headers.get(name).unwrap().to_str().unwrap();
+            assert_eq!(header(POLY_ADDRESS), "0xabc");
+            assert_eq!(header(POLY_API_KEY), "key");
+
+            let timestamp: i64 = header(POLY_TIMESTAMP).parse().unwrap();
+            let sent_body = String::from_utf8(request.body.clone()).unwrap();
+            let body = (!sent_body.is_empty()).then_some(sent_body.as_str());
+            let expected =
+                build_hmac_signature(secret, timestamp, request.method.as_str(), sign_path, body)
+                    .unwrap();
+            assert_eq!(header(POLY_SIGNATURE), expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_or_derive_api_key() {
+        use crate::httpws::auth::{POLY_ADDRESS, POLY_NONCE, POLY_SIGNATURE};
+        use wiremock::matchers::header;
+
+        let signer = WalletSigner::from_hex(
+            "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+        )
+        .unwrap();
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/auth/api-key"))
+            .respond_with(
+                ResponseTemplate::new(400)
+                    .set_body_json(serde_json::json!({"error": "Could not create api key"})),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/auth/derive-api-key"))
+            .and(header(POLY_ADDRESS, "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266"))
+            .and(header(POLY_NONCE, "0"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "apiKey": "00000000-0000-0000-0000-000000000000",
+                "secret": "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=",
+                "passphrase": "pass"
+            })))
+            .mount(&server)
+            .await;
+
+        let client = RestClient::with_base_url(&server.uri()).unwrap();
+        let creds = client.create_or_derive_api_key(&signer, 0).await.unwrap();
+        assert_eq!(creds.api_key, "00000000-0000-0000-0000-000000000000");
+        assert_eq!(creds.passphrase, "pass");
+
+        let requests = server.received_requests().await.unwrap();
+        assert_eq!(requests.len(), 2);
+        let signature = requests[1].headers.get(POLY_SIGNATURE).unwrap().to_str().unwrap();
+        assert!(signature.starts_with("0x") && signature.len() == 132);
+    }
+
+    #[tokio::test]
+    async fn test_create_and_post_order() {
+        use crate::httpws::orders::{OrderArgs, OrderBuilder};
+        use wiremock::matchers::body_partial_json;
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/tick-size"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"minimum_tick_size": 0.01})),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/neg-risk"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"neg_risk": true})),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/order"))
+            .and(body_partial_json(serde_json::json!({
+                "owner": "key",
+                "orderType": "GTC",
+                "order": {"tokenId": "123", "makerAmount": "5500000", "side": "BUY"}
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "success": true,
+                "errorMsg": "",
+                "orderID": "0xorder",
+                "status": "live",
+                "transactionsHashes": null
+            })))
+            .mount(&server)
+            .await;
+
+        let client = authed_client(&server.uri());
+        let signer = WalletSigner::from_hex(
+            "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+        )
+        .unwrap();
+        let builder = OrderBuilder::new(signer, POLYGON_CHAIN_ID);
+
+        // 0.559 rounds down to the 0.01 tick for a BUY
+        let args = OrderArgs::new("123", "0.559", "10", OrderSide::Buy);
+        let response = client.create_and_post_order(&builder, &args, OrderType::Gtc).await.unwrap();
+        assert_eq!(response.order_id, "0xorder");
+        assert_eq!(response.status, "live");
+        assert!(response.transaction_hashes.is_empty());
+
+        // Signed for the neg-risk exchange, so it differs from the standard one
+        let requests = server.received_requests().await.unwrap();
+        let sent: PostOrderRequest = serde_json::from_slice(&requests[2].body).unwrap();
+        let standard = builder.build_with_salt(&args, "0.01", false, sent.order.salt).unwrap();
+        let neg_risk = builder.build_with_salt(&args, "0.01", true, sent.order.salt).unwrap();
+        assert_ne!(sent.order.signature, standard.signature);
+        assert_eq!(sent.order.signature, neg_risk.signature);
+
+        let err = client.create_and_post_order(&builder, &args, OrderType::Gtd).await.unwrap_err();
+        assert!(matches!(err, AdapterError::InvalidOrder(_)));
+
+        let err = client
+            .create_and_post_order(
+                &builder,
+                &OrderArgs::new("123", "0.999", "10", OrderSide::Sell),
+                OrderType::Gtc,
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AdapterError::InvalidOrder(_)));
+    }
+
+    #[tokio::test]
+    async fn test_post_order_rejected() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/order"))
+            .and(wiremock::matchers::body_partial_json(serde_json::json!({"orderType": "FOK"})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "success": false,
+                "errorMsg": "order couldn't be fully filled",
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/order"))
+            .respond_with(
+                ResponseTemplate::new(400).set_body_string("not enough balance / allowance"),
+            )
+            .mount(&server)
+            .await;
+
+        let client = authed_client(&server.uri());
+        let signer = WalletSigner::from_hex(
+            "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+        )
+        .unwrap();
+        let builder = crate::httpws::orders::OrderBuilder::new(signer, POLYGON_CHAIN_ID);
+        let args = crate::httpws::orders::OrderArgs::new("123", "0.5", "10", OrderSide::Sell);
+        let order = builder.build(&args, "0.01", false).unwrap();
+
+        let err = client.post_order(order.clone(), OrderType::Fok).await.unwrap_err();
+        assert!(matches!(err, AdapterError::OrderRejected(ref m) if m.contains("fully filled")));
+
+        let err = client.post_order(order.clone(), OrderType::Gtc).await.unwrap_err();
+        assert!(matches!(err, AdapterError::OrderRejected(ref m) if m.contains("balance")));
+
+        let unauthenticated = RestClient::with_base_url(&server.uri()).unwrap();
+        let err = unauthenticated.post_order(order, OrderType::Gtc).await.unwrap_err();
+        assert!(matches!(err, AdapterError::Auth(_)));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_endpoints() {
+        use wiremock::matchers::body_json;
+
+        let server = MockServer::start().await;
+        Mock::given(method("DELETE"))
+            .and(path("/order"))
+            .and(body_json(serde_json::json!({"orderID": "0x1"})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "canceled": ["0x1"],
+                "not_canceled": {}
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path("/orders"))
+            .and(body_json(serde_json::json!(["0x1", "0x2"])))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "canceled": ["0x1"],
+                "not_canceled": {"0x2": "order not found"}
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path("/cancel-all"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "canceled": [],
+                "not_canceled": null
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path("/cancel-market-orders"))
+            .and(body_json(serde_json::json!({"market": "0xabc", "asset_id": ""})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "canceled": ["0x3"]
+            })))
+            .mount(&server)
+            .await;
+
+        let client = authed_client(&server.uri());
+
+        let response = client.cancel_order("0x1").await.unwrap();
+        assert_eq!(response.canceled, vec!["0x1"]);
+
+        let response = client.cancel_orders(&["0x1".to_string(), "0x2".to_string()]).await.unwrap();
+        assert_eq!(response.not_canceled["0x2"], "order not found");
+
+        let response = client.cancel_all().await.unwrap();
+        assert!(response.canceled.is_empty() && response.not_canceled.is_empty());
+
+        let response = client.cancel_market_orders(Some("0xabc"), None).await.unwrap();
+        assert_eq!(response.canceled, vec!["0x3"]);
+    }
 }
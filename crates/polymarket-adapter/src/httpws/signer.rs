@@ -0,0 +1,203 @@
+//! Private-key signer and EIP-712 hashing for the httpws backend
+//!
+//! # Design
+//! - `WalletSigner` wraps a secp256k1 key and signs 32-byte digests,
+//!   producing the 65-byte `r || s || v` hex signature the CLOB expects (v = 27/28)
+//! - EIP-712 helpers cover only the static types the CLOB uses
+//!   (address, uint256, string); no general ABI encoder
+//!
+//! # Source
+//! - EIP-712: https://eips.ethereum.org/EIPS/eip-712
+//! - L1 Authentication: https://docs.polymarket.com/developers/CLOB/authentication
+
+use k256::ecdsa::SigningKey;
+use sha3::{Digest, Keccak256};
+
+use crate::error::{AdapterError, AdapterResult};
+
+/// Keccak-256 hash
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    Keccak256::digest(data).into()
+}
+
+/// ABI-encode a uint256 from a u128
+pub fn encode_u256(value: u128) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    out[16..].copy_from_slice(&value.to_be_bytes());
+    out
+}
+
+/// ABI-encode a uint256 given as a decimal string (e.g. a 77-digit token id)
+pub fn encode_u256_decimal(value: &str) -> AdapterResult<[u8; 32]> {
+    if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(AdapterError::InvalidOrder(format!("Not a uint256: {:?}", value)));
+    }
+
+    // Big-endian base-256 accumulator: out = out * 10 + digit
+    let mut out = [0u8; 32];
+    for digit in value.bytes().map(|b| b - b'0') {
+        let mut carry = digit as u16;
+        for byte in out.iter_mut().rev() {
+            let v = (*byte as u16) * 10 + carry;
+            *byte = v as u8;
+            carry = v >> 8;
+        }
+        if carry != 0 {
+            return Err(AdapterError::InvalidOrder(format!("uint256 overflow: {}", value)));
+        }
+    }
+    Ok(out)
+}
+
+/// ABI-encode an address (left-padded to 32 bytes)
+pub fn encode_address(address: &[u8; 20]) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    out[12..].copy_from_slice(address);
+    out
+}
+
+/// Parse a 0x-prefixed (or bare) 20-byte hex address
+pub fn parse_address(address: &str) -> AdapterResult<[u8; 20]> {
+    let bytes = hex::decode(address.trim_start_matches("0x"))
+        .map_err(|e| AdapterError::Auth(format!("Invalid address {}: {}", address, e)))?;
+    bytes.try_into().map_err(|_| AdapterError::Auth(format!("Address {} is not 20 bytes", address)))
+}
+
+/// EIP-712 domain separator
+///
+/// `verifying_contract` is omitted from the domain type when `None`
+/// (the ClobAuth domain has no contract).
+pub fn domain_separator(
+    name: &str,
+    version: &str,
+    chain_id: u64,
+    verifying_contract: Option<&[u8; 20]>,
+) -> [u8; 32] {
+    let type_string = match verifying_contract {
+        Some(_) => {
+            "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)"
+        }
+        None => "EIP712Domain(string name,string version,uint256 chainId)",
+    };
+
+    let mut encoded = Vec::with_capacity(32 * 5);
+    encoded.extend_from_slice(&keccak256(type_string.as_bytes()));
+    encoded.extend_from_slice(&keccak256(name.as_bytes()));
+    encoded.extend_from_slice(&keccak256(version.as_bytes()));
+    encoded.extend_from_slice(&encode_u256(chain_id as u128));
+    if let Some(contract) = verifying_contract {
+        encoded.extend_from_slice(&encode_address(contract));
+    }
+    keccak256(&encoded)
+}
+
+/// Final EIP-712 digest: `keccak256(0x1901 || domainSeparator || structHash)`
+pub fn typed_data_hash(domain_separator: &[u8; 32], struct_hash: &[u8; 32]) -> [u8; 32] {
+    let mut encoded = Vec::with_capacity(66);
+    encoded.extend_from_slice(&[0x19, 0x01]);
+    encoded.extend_from_slice(domain_separator);
+    encoded.extend_from_slice(struct_hash);
+    keccak256(&encoded)
+}
+
+/// secp256k1 private-key signer
+#[derive(Clone)]
+pub struct WalletSigner {
+    key: SigningKey,
+    address: [u8; 20],
+}
+
+impl WalletSigner {
+    /// Create from a hex private key (with or without 0x prefix)
+    pub fn from_hex(private_key: &str) -> AdapterResult<Self> {
+        let bytes = hex::decode(private_key.trim().trim_start_matches("0x"))
+            .map_err(|_| AdapterError::Auth("Invalid private key hex".to_string()))?;
+        let key = SigningKey::from_slice(&bytes)
+            .map_err(|_| AdapterError::Auth("Invalid private key".to_string()))?;
+
+        let point = key.verifying_key().to_encoded_point(false);
+        let hash = keccak256(&point.as_bytes()[1..]);
+        let mut address = [0u8; 20];
+        address.copy_from_slice(&hash[12..]);
+
+        Ok(Self { key, address })
+    }
+
+    /// Create from the POLY_PRIVATE_KEY environment variable
+    pub fn from_env() -> Option<AdapterResult<Self>> {
+        std::env::var("POLY_PRIVATE_KEY").ok().map(|pk| Self::from_hex(&pk))
+    }
+
+    /// Raw 20-byte address
+    pub fn address_bytes(&self) -> &[u8; 20] {
+        &self.address
+    }
+
+    /// Lowercase 0x-prefixed address
+    pub fn address(&self) -> String {
+        format!("0x{}", hex::encode(self.address))
+    }
+
+    /// Sign a 32-byte digest, returning `0x{r}{s}{v}` with v = 27/28
+    pub fn sign_hash(&self, hash: &[u8; 32]) -> AdapterResult<String> {
+        let (signature, recovery_id) = self
+            .key
+            .sign_prehash_recoverable(hash)
+            .map_err(|e| AdapterError::Auth(format!("Signing failed: {}", e)))?;
+
+        let mut bytes = [0u8; 65];
+        bytes[..64].copy_from_slice(&signature.to_bytes());
+        bytes[64] = 27 + recovery_id.to_byte();
+        Ok(format!("0x{}", hex::encode(bytes)))
+    }
+}
+
+impl std::fmt::Debug for WalletSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WalletSigner").field("address", &self.address()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Publicly known development key (anvil/hardhat account #0)
+    const TEST_PRIVATE_KEY: &str =
+        "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+    #[test]
+    fn test_keccak256() {
+        assert_eq!(
+            hex::encode(keccak256(b"")),
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+    }
+
+    #[test]
+    fn test_encode_u256_decimal() {
+        assert_eq!(encode_u256_decimal("1234567").unwrap(), encode_u256(1234567));
+
+        // 2^256 - 1
+        let max = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+        assert_eq!(encode_u256_decimal(max).unwrap(), [0xff; 32]);
+        assert!(encode_u256_decimal(&format!("{}0", max)).is_err());
+        assert!(encode_u256_decimal("0x12").is_err());
+    }
+
+    #[test]
+    fn test_signer_address() {
+        let signer = WalletSigner::from_hex(TEST_PRIVATE_KEY).unwrap();
+        assert_eq!(signer.address(), "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266");
+        assert!(!format!("{:?}", signer).contains("ac0974"));
+
+        assert!(matches!(WalletSigner::from_hex("0x1234"), Err(AdapterError::Auth(_))));
+    }
+
+    #[test]
+    fn test_parse_address() {
+        let parsed = parse_address("0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266").unwrap();
+        assert_eq!(&parsed, WalletSigner::from_hex(TEST_PRIVATE_KEY).unwrap().address_bytes());
+        assert!(parse_address("0x1234").is_err());
+    }
+}
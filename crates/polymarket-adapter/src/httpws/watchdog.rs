@@ -0,0 +1,344 @@
+//! Stale-feed watchdog for market subscriptions
+//!
+//! The socket can stay open (PONGs keep arriving) while one subscribed asset
+//! goes silent. `FeedWatchdog` tracks the last message time per `asset_id` and
+//! flags assets that have been silent longer than `WatchdogConfig::stale_after`.
+//!
+//! # Design
+//! - `MarketWsClient` records every message and runs the check every
+//!   `check_interval` on the live connection
+//! - An asset is tracked from the first check after it is subscribed, so one
+//!   that never sends anything goes stale as well
+//! - `StaleEvent::Stale` is raised once per silence, `Recovered` on the next
+//!   message for that asset. `MarketWsClient` drains them into its message
+//!   stream as `LifecycleEvent::Stale`/`Recovered`; a standalone watchdog keeps
+//!   the last `MAX_PENDING_EVENTS` for `take_events`
+//! - `StaleAction` decides what the client does about it: report only,
+//!   resubscribe the stale assets on the live socket, or reconnect; the action
+//!   repeats every `stale_after` while the asset stays silent
+//! - Quoting must check `is_stale` (or watch for the lifecycle events) and stop
+//!   quoting silent assets; the watchdog never touches orders itself
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tracing::{info, warn};
+
+use crate::types::WsInboundMessage;
+
+/// Undrained events kept per watchdog (oldest dropped first)
+pub const MAX_PENDING_EVENTS: usize = 1024;
+
+/// What the client does when assets go stale
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StaleAction {
+    /// Raise events only
+    #[default]
+    Report,
+    /// Unsubscribe and subscribe the stale assets again on the live socket
+    Resubscribe,
+    /// Drop the connection and reconnect
+    Reconnect,
+}
+
+impl StaleAction {
+    /// Parse from a CLI value ("report", "resubscribe", "reconnect")
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "report" => Some(StaleAction::Report),
+            "resubscribe" => Some(StaleAction::Resubscribe),
+            "reconnect" => Some(StaleAction::Reconnect),
+            _ => None,
+        }
+    }
+}
+
+/// Watchdog settings
+#[derive(Clone, Debug)]
+pub struct WatchdogConfig {
+    /// Silence after which an asset is stale
+    pub stale_after: Duration,
+    /// How often the client checks
+    pub check_interval: Duration,
+    /// Reaction to stale assets
+    pub action: StaleAction,
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            stale_after: Duration::from_secs(30),
+            check_interval: Duration::from_secs(1),
+            action: StaleAction::Report,
+        }
+    }
+}
+
+/// Staleness transition of one asset
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StaleEvent {
+    /// No message for `silent_for`
+    Stale { asset_id: String, silent_for: Duration },
+    /// Messages resumed after `silent_for` of silence
+    Recovered { asset_id: String, silent_for: Duration },
+}
+
+#[derive(Debug)]
+struct AssetState {
+    /// Last message (or start of tracking)
+    last_seen: Instant,
+    /// Next time the stale action fires
+    due: Instant,
+    stale: bool,
+}
+
+#[derive(Debug, Default)]
+struct WatchdogState {
+    config: Option<WatchdogConfig>,
+    assets: HashMap<String, AssetState>,
+    events: VecDeque<StaleEvent>,
+}
+
+impl WatchdogState {
+    fn push_event(&mut self, event: StaleEvent) {
+        if self.events.len() == MAX_PENDING_EVENTS {
+            self.events.pop_front();
+        }
+        self.events.push_back(event);
+    }
+}
+
+/// Shared per-asset last-message tracker (cheap to clone)
+#[derive(Clone, Debug, Default)]
+pub struct FeedWatchdog {
+    state: Arc<Mutex<WatchdogState>>,
+}
+
+impl FeedWatchdog {
+    /// Watchdog raising events per `config`
+    pub fn new(config: WatchdogConfig) -> Self {
+        let watchdog = Self::default();
+        watchdog.set_config(Some(config));
+        watchdog
+    }
+
+    /// Current settings (None = tracking only, never stale)
+    pub fn config(&self) -> Option<WatchdogConfig> {
+        self.state.lock().unwrap().config.clone()
+    }
+
+    /// Enable, change or disable staleness checks
+    pub fn set_config(&self, config: Option<WatchdogConfig>) {
+        self.state.lock().unwrap().config = config;
+    }
+
+    /// Record a message for every asset it carries
+    pub fn record(&self, msg: &WsInboundMessage) {
+        let now = Instant::now();
+        for asset_id in msg.asset_ids() {
+            self.record_at(asset_id, now);
+        }
+    }
+
+    /// Restart the silence timer of every tracked asset (e.g. after a reconnect)
+    ///
+    /// Stale assets stay stale until a message arrives.
+    pub fn grace(&self) {
+        self.grace_at(Instant::now());
+    }
+
+    /// Run the check for the current subscription set
+    ///
+    /// Returns the assets whose stale action is due (newly stale, or still
+    /// silent `stale_after` after the previous action).
+    pub fn check(&self, subscribed: &[String]) -> Vec<String> {
+        self.check_at(subscribed, Instant::now())
+    }
+
+    /// Whether the asset is currently stale
+    pub fn is_stale(&self, asset_id: &str) -> bool {
+        self.state.lock().unwrap().assets.get(asset_id).is_some_and(|a| a.stale)
+    }
+
+    /// Stale assets, sorted
+    pub fn stale_assets(&self) -> Vec<String> {
+        let state = self.state.lock().unwrap();
+        let mut ids: Vec<String> =
+            state.assets.iter().filter(|(_, a)| a.stale).map(|(id, _)| id.clone()).collect();
+        ids.sort();
+        ids
+    }
+
+    /// Time since the asset's last message (or start of tracking)
+    pub fn silent_for(&self, asset_id: &str) -> Option<Duration> {
+        self.state.lock().unwrap().assets.get(asset_id).map(|a| a.last_seen.elapsed())
+    }
+
+    /// Drain staleness transitions since the last call (at most `MAX_PENDING_EVENTS`)
+    ///
+    /// `MarketWsClient` drains these itself; use this for a standalone watchdog.
+    pub fn take_events(&self) -> Vec<StaleEvent> {
+        self.state.lock().unwrap().events.drain(..).collect()
+    }
+
+    fn record_at(&self, asset_id: &str, now: Instant) {
+        let mut state = self.state.lock().unwrap();
+        let stale_after = state.config.as_ref().map(|c| c.stale_after).unwrap_or_default();
+        let asset = state.assets.entry(asset_id.to_string()).or_insert(AssetState {
+            last_seen: now,
+            due: now,
+            stale: false,
+        });
+        let recovered = asset.stale.then(|| now.saturating_duration_since(asset.last_seen));
+        asset.stale = false;
+        asset.last_seen = now;
+        asset.due = now + stale_after;
+
+        if let Some(silent_for) = recovered {
+            info!("Feed for {} recovered after {:?}", asset_id, silent_for);
+            state.push_event(StaleEvent::Recovered { asset_id: asset_id.to_string(), silent_for });
+        }
+    }
+
+    fn grace_at(&self, now: Instant) {
+        let mut state = self.state.lock().unwrap();
+        let stale_after = state.config.as_ref().map(|c| c.stale_after).unwrap_or_default();
+        for asset in state.assets.values_mut() {
+            asset.due = now + stale_after;
+        }
+    }
+
+    fn check_at(&self, subscribed: &[String], now: Instant) -> Vec<String> {
+        let mut state = self.state.lock().unwrap();
+        let Some(stale_after) = state.config.as_ref().map(|c| c.stale_after) else {
+            return Vec::new();
+        };
+        let mut raised = Vec::new();
+        let assets = &mut state.assets;
+
+        // Follow the subscription set: drop removed assets, start tracking new ones
+        assets.retain(|id, _| subscribed.contains(id));
+        for id in subscribed {
+            assets.entry(id.clone()).or_insert(AssetState {
+                last_seen: now,
+                due: now + stale_after,
+                stale: false,
+            });
+        }
+
+        let mut due = Vec::new();
+        for id in subscribed {
+            let asset = assets.get_mut(id).expect("tracked above");
+            if now < asset.due {
+                continue;
+            }
+            if !asset.stale {
+                let silent_for = now.saturating_duration_since(asset.last_seen);
+                warn!("Feed for {} stale: no message for {:?}", id, silent_for);
+                raised.push(StaleEvent::Stale { asset_id: id.clone(), silent_for });
+                asset.stale = true;
+            }
+            asset.due = now + stale_after;
+            due.push(id.clone());
+        }
+        for event in raised {
+            state.push_event(event);
+        }
+        due
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_stale_and_recovered() {
+        let watchdog = FeedWatchdog::new(WatchdogConfig {
+            stale_after: Duration::from_secs(10),
+            ..Default::default()
+        });
+        let t0 = Instant::now();
+        let subscribed = ids(&["a", "b"]);
+
+        watchdog.record_at("a", t0);
+        assert!(watchdog.check_at(&subscribed, t0).is_empty());
+
+        // "a" keeps talking, "b" never sends anything
+        watchdog.record_at("a", t0 + Duration::from_secs(8));
+        assert_eq!(watchdog.check_at(&subscribed, t0 + Duration::from_secs(10)), ids(&["b"]));
+        assert!(watchdog.is_stale("b") && !watchdog.is_stale("a"));
+        assert_eq!(
+            watchdog.take_events(),
+            [StaleEvent::Stale { asset_id: "b".to_string(), silent_for: Duration::from_secs(10) }]
+        );
+
+        // Still silent: action due again after another stale_after, no duplicate event
+        assert!(watchdog.check_at(&subscribed, t0 + Duration::from_secs(15)).is_empty());
+        watchdog.record_at("a", t0 + Duration::from_secs(16));
+        assert_eq!(watchdog.check_at(&subscribed, t0 + Duration::from_secs(20)), ids(&["b"]));
+        assert!(watchdog.take_events().is_empty());
+
+        watchdog.record_at("b", t0 + Duration::from_secs(25));
+        assert_eq!(
+            watchdog.take_events(),
+            [StaleEvent::Recovered {
+                asset_id: "b".to_string(),
+                silent_for: Duration::from_secs(25)
+            }]
+        );
+        assert!(watchdog.stale_assets().is_empty());
+    }
+
+    #[test]
+    fn test_pending_events_capped() {
+        let watchdog = FeedWatchdog::new(WatchdogConfig {
+            stale_after: Duration::from_secs(10),
+            ..Default::default()
+        });
+        let t0 = Instant::now();
+        let subscribed: Vec<String> =
+            (0..MAX_PENDING_EVENTS + 5).map(|i| format!("asset{}", i)).collect();
+        watchdog.check_at(&subscribed, t0);
+        watchdog.check_at(&subscribed, t0 + Duration::from_secs(10));
+
+        // Nobody drained: the oldest five were dropped
+        let events = watchdog.take_events();
+        assert_eq!(events.len(), MAX_PENDING_EVENTS);
+        assert!(matches!(&events[0], StaleEvent::Stale { asset_id, .. } if asset_id == "asset5"));
+        assert!(watchdog.take_events().is_empty());
+    }
+
+    #[test]
+    fn test_follows_subscription_and_grace() {
+        let watchdog = FeedWatchdog::new(WatchdogConfig {
+            stale_after: Duration::from_secs(10),
+            ..Default::default()
+        });
+        let t0 = Instant::now();
+        watchdog.check_at(&ids(&["a", "b"]), t0);
+
+        // Reconnect at t0+8 restarts the timers; "b" was unsubscribed
+        watchdog.grace_at(t0 + Duration::from_secs(8));
+        assert!(watchdog.check_at(&ids(&["a"]), t0 + Duration::from_secs(12)).is_empty());
+        assert_eq!(watchdog.check_at(&ids(&["a"]), t0 + Duration::from_secs(18)), ids(&["a"]));
+        assert_eq!(watchdog.stale_assets(), ids(&["a"]));
+        assert_eq!(watchdog.silent_for("b"), None);
+
+        // Disabled: tracking only
+        let passive = FeedWatchdog::default();
+        passive.record(&WsInboundMessage::parse(
+            r#"{"event_type":"price_change","market":"m","timestamp":"1","price_changes":[
+                {"asset_id":"x","price":"0.5","size":"1","side":"BUY"},
+                {"asset_id":"y","price":"0.5","size":"1","side":"SELL"}]}"#,
+        ));
+        assert!(passive.silent_for("x").is_some() && passive.silent_for("y").is_some());
+        assert!(passive.check(&ids(&["x", "y"])).is_empty());
+    }
+}
@@ -4,71 +4,142 @@
 //!
 //! # Features
 //! - Connect to market channel (no auth required)
-//! - Subscribe to asset_ids
+//! - Subscribe to asset_ids, add/remove them on a live connection (`MarketSubscriptions`)
 //! - Parse incoming messages with Unknown fallback
-//! - Write raw JSONL to file
-//! - Automatic reconnection with exponential backoff
+//! - Write raw or enveloped JSONL to file (optional sink, see `recorder`)
+//! - Stream parsed messages to a consumer via `tokio::sync::mpsc`
+//! - Automatic reconnection with jittered exponential backoff and a circuit
+//!   breaker (`set_reconnect_policy`, `feed_status`)
+//! - Stale-feed watchdog per asset (`set_watchdog`, see `watchdog`), reported
+//!   as `Stale`/`Recovered` lifecycle events
+//! - Lifecycle events in the message stream (`LifecycleEvent`): an asset is
+//!   `Resynced` by its first `book` snapshot after each (re)subscribe
 //! - Application-level PING/PONG (NOT WebSocket ping frames)
 //!
-//! # Important: Application-Level Keepalive
-//! Polymarket requires sending the literal string "PING" as a text message every 10 seconds.
-//! This is NOT the WebSocket protocol ping frame!
-//! See: https://docs.polymarket.com/developers/CLOB/websocket/wss-overview
+//! Connect, keepalive and reconnect live in `connection::WsConnection`,
+//! shared with the user channel.
+//! `pool::MarketWsPool` shards large subscriptions over several clients.
 //!
 //! # Source
 //! - WSS Overview: https://docs.polymarket.com/developers/CLOB/websocket/wss-overview
 //! - Market Channel: https://docs.polymarket.com/developers/CLOB/websocket/market-channel
 
-use anyhow::{Context, Result};
+use anyhow::Result;
 use chrono::Utc;
-use futures_util::{SinkExt, StreamExt};
+use std::collections::HashSet;
 use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
-use std::time::{Duration, Instant};
-use tokio::fs::File;
-use tokio::io::AsyncWriteExt;
-use tokio_tungstenite::{connect_async, tungstenite::Message};
-use tracing::{debug, error, info, warn};
-
-use crate::types::{MessageStats, SubscribeRequest, WsInboundMessage};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
+use tracing::{debug, info};
+
+use crate::error::{AdapterError, AdapterResult};
+use crate::httpws::connection::{
+    ChannelAction, FeedStatus, ReconnectPolicy, WsChannel, WsConnection,
+};
+use crate::httpws::recorder::JsonlRecorder;
+use crate::httpws::watchdog::{FeedWatchdog, StaleAction, StaleEvent, WatchdogConfig};
+use crate::types::{
+    LifecycleEvent, MarketMessage, MessageStats, SubscribeRequest, SubscriptionChange,
+    WsInboundMessage,
+};
 use crate::CLOB_WSS_ENDPOINT;
 
-/// Maximum reconnection backoff interval
-const MAX_BACKOFF_SECS: u64 = 30;
+/// Subscription change queued for a live connection
+#[derive(Clone, Debug)]
+enum SubscriptionCommand {
+    Subscribe(Vec<String>),
+    Unsubscribe(Vec<String>),
+}
 
-/// Initial backoff interval
-const INITIAL_BACKOFF_SECS: u64 = 1;
+/// Handle for changing the asset subscription of a running `MarketWsClient`
+///
+/// The handle owns the authoritative subscription set: changes are sent on the
+/// live socket as `SubscriptionChange` messages, and the full set is replayed
+/// in the initial subscribe after every reconnect.
+#[derive(Clone)]
+pub struct MarketSubscriptions {
+    asset_ids: Arc<Mutex<Vec<String>>>,
+    commands: mpsc::UnboundedSender<SubscriptionCommand>,
+}
 
-/// Flush file every N messages
-const FLUSH_EVERY_MSGS: u64 = 200;
+impl MarketSubscriptions {
+    /// Add assets to the subscription (already subscribed ids are ignored)
+    pub fn subscribe(&self, asset_ids: Vec<String>) {
+        let added: Vec<String> = {
+            let mut current = self.asset_ids.lock().unwrap();
+            let mut added = Vec::new();
+            for id in asset_ids {
+                if !current.contains(&id) && !added.contains(&id) {
+                    added.push(id);
+                }
+            }
+            current.extend(added.iter().cloned());
+            added
+        };
 
-/// Flush file at least every N seconds
-const FLUSH_EVERY_SECS: u64 = 5;
+        if !added.is_empty() {
+            // Receiver only goes away with the client; the set is still replayed on connect
+            let _ = self.commands.send(SubscriptionCommand::Subscribe(added));
+        }
+    }
 
-/// Log progress every N seconds
-const LOG_PROGRESS_EVERY_SECS: u64 = 60;
+    /// Remove assets from the subscription (unknown ids are ignored)
+    pub fn unsubscribe(&self, asset_ids: Vec<String>) {
+        let removed: Vec<String> = {
+            let mut current = self.asset_ids.lock().unwrap();
+            let removed: Vec<String> =
+                current.iter().filter(|id| asset_ids.contains(id)).cloned().collect();
+            current.retain(|id| !removed.contains(id));
+            removed
+        };
 
-/// Application-level PING interval (Polymarket requirement)
-/// Send literal "PING" text message every 10 seconds
-const APP_PING_INTERVAL_SECS: u64 = 10;
+        if !removed.is_empty() {
+            let _ = self.commands.send(SubscriptionCommand::Unsubscribe(removed));
+        }
+    }
+
+    /// Current subscription set
+    pub fn current(&self) -> Vec<String> {
+        self.asset_ids.lock().unwrap().clone()
+    }
+}
 
 /// Market channel WebSocket client
 pub struct MarketWsClient {
     endpoint: String,
-    asset_ids: Vec<String>,
+    subscriptions: MarketSubscriptions,
+    commands: tokio::sync::Mutex<mpsc::UnboundedReceiver<SubscriptionCommand>>,
     enable_features: bool,
+    reconnect: ReconnectPolicy,
+    status: watch::Sender<FeedStatus>,
+    watchdog: FeedWatchdog,
 }
 
 impl MarketWsClient {
     /// Create a new market channel client
     pub fn new(asset_ids: Vec<String>) -> Self {
-        Self { endpoint: CLOB_WSS_ENDPOINT.to_string(), asset_ids, enable_features: true }
+        Self::with_endpoint(CLOB_WSS_ENDPOINT, asset_ids)
     }
 
     /// Create with custom endpoint (for testing)
     pub fn with_endpoint(endpoint: &str, asset_ids: Vec<String>) -> Self {
-        Self { endpoint: endpoint.to_string(), asset_ids, enable_features: true }
+        let (tx, rx) = mpsc::unbounded_channel();
+        let subscriptions =
+            MarketSubscriptions { asset_ids: Arc::new(Mutex::new(Vec::new())), commands: tx };
+        subscriptions.asset_ids.lock().unwrap().extend(dedup(asset_ids));
+
+        Self {
+            endpoint: endpoint.to_string(),
+            subscriptions,
+            commands: tokio::sync::Mutex::new(rx),
+            enable_features: true,
+            reconnect: ReconnectPolicy::default(),
+            status: watch::Sender::new(FeedStatus::default()),
+            watchdog: FeedWatchdog::default(),
+        }
     }
 
     /// Enable or disable feature-flagged messages
@@ -76,6 +147,31 @@ impl MarketWsClient {
         self.enable_features = enable;
     }
 
+    /// Set backoff, circuit breaker and give-up behaviour
+    pub fn set_reconnect_policy(&mut self, policy: ReconnectPolicy) {
+        self.reconnect = policy;
+    }
+
+    /// Watch connection health (`FeedStatus::Down` when the circuit is open)
+    pub fn feed_status(&self) -> watch::Receiver<FeedStatus> {
+        self.status.subscribe()
+    }
+
+    /// Enable the stale-feed watchdog
+    pub fn set_watchdog(&mut self, config: WatchdogConfig) {
+        self.watchdog.set_config(Some(config));
+    }
+
+    /// Per-asset last-message tracker (stale assets, events)
+    pub fn watchdog(&self) -> FeedWatchdog {
+        self.watchdog.clone()
+    }
+
+    /// Handle for adding/removing assets while the client is running
+    pub fn subscriptions(&self) -> MarketSubscriptions {
+        self.subscriptions.clone()
+    }
+
     /// Run the client, collecting messages until limit or shutdown
     ///
     /// # Arguments
@@ -87,213 +183,201 @@ impl MarketWsClient {
         output_path: &Path,
         limit: u64,
         shutdown: Arc<AtomicBool>,
-    ) -> Result<MessageStats> {
-        let mut stats = MessageStats::new();
-        let mut backoff_secs = INITIAL_BACKOFF_SECS;
-        let mut total_collected: u64 = 0;
-        let mut reconnect_count: u64 = 0;
-
-        // Timing for periodic flush and progress logging
-        let start_time = Instant::now();
-        let mut last_flush = Instant::now();
-        let mut last_progress_log = Instant::now();
-        let mut last_flush_count: u64 = 0;
-
-        // Create output file
-        let mut file = File::create(output_path).await.context("Failed to create output file")?;
-
-        info!("Starting market channel client, output: {}", output_path.display());
-
-        while !shutdown.load(Ordering::Relaxed) {
-            match self.connect_and_subscribe().await {
-                Ok((mut write, mut read)) => {
-                    info!("Connected and subscribed to market channel");
-                    backoff_secs = INITIAL_BACKOFF_SECS; // Reset backoff on success
-
-                    // Application-level PING timer (Polymarket requirement)
-                    let mut ping_interval =
-                        tokio::time::interval(Duration::from_secs(APP_PING_INTERVAL_SECS));
-                    ping_interval.tick().await; // Skip first immediate tick
-
-                    // Read messages using select! to handle both reading and sending PINGs
-                    loop {
-                        // Check shutdown
-                        if shutdown.load(Ordering::Relaxed) {
-                            break;
-                        }
-
-                        // Check limit
-                        if limit > 0 && total_collected >= limit {
-                            info!("Reached message limit: {}", limit);
-                            file.flush().await?;
-                            return Ok(stats);
-                        }
-
-                        // Periodic progress logging (every 60s)
-                        if last_progress_log.elapsed() >= Duration::from_secs(LOG_PROGRESS_EVERY_SECS)
-                        {
-                            let uptime_secs = start_time.elapsed().as_secs();
-                            info!(
-                                "Progress: uptime={}s total={} parsed_ok={} unknown={} reconnects={}",
-                                uptime_secs,
-                                stats.total_messages,
-                                stats.parsed_ok,
-                                stats.unknown_type_count,
-                                reconnect_count
-                            );
-                            last_progress_log = Instant::now();
-                        }
-
-                        // Use select! to handle both message reading and PING sending
-                        tokio::select! {
-                            // Application-level PING (every 10 seconds)
-                            _ = ping_interval.tick() => {
-                                debug!("Sending application-level PING");
-                                if let Err(e) = write.send(Message::Text("PING".into())).await {
-                                    warn!("Failed to send PING: {}", e);
-                                    break;
-                                }
-                            }
-
-                            // Read incoming messages
-                            msg = read.next() => {
-                                match msg {
-                                    Some(Ok(Message::Text(text))) => {
-                                        // Skip PONG responses (server echoes back)
-                                        let text_str: &str = text.as_ref();
-                                        if text_str == "PONG" {
-                                            debug!("Received PONG");
-                                            continue;
-                                        }
-
-                                        // Write raw to file (JSONL format)
-                                        file.write_all(text.as_bytes()).await?;
-                                        file.write_all(b"\n").await?;
-
-                                        // Parse and record stats
-                                        let parsed = WsInboundMessage::parse(&text);
-                                        stats.record(&parsed);
-                                        total_collected += 1;
-
-                                        // Periodic flush: every N messages or every T seconds
-                                        let msgs_since_flush = total_collected - last_flush_count;
-                                        if msgs_since_flush >= FLUSH_EVERY_MSGS
-                                            || last_flush.elapsed() >= Duration::from_secs(FLUSH_EVERY_SECS)
-                                        {
-                                            file.flush().await?;
-                                            last_flush = Instant::now();
-                                            last_flush_count = total_collected;
-                                        }
-
-                                        if total_collected % 100 == 0 {
-                                            debug!(
-                                                "Collected {} messages, {} unknown",
-                                                total_collected, stats.unknown_type_count
-                                            );
-                                        }
-                                    }
-                                    Some(Ok(Message::Ping(data))) => {
-                                        // Respond to WebSocket-level ping (if server sends it)
-                                        if let Err(e) = write.send(Message::Pong(data)).await {
-                                            warn!("Failed to send pong: {}", e);
-                                        }
-                                    }
-                                    Some(Ok(Message::Close(_))) => {
-                                        info!("Server closed connection");
-                                        break;
-                                    }
-                                    Some(Ok(_)) => {
-                                        // Binary or other message types - ignore
-                                    }
-                                    Some(Err(e)) => {
-                                        warn!("WebSocket error: {}", e);
-                                        break;
-                                    }
-                                    None => {
-                                        info!("WebSocket stream ended");
-                                        break;
-                                    }
-                                }
-                            }
-                        }
-                    }
-
-                    // Flush file before reconnect
-                    file.flush().await?;
-                }
-                Err(e) => {
-                    error!("Connection failed: {}", e);
-                }
-            }
+    ) -> AdapterResult<MessageStats> {
+        let recorder = JsonlRecorder::create(output_path).await?;
+        self.run_with(Some(recorder), None, limit, shutdown).await
+    }
 
-            // Check shutdown before reconnect
-            if shutdown.load(Ordering::Relaxed) {
-                break;
-            }
+    /// Spawn the client on the runtime and stream parsed messages through a channel
+    ///
+    /// The returned receiver yields every parsed message in arrival order. The
+    /// client stops when `shutdown` is set or the receiver is dropped; the join
+    /// handle resolves to the final stats.
+    ///
+    /// # Arguments
+    /// * `buffer` - Channel capacity; a slow consumer applies backpressure to the socket reader
+    /// * `recorder` - Optional JSONL sink, written before each message is sent
+    /// * `shutdown` - Atomic flag to signal shutdown
+    pub fn spawn_stream(
+        self,
+        buffer: usize,
+        recorder: Option<JsonlRecorder>,
+        shutdown: Arc<AtomicBool>,
+    ) -> (mpsc::Receiver<WsInboundMessage>, JoinHandle<AdapterResult<MessageStats>>) {
+        let (tx, rx) = mpsc::channel(buffer);
+        let handle =
+            tokio::spawn(async move { self.run_with(recorder, Some(tx), 0, shutdown).await });
+        (rx, handle)
+    }
 
-            // Exponential backoff
-            reconnect_count += 1;
-            warn!("Reconnecting in {} seconds... (reconnect #{})", backoff_secs, reconnect_count);
-            tokio::time::sleep(Duration::from_secs(backoff_secs)).await;
-            backoff_secs = (backoff_secs * 2).min(MAX_BACKOFF_SECS);
-        }
+    /// Run the client with optional JSONL recording and optional channel output
+    ///
+    /// # Arguments
+    /// * `recorder` - Optional JSONL sink for raw messages
+    /// * `sender` - Optional channel receiving each parsed message
+    /// * `limit` - Maximum messages to collect (0 = unlimited)
+    /// * `shutdown` - Atomic flag to signal shutdown
+    pub async fn run_with(
+        &self,
+        recorder: Option<JsonlRecorder>,
+        sender: Option<mpsc::Sender<WsInboundMessage>>,
+        limit: u64,
+        shutdown: Arc<AtomicBool>,
+    ) -> AdapterResult<MessageStats> {
+        let channel = MarketChannel {
+            subscriptions: self.subscriptions.clone(),
+            enable_features: self.enable_features,
+            watchdog: self.watchdog.clone(),
+            pending: Mutex::new(HashSet::new()),
+        };
 
-        // Final flush
-        file.flush().await?;
+        // Only one run loop can own the subscription command queue
+        let mut commands = self.commands.lock().await;
+        WsConnection::new(&self.endpoint, channel)
+            .with_policy(self.reconnect.clone())
+            .with_status(self.status.clone())
+            .run(Some(&mut commands), recorder, sender, limit, shutdown)
+            .await
+    }
+}
 
-        let uptime_secs = start_time.elapsed().as_secs();
-        info!(
-            "Market client stopped. uptime={}s total={} parsed_ok={} unknown={} reconnects={}",
-            uptime_secs, stats.total_messages, stats.parsed_ok, stats.unknown_type_count, reconnect_count
-        );
+/// Market channel protocol: asset subscribe, live subscription changes, stale-feed checks
+struct MarketChannel {
+    subscriptions: MarketSubscriptions,
+    enable_features: bool,
+    watchdog: FeedWatchdog,
+    /// Assets subscribed on this connection still waiting for their snapshot
+    pending: Mutex<HashSet<String>>,
+}
 
-        Ok(stats)
+impl MarketChannel {
+    fn expect_snapshots(&self, ids: &[String]) {
+        self.pending.lock().unwrap().extend(ids.iter().cloned());
     }
+}
 
-    /// Connect and subscribe to the market channel
-    async fn connect_and_subscribe(
-        &self,
-    ) -> Result<(
-        futures_util::stream::SplitSink<
-            tokio_tungstenite::WebSocketStream<
-                tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
-            >,
-            Message,
-        >,
-        futures_util::stream::SplitStream<
-            tokio_tungstenite::WebSocketStream<
-                tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
-            >,
-        >,
-    )> {
-        info!("Connecting to {}", self.endpoint);
-
-        let (ws_stream, response) = match connect_async(&self.endpoint).await {
-            Ok(r) => r,
-            Err(e) => {
-                error!("WebSocket connection error: {:?}", e);
-                return Err(anyhow::anyhow!("WebSocket connection failed: {}", e));
+impl WsChannel for MarketChannel {
+    type Command = SubscriptionCommand;
+
+    fn name(&self) -> &'static str {
+        "market"
+    }
+
+    /// Subscribe with the current (possibly changed) asset set
+    fn subscribe_message(&self) -> AdapterResult<String> {
+        let asset_ids = self.subscriptions.current();
+        let subscribe_req = SubscribeRequest::market(asset_ids.clone(), self.enable_features);
+        let subscribe_json = serde_json::to_string(&subscribe_req)
+            .map_err(|e| AdapterError::decode("subscribe request", e))?;
+
+        info!("Subscribing to {} assets: {:?}", asset_ids.len(), &asset_ids);
+        debug!("Subscribe request: {}", subscribe_json);
+        Ok(subscribe_json)
+    }
+
+    fn command_message(&self, command: SubscriptionCommand) -> AdapterResult<String> {
+        let change = match command {
+            SubscriptionCommand::Subscribe(ids) => {
+                info!("Subscribing to {} additional assets: {:?}", ids.len(), ids);
+                self.expect_snapshots(&ids);
+                SubscriptionChange::subscribe_assets(ids, self.enable_features)
+            }
+            SubscriptionCommand::Unsubscribe(ids) => {
+                info!("Unsubscribing from {} assets: {:?}", ids.len(), ids);
+                self.pending.lock().unwrap().retain(|id| !ids.contains(id));
+                SubscriptionChange::unsubscribe_assets(ids)
             }
         };
+        serde_json::to_string(&change).map_err(|e| AdapterError::decode("subscription change", e))
+    }
 
-        debug!("WebSocket connected, status: {}", response.status());
+    fn subscribed_ids(&self) -> Vec<String> {
+        self.subscriptions.current()
+    }
 
-        let (mut write, read) = ws_stream.split();
+    fn on_connected(&self) {
+        // Snapshots for every asset follow the subscribe
+        self.watchdog.grace();
+        *self.pending.lock().unwrap() = self.subscriptions.current().into_iter().collect();
+    }
 
-        // Send subscription request
-        let subscribe_req = SubscribeRequest::market(self.asset_ids.clone(), self.enable_features);
-        let subscribe_json = serde_json::to_string(&subscribe_req)?;
+    /// Record for the watchdog; a snapshot resyncs the assets waiting for one
+    fn on_message(&self, msg: &WsInboundMessage) -> Vec<String> {
+        self.watchdog.record(msg);
 
-        info!("Subscribing to {} assets: {:?}", self.asset_ids.len(), &self.asset_ids);
-        debug!("Subscribe request: {}", subscribe_json);
+        let is_snapshot = matches!(
+            msg,
+            WsInboundMessage::Market(MarketMessage::Book(_)) | WsInboundMessage::SnapshotArray(_)
+        );
+        if !is_snapshot {
+            return Vec::new();
+        }
+        let mut pending = self.pending.lock().unwrap();
+        msg.asset_ids().into_iter().filter(|id| pending.remove(*id)).map(String::from).collect()
+    }
 
-        write
-            .send(Message::Text(subscribe_json.into()))
-            .await
-            .context("Failed to send subscribe request")?;
+    fn take_events(&self, conn_id: u64) -> Vec<LifecycleEvent> {
+        self.watchdog
+            .take_events()
+            .into_iter()
+            .map(|event| match event {
+                StaleEvent::Stale { asset_id, silent_for } => LifecycleEvent::Stale {
+                    conn_id,
+                    asset_id,
+                    silent_for_ms: silent_for.as_millis() as u64,
+                },
+                StaleEvent::Recovered { asset_id, silent_for } => LifecycleEvent::Recovered {
+                    conn_id,
+                    asset_id,
+                    silent_for_ms: silent_for.as_millis() as u64,
+                },
+            })
+            .collect()
+    }
+
+    fn check_interval(&self) -> Option<Duration> {
+        self.watchdog.config().map(|c| c.check_interval)
+    }
+
+    fn check(&self) -> AdapterResult<ChannelAction> {
+        let Some(config) = self.watchdog.config() else {
+            return Ok(ChannelAction::None);
+        };
+        let stale = self.watchdog.check(&self.subscriptions.current());
+        if stale.is_empty() {
+            return Ok(ChannelAction::None);
+        }
 
-        Ok((write, read))
+        match config.action {
+            StaleAction::Report => Ok(ChannelAction::None),
+            StaleAction::Resubscribe => {
+                info!("Resubscribing {} stale assets: {:?}", stale.len(), stale);
+                self.expect_snapshots(&stale);
+                let unsubscribe = SubscriptionChange::unsubscribe_assets(stale.clone());
+                let subscribe = SubscriptionChange::subscribe_assets(stale, self.enable_features);
+                let frames = [unsubscribe, subscribe]
+                    .iter()
+                    .map(serde_json::to_string)
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|e| AdapterError::decode("subscription change", e))?;
+                Ok(ChannelAction::Send(frames))
+            }
+            StaleAction::Reconnect => {
+                Ok(ChannelAction::Reconnect(format!("stale assets {:?}", stale)))
+            }
+        }
+    }
+}
+
+/// Remove duplicate ids, keeping first occurrence order
+fn dedup(ids: Vec<String>) -> Vec<String> {
+    let mut out: Vec<String> = Vec::with_capacity(ids.len());
+    for id in ids {
+        if !out.contains(&id) {
+            out.push(id);
+        }
     }
+    out
 }
 
 /// Generate timestamped output filename
@@ -336,11 +420,13 @@ pub async fn smoke_test_market_ws(asset_id: &str) -> Result<MessageStats> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use futures_util::{SinkExt, StreamExt};
+    use tokio_tungstenite::tungstenite::Message;
 
     #[test]
     fn test_client_creation() {
         let client = MarketWsClient::new(vec!["test".to_string()]);
-        assert_eq!(client.asset_ids, vec!["test"]);
+        assert_eq!(client.subscriptions().current(), vec!["test"]);
         assert_eq!(client.endpoint, CLOB_WSS_ENDPOINT);
     }
 
@@ -350,4 +436,266 @@ mod tests {
         assert!(filename.starts_with("test_"));
         assert!(filename.ends_with(".jsonl"));
     }
+
+    /// Test: spawn_stream delivers parsed messages and stops when the receiver is dropped
+    #[tokio::test]
+    async fn test_spawn_stream_delivers_messages() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        // Minimal market channel server: expect a subscribe, then push messages
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let subscribe = ws.next().await.unwrap().unwrap();
+            assert!(subscribe.to_text().unwrap().contains("assets_ids"));
+
+            let book = r#"{"event_type":"book","asset_id":"token123","market":"cond","timestamp":"1","bids":[],"asks":[]}"#;
+            for _ in 0..5 {
+                if ws.send(Message::Text(book.into())).await.is_err() {
+                    return;
+                }
+            }
+            // Keep the socket open until the client goes away
+            while let Some(Ok(_)) = ws.next().await {}
+        });
+
+        let client =
+            MarketWsClient::with_endpoint(&format!("ws://{}", addr), vec!["token123".to_string()]);
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let (mut rx, handle) = client.spawn_stream(1, None, shutdown);
+
+        // Lifecycle events frame the first snapshot
+        for expected in ["connected", "subscribed", "book", "resynced", "book"] {
+            let msg = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+                .await
+                .expect("timed out waiting for message")
+                .expect("stream ended early");
+            assert_eq!(msg.event_type(), Some(expected));
+        }
+
+        drop(rx);
+        let stats = tokio::time::timeout(Duration::from_secs(5), handle)
+            .await
+            .expect("client did not stop after receiver was dropped")
+            .unwrap()
+            .unwrap();
+        assert!(stats.total_messages >= 2);
+    }
+
+    #[test]
+    fn test_subscriptions_dedup_and_remove() {
+        let client = MarketWsClient::new(vec!["a".to_string(), "a".to_string()]);
+        let subs = client.subscriptions();
+        assert_eq!(subs.current(), vec!["a"]);
+
+        subs.subscribe(vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(subs.current(), vec!["a", "b"]);
+
+        subs.unsubscribe(vec!["a".to_string(), "zzz".to_string()]);
+        assert_eq!(subs.current(), vec!["b"]);
+    }
+
+    async fn next(rx: &mut mpsc::UnboundedReceiver<String>) -> String {
+        tokio::time::timeout(Duration::from_secs(5), rx.recv()).await.unwrap().unwrap()
+    }
+
+    /// Test: dynamic subscribe is sent on the live socket and replayed after reconnect
+    #[tokio::test]
+    async fn test_dynamic_subscribe_and_replay_on_reconnect() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (seen_tx, mut seen_rx) = mpsc::unbounded_channel::<String>();
+
+        tokio::spawn(async move {
+            // First connection: record subscribe + one dynamic change, then drop the socket
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            for _ in 0..2 {
+                let msg = ws.next().await.unwrap().unwrap();
+                seen_tx.send(msg.to_text().unwrap().to_string()).unwrap();
+            }
+            drop(ws);
+
+            // Second connection: record the replayed subscribe
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let msg = ws.next().await.unwrap().unwrap();
+            seen_tx.send(msg.to_text().unwrap().to_string()).unwrap();
+            while let Some(Ok(_)) = ws.next().await {}
+        });
+
+        let client =
+            MarketWsClient::with_endpoint(&format!("ws://{}", addr), vec!["old".to_string()]);
+        let subs = client.subscriptions();
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let (_rx, handle) = client.spawn_stream(16, None, shutdown.clone());
+
+        let initial = next(&mut seen_rx).await;
+        assert!(initial.contains("\"assets_ids\":[\"old\"]"));
+
+        subs.subscribe(vec!["new".to_string()]);
+        let change = next(&mut seen_rx).await;
+        assert!(change.contains("\"operation\":\"subscribe\""));
+        assert!(change.contains("\"asset_ids\":[\"new\"]"));
+
+        subs.unsubscribe(vec!["old".to_string()]);
+        let replayed = next(&mut seen_rx).await;
+        assert!(replayed.contains("\"assets_ids\":[\"new\"]"), "got {}", replayed);
+
+        shutdown.store(true, Ordering::Relaxed);
+        handle.abort();
+    }
+
+    /// Test: lifecycle events mark the gap between connections and the resync after it
+    #[tokio::test]
+    async fn test_lifecycle_events_across_reconnect() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let book = r#"{"event_type":"book","asset_id":"a","market":"cond","timestamp":"1","bids":[],"asks":[]}"#;
+            let change = r#"{"event_type":"price_change","market":"cond","timestamp":"2","price_changes":[{"asset_id":"a","price":"0.5","size":"1","side":"BUY"}]}"#;
+
+            // First connection: snapshot, one update, then close
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            ws.next().await.unwrap().unwrap();
+            ws.send(Message::Text(book.into())).await.unwrap();
+            ws.send(Message::Text(change.into())).await.unwrap();
+            ws.send(Message::Close(None)).await.unwrap();
+            drop(ws);
+
+            // Second connection: update before the snapshot, then snapshot
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            ws.next().await.unwrap().unwrap();
+            ws.send(Message::Text(change.into())).await.unwrap();
+            ws.send(Message::Text(book.into())).await.unwrap();
+            while let Some(Ok(_)) = ws.next().await {}
+        });
+
+        let mut client =
+            MarketWsClient::with_endpoint(&format!("ws://{}", addr), vec!["a".to_string()]);
+        let mut policy = ReconnectPolicy::default();
+        policy.initial = Duration::from_millis(10);
+        policy.jitter = 0.0;
+        client.set_reconnect_policy(policy);
+        let (mut rx, handle) = client.spawn_stream(64, None, Arc::new(AtomicBool::new(false)));
+
+        let mut events = Vec::new();
+        while events.len() < 11 {
+            let msg = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+                .await
+                .expect("timed out waiting for message")
+                .expect("stream ended early");
+            events.push(msg);
+        }
+        let kinds: Vec<&str> = events.iter().filter_map(|m| m.event_type()).collect();
+        assert_eq!(
+            kinds,
+            [
+                "connected",
+                "subscribed",
+                "book",
+                "resynced",
+                "price_change",
+                "disconnected",
+                "connected",
+                "subscribed",
+                "price_change",
+                "book",
+                "resynced",
+            ]
+        );
+
+        let first = events[0].lifecycle().unwrap().conn_id();
+        let second = events[6].lifecycle().unwrap().conn_id();
+        assert_ne!(first, second);
+        assert_eq!(
+            events[1].lifecycle(),
+            Some(&LifecycleEvent::Subscribed { conn_id: first, ids: vec!["a".to_string()] })
+        );
+        assert_eq!(
+            events[5].lifecycle(),
+            Some(&LifecycleEvent::Disconnected {
+                conn_id: first,
+                ids: vec!["a".to_string()],
+                reason: "server closed connection".to_string()
+            })
+        );
+        assert_eq!(
+            events[6].lifecycle(),
+            Some(&LifecycleEvent::Connected { conn_id: second, epoch: 1 })
+        );
+        assert_eq!(
+            events[10].lifecycle(),
+            Some(&LifecycleEvent::Resynced { conn_id: second, asset_id: "a".to_string() })
+        );
+
+        handle.abort();
+    }
+
+    /// Test: a silent asset goes stale and is resubscribed while the other keeps flowing
+    #[tokio::test]
+    async fn test_watchdog_resubscribes_silent_asset() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (seen_tx, mut seen_rx) = mpsc::unbounded_channel::<String>();
+
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let (mut write, mut read) = ws.split();
+            // Only "a" ever publishes
+            tokio::spawn(async move {
+                let book = r#"{"event_type":"book","asset_id":"a","market":"cond","timestamp":"1","bids":[],"asks":[]}"#;
+                while write.send(Message::Text(book.into())).await.is_ok() {
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                }
+            });
+            while let Some(Ok(msg)) = read.next().await {
+                let text = msg.to_text().unwrap_or_default().to_string();
+                if text != "PING" && seen_tx.send(text).is_err() {
+                    return;
+                }
+            }
+        });
+
+        let mut client = MarketWsClient::with_endpoint(
+            &format!("ws://{}", addr),
+            vec!["a".to_string(), "b".to_string()],
+        );
+        client.set_watchdog(WatchdogConfig {
+            stale_after: Duration::from_millis(200),
+            check_interval: Duration::from_millis(20),
+            action: StaleAction::Resubscribe,
+        });
+        let watchdog = client.watchdog();
+        let (mut rx, handle) = client.spawn_stream(1024, None, Arc::new(AtomicBool::new(false)));
+
+        let initial = next(&mut seen_rx).await;
+        assert!(initial.contains("\"assets_ids\":[\"a\",\"b\"]"));
+        let unsubscribe = next(&mut seen_rx).await;
+        assert!(unsubscribe.contains("\"operation\":\"unsubscribe\""));
+        assert!(unsubscribe.contains("\"asset_ids\":[\"b\"]"), "got {}", unsubscribe);
+        let resubscribe = next(&mut seen_rx).await;
+        assert!(resubscribe.contains("\"operation\":\"subscribe\""));
+        assert!(resubscribe.contains("\"asset_ids\":[\"b\"]"), "got {}", resubscribe);
+
+        assert_eq!(watchdog.stale_assets(), ["b"]);
+
+        // Delivered in the stream, not left in the watchdog
+        let stale = loop {
+            let msg =
+                tokio::time::timeout(Duration::from_secs(5), rx.recv()).await.unwrap().unwrap();
+            if let Some(event @ LifecycleEvent::Stale { .. }) = msg.lifecycle() {
+                break event.clone();
+            }
+        };
+        assert!(matches!(stale, LifecycleEvent::Stale { asset_id, .. } if asset_id == "b"));
+        assert!(watchdog.take_events().is_empty());
+
+        handle.abort();
+    }
 }
@@ -6,6 +6,16 @@
 //! User channel requires L2 credentials (apiKey, secret, passphrase)
 //! passed in the subscription message's `auth` field.
 //!
+//! # Consumers
+//! - Raw or enveloped JSONL recording (optional sink, see `recorder`)
+//! - Parsed messages via `tokio::sync::mpsc` (`spawn_stream`)
+//! - `connection_counter` increments on every successful subscribe, so order
+//!   state can be reconciled over REST after a reconnect (`OrderKeeper`)
+//!
+//! # Keepalive
+//! Same `connection::WsConnection` loop as the market channel: literal "PING"
+//! text every 10 seconds, reconnect per `ReconnectPolicy`, health via `feed_status`.
+//!
 //! # Source
 //! - WSS Overview: https://docs.polymarket.com/developers/CLOB/websocket/wss-overview
 //! - User Channel: https://docs.polymarket.com/developers/CLOB/websocket/user-channel
@@ -13,40 +23,30 @@
 
 use anyhow::{Context, Result};
 use chrono::Utc;
-use futures_util::{SinkExt, StreamExt};
+use std::convert::Infallible;
 use std::path::Path;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::{Duration, Instant};
-use tokio::fs::File;
-use tokio::io::AsyncWriteExt;
-use tokio_tungstenite::{connect_async, tungstenite::Message};
-use tracing::{debug, error, info, warn};
+use std::time::Duration;
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
+use tracing::{debug, info};
 
+use crate::error::{AdapterError, AdapterResult};
 use crate::httpws::auth::ApiCredentials;
+use crate::httpws::connection::{FeedStatus, ReconnectPolicy, WsChannel, WsConnection};
+use crate::httpws::recorder::JsonlRecorder;
 use crate::types::{MessageStats, SubscribeRequest, WsAuth, WsInboundMessage};
 use crate::CLOB_WSS_ENDPOINT;
 
-/// Maximum reconnection backoff interval
-const MAX_BACKOFF_SECS: u64 = 30;
-
-/// Initial backoff interval
-const INITIAL_BACKOFF_SECS: u64 = 1;
-
-/// Flush file every N messages
-const FLUSH_EVERY_MSGS: u64 = 200;
-
-/// Flush file at least every N seconds
-const FLUSH_EVERY_SECS: u64 = 5;
-
-/// Log progress every N seconds
-const LOG_PROGRESS_EVERY_SECS: u64 = 60;
-
 /// User channel WebSocket client
 pub struct UserWsClient {
     endpoint: String,
     credentials: ApiCredentials,
     market_ids: Vec<String>,
+    connections: Arc<AtomicU64>,
+    reconnect: ReconnectPolicy,
+    status: watch::Sender<FeedStatus>,
 }
 
 impl UserWsClient {
@@ -56,7 +56,7 @@ impl UserWsClient {
     /// * `credentials` - L2 API credentials (apiKey, secret, passphrase)
     /// * `market_ids` - Condition IDs to subscribe to
     pub fn new(credentials: ApiCredentials, market_ids: Vec<String>) -> Self {
-        Self { endpoint: CLOB_WSS_ENDPOINT.to_string(), credentials, market_ids }
+        Self::with_endpoint(CLOB_WSS_ENDPOINT, credentials, market_ids)
     }
 
     /// Create with custom endpoint (for testing)
@@ -65,7 +65,31 @@ impl UserWsClient {
         credentials: ApiCredentials,
         market_ids: Vec<String>,
     ) -> Self {
-        Self { endpoint: endpoint.to_string(), credentials, market_ids }
+        Self {
+            endpoint: endpoint.to_string(),
+            credentials,
+            market_ids,
+            connections: Arc::new(AtomicU64::new(0)),
+            reconnect: ReconnectPolicy::default(),
+            status: watch::Sender::new(FeedStatus::default()),
+        }
+    }
+
+    /// Set backoff, circuit breaker and give-up behaviour
+    pub fn set_reconnect_policy(&mut self, policy: ReconnectPolicy) {
+        self.reconnect = policy;
+    }
+
+    /// Watch connection health (`FeedStatus::Down` when the circuit is open)
+    pub fn feed_status(&self) -> watch::Receiver<FeedStatus> {
+        self.status.subscribe()
+    }
+
+    /// Counter of successful subscribes (1 after the first connect)
+    ///
+    /// A change means messages may have been missed while disconnected.
+    pub fn connection_counter(&self) -> Arc<AtomicU64> {
+        self.connections.clone()
     }
 
     /// Run the client, collecting messages until limit or shutdown
@@ -79,188 +103,92 @@ impl UserWsClient {
         output_path: &Path,
         limit: u64,
         shutdown: Arc<AtomicBool>,
-    ) -> Result<MessageStats> {
-        let mut stats = MessageStats::new();
-        let mut backoff_secs = INITIAL_BACKOFF_SECS;
-        let mut total_collected: u64 = 0;
-        let mut reconnect_count: u64 = 0;
-
-        // Timing for periodic flush and progress logging
-        let start_time = Instant::now();
-        let mut last_flush = Instant::now();
-        let mut last_progress_log = Instant::now();
-        let mut last_flush_count: u64 = 0;
-
-        // Create output file
-        let mut file = File::create(output_path).await.context("Failed to create output file")?;
-
-        info!("Starting user channel client, output: {}", output_path.display());
-
-        while !shutdown.load(Ordering::Relaxed) {
-            match self.connect_and_subscribe().await {
-                Ok((mut write, mut read)) => {
-                    info!("Connected and subscribed to user channel");
-                    backoff_secs = INITIAL_BACKOFF_SECS; // Reset backoff on success
-
-                    // Read messages
-                    while !shutdown.load(Ordering::Relaxed) {
-                        // Check limit
-                        if limit > 0 && total_collected >= limit {
-                            info!("Reached message limit: {}", limit);
-                            file.flush().await?;
-                            return Ok(stats);
-                        }
-
-                        // Periodic progress logging (every 60s)
-                        if last_progress_log.elapsed() >= Duration::from_secs(LOG_PROGRESS_EVERY_SECS)
-                        {
-                            let uptime_secs = start_time.elapsed().as_secs();
-                            info!(
-                                "Progress: uptime={}s total={} parsed_ok={} unknown={} reconnects={}",
-                                uptime_secs,
-                                stats.total_messages,
-                                stats.parsed_ok,
-                                stats.unknown_type_count,
-                                reconnect_count
-                            );
-                            last_progress_log = Instant::now();
-                        }
-
-                        // Read with timeout for responsiveness
-                        let msg = tokio::time::timeout(Duration::from_secs(30), read.next()).await;
-
-                        match msg {
-                            Ok(Some(Ok(Message::Text(text)))) => {
-                                // Write raw to file (JSONL format)
-                                file.write_all(text.as_bytes()).await?;
-                                file.write_all(b"\n").await?;
-
-                                // Parse and record stats
-                                let parsed = WsInboundMessage::parse(&text);
-                                stats.record(&parsed);
-                                total_collected += 1;
-
-                                // Periodic flush: every N messages or every T seconds
-                                let msgs_since_flush = total_collected - last_flush_count;
-                                if msgs_since_flush >= FLUSH_EVERY_MSGS
-                                    || last_flush.elapsed() >= Duration::from_secs(FLUSH_EVERY_SECS)
-                                {
-                                    file.flush().await?;
-                                    last_flush = Instant::now();
-                                    last_flush_count = total_collected;
-                                }
-
-                                if total_collected % 10 == 0 {
-                                    debug!(
-                                        "Collected {} user messages, {} unknown",
-                                        total_collected, stats.unknown_type_count
-                                    );
-                                }
-                            }
-                            Ok(Some(Ok(Message::Ping(data)))) => {
-                                // Respond to ping
-                                if let Err(e) = write.send(Message::Pong(data)).await {
-                                    warn!("Failed to send pong: {}", e);
-                                }
-                            }
-                            Ok(Some(Ok(Message::Close(_)))) => {
-                                info!("Server closed connection");
-                                break;
-                            }
-                            Ok(Some(Ok(_))) => {
-                                // Binary or other message types - ignore
-                            }
-                            Ok(Some(Err(e))) => {
-                                warn!("WebSocket error: {}", e);
-                                break;
-                            }
-                            Ok(None) => {
-                                info!("WebSocket stream ended");
-                                break;
-                            }
-                            Err(_) => {
-                                // Timeout - send ping to keep alive
-                                debug!("Read timeout, sending ping");
-                                if let Err(e) = write.send(Message::Ping(vec![].into())).await {
-                                    warn!("Failed to send ping: {}", e);
-                                    break;
-                                }
-                            }
-                        }
-                    }
-
-                    // Flush file before reconnect
-                    file.flush().await?;
-                }
-                Err(e) => {
-                    error!("Connection failed: {}", e);
-                }
-            }
+    ) -> AdapterResult<MessageStats> {
+        let recorder = JsonlRecorder::create(output_path).await?;
+        self.run_with(Some(recorder), None, limit, shutdown).await
+    }
 
-            // Check shutdown before reconnect
-            if shutdown.load(Ordering::Relaxed) {
-                break;
-            }
+    /// Spawn the client on the runtime and stream parsed messages through a channel
+    ///
+    /// Same contract as `MarketWsClient::spawn_stream`.
+    pub fn spawn_stream(
+        self,
+        buffer: usize,
+        recorder: Option<JsonlRecorder>,
+        shutdown: Arc<AtomicBool>,
+    ) -> (mpsc::Receiver<WsInboundMessage>, JoinHandle<AdapterResult<MessageStats>>) {
+        let (tx, rx) = mpsc::channel(buffer);
+        let handle =
+            tokio::spawn(async move { self.run_with(recorder, Some(tx), 0, shutdown).await });
+        (rx, handle)
+    }
 
-            // Exponential backoff
-            reconnect_count += 1;
-            warn!("Reconnecting in {} seconds... (reconnect #{})", backoff_secs, reconnect_count);
-            tokio::time::sleep(Duration::from_secs(backoff_secs)).await;
-            backoff_secs = (backoff_secs * 2).min(MAX_BACKOFF_SECS);
-        }
+    /// Run the client with optional JSONL recording and optional channel output
+    ///
+    /// # Arguments
+    /// * `recorder` - Optional JSONL sink for raw messages
+    /// * `sender` - Optional channel receiving each parsed message
+    /// * `limit` - Maximum messages to collect (0 = unlimited)
+    /// * `shutdown` - Atomic flag to signal shutdown
+    pub async fn run_with(
+        &self,
+        recorder: Option<JsonlRecorder>,
+        sender: Option<mpsc::Sender<WsInboundMessage>>,
+        limit: u64,
+        shutdown: Arc<AtomicBool>,
+    ) -> AdapterResult<MessageStats> {
+        self.connection().run(None, recorder, sender, limit, shutdown).await
+    }
 
-        // Final flush
-        file.flush().await?;
+    fn connection(&self) -> WsConnection<UserChannel> {
+        let channel = UserChannel {
+            credentials: self.credentials.clone(),
+            market_ids: self.market_ids.clone(),
+            connections: self.connections.clone(),
+        };
+        WsConnection::new(&self.endpoint, channel)
+            .with_policy(self.reconnect.clone())
+            .with_status(self.status.clone())
+    }
+}
 
-        let uptime_secs = start_time.elapsed().as_secs();
-        info!(
-            "User client stopped. uptime={}s total={} parsed_ok={} unknown={} reconnects={}",
-            uptime_secs, stats.total_messages, stats.parsed_ok, stats.unknown_type_count, reconnect_count
-        );
+/// User channel protocol: authenticated market subscribe, no live commands
+struct UserChannel {
+    credentials: ApiCredentials,
+    market_ids: Vec<String>,
+    connections: Arc<AtomicU64>,
+}
+
+impl WsChannel for UserChannel {
+    type Command = Infallible;
 
-        Ok(stats)
+    fn name(&self) -> &'static str {
+        "user"
     }
 
-    /// Connect and subscribe to the user channel
-    async fn connect_and_subscribe(
-        &self,
-    ) -> Result<(
-        futures_util::stream::SplitSink<
-            tokio_tungstenite::WebSocketStream<
-                tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
-            >,
-            Message,
-        >,
-        futures_util::stream::SplitStream<
-            tokio_tungstenite::WebSocketStream<
-                tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
-            >,
-        >,
-    )> {
-        info!("Connecting to {}", self.endpoint);
-
-        let (ws_stream, response) =
-            connect_async(&self.endpoint).await.context("WebSocket connection failed")?;
-
-        debug!("WebSocket connected, status: {}", response.status());
-
-        let (mut write, read) = ws_stream.split();
-
-        // Send subscription request with auth
+    /// Subscribe with auth
+    fn subscribe_message(&self) -> AdapterResult<String> {
         let auth = WsAuth::from(&self.credentials);
         let subscribe_req = SubscribeRequest::user(auth, self.market_ids.clone());
-        let subscribe_json = serde_json::to_string(&subscribe_req)?;
+        let subscribe_json = serde_json::to_string(&subscribe_req)
+            .map_err(|e| AdapterError::decode("subscribe request", e))?;
 
         info!("Subscribing to {} markets with authentication", self.market_ids.len());
         // Don't log the full request as it contains credentials
         debug!("Subscribe request: [REDACTED - contains auth]");
+        Ok(subscribe_json)
+    }
+
+    fn command_message(&self, command: Infallible) -> AdapterResult<String> {
+        match command {}
+    }
 
-        write
-            .send(Message::Text(subscribe_json.into()))
-            .await
-            .context("Failed to send subscribe request")?;
+    fn subscribed_ids(&self) -> Vec<String> {
+        self.market_ids.clone()
+    }
 
-        Ok((write, read))
+    fn on_connected(&self) {
+        self.connections.fetch_add(1, Ordering::SeqCst);
     }
 }
 
@@ -312,6 +240,9 @@ pub async fn smoke_test_user_ws(market_ids: Vec<String>) -> Result<MessageStats>
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::LifecycleEvent;
+    use futures_util::{SinkExt, StreamExt};
+    use tokio_tungstenite::tungstenite::Message;
 
     #[test]
     fn test_output_filename_generation() {
@@ -319,4 +250,61 @@ mod tests {
         assert!(filename.starts_with("ws_user_"));
         assert!(filename.ends_with(".jsonl"));
     }
+
+    fn test_credentials() -> ApiCredentials {
+        ApiCredentials {
+            api_key: "key".to_string(),
+            secret: "c2VjcmV0".to_string(),
+            passphrase: "pass".to_string(),
+        }
+    }
+
+    /// Test: idle user connection sends the literal "PING" and swallows the "PONG"
+    ///
+    /// Paused clock: the 10s PING interval elapses as soon as the client is idle.
+    #[tokio::test(start_paused = true)]
+    async fn test_app_level_ping_keepalive() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (seen_tx, mut seen_rx) = mpsc::unbounded_channel::<String>();
+
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            for _ in 0..2 {
+                let msg = ws.next().await.unwrap().unwrap();
+                seen_tx.send(msg.to_text().unwrap().to_string()).unwrap();
+            }
+            ws.send(Message::Text("PONG".into())).await.unwrap();
+            let order = r#"{"event_type":"order","id":"0x1","market":"cond","asset_id":"up","side":"BUY","price":"0.5","original_size":"10","size_matched":"0","type":"PLACEMENT","timestamp":"1"}"#;
+            ws.send(Message::Text(order.into())).await.unwrap();
+            while let Some(Ok(_)) = ws.next().await {}
+        });
+
+        let client = UserWsClient::with_endpoint(
+            &format!("ws://{}", addr),
+            test_credentials(),
+            vec!["cond".to_string()],
+        );
+        let connections = client.connection_counter();
+        let (mut rx, handle) = client.spawn_stream(4, None, Arc::new(AtomicBool::new(false)));
+
+        let subscribe = seen_rx.recv().await.unwrap();
+        assert!(subscribe.contains("\"auth\""));
+        assert_eq!(seen_rx.recv().await.unwrap(), "PING");
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+
+        // PONG is not delivered; the order message is (after the lifecycle events)
+        assert_eq!(rx.recv().await.unwrap().event_type(), Some("connected"));
+        let subscribed = rx.recv().await.unwrap();
+        assert!(matches!(
+            subscribed.lifecycle(),
+            Some(LifecycleEvent::Subscribed { ids, .. }) if ids == &["cond"]
+        ));
+        let msg = rx.recv().await.unwrap();
+        assert_eq!(msg.event_type(), Some("order"));
+
+        drop(rx);
+        handle.abort();
+    }
 }
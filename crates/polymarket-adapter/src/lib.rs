@@ -1,9 +1,15 @@
 //! Polymarket CLOB Adapter
 //!
-//! Dual backend support:
+//! Dual backend support (common interface: `backend::ClobBackend`):
 //! - `httpws`: Custom REST + WebSocket implementation
 //! - `rsclob`: Official rs-clob-client wrapper (requires feature flag)
 //! - `gamma`: Gamma API client for market discovery and resolution
+//! - `book`: Local L2 order book reconstruction from market channel messages
+//! - `order_manager`: Order and fill lifecycle from user channel messages
+//! - `positions`: Per-token positions and PnL, Up/Down pairs as one binary position
+//! - `paper`: Paper-trading exchange filling simulated orders against market data
+//! - `replay`: Recorded JSONL replay through the live client stream interface
+//! - `risk`: Pre-trade risk limits, kill switch and series freezes
 //!
 //! # Official Documentation
 //! - Endpoints: https://docs.polymarket.com/quickstart/reference/endpoints
@@ -13,6 +19,14 @@
 //! - Authentication: https://docs.polymarket.com/developers/CLOB/authentication
 //! - Gamma Structure: https://docs.polymarket.com/developers/gamma-markets-api/gamma-structure
 
+pub mod backend;
+pub mod book;
+pub mod error;
+pub mod order_manager;
+pub mod paper;
+pub mod positions;
+pub mod replay;
+pub mod risk;
 pub mod types;
 
 #[cfg(feature = "httpws")]
@@ -24,6 +38,7 @@ pub mod rsclob;
 #[cfg(feature = "httpws")]
 pub mod gamma;
 
+pub use error::{AdapterError, AdapterResult};
 pub use types::*;
 
 /// Official CLOB REST API base URL
@@ -34,6 +49,10 @@ pub const CLOB_REST_BASE: &str = "https://clob.polymarket.com";
 /// Source: https://docs.polymarket.com/quickstart/reference/endpoints
 pub const GAMMA_API_BASE: &str = "https://gamma-api.polymarket.com";
 
+/// Polygon mainnet chain id (EIP-712 domains for auth and orders)
+/// Source: https://docs.polymarket.com/developers/CLOB/authentication
+pub const POLYGON_CHAIN_ID: u64 = 137;
+
 /// Official CLOB WebSocket endpoint for Market Channel
 /// Source: https://docs.polymarket.com/developers/CLOB/websocket/market-channel
 /// Note: Use /ws/market for market channel, /ws/user for user channel
@@ -0,0 +1,966 @@
+//! Order lifecycle tracking from user channel messages
+//!
+//! # Order States
+//! - `Pending`: posted over REST, not yet seen on the user channel
+//! - `Live`: PLACEMENT seen (or listed by REST open orders); may be partially matched
+//! - `Filled`: `size_matched` reached `original_size`
+//! - `Cancelled`: CANCELLATION seen, or the order vanished from REST open orders
+//!
+//! Filled and Cancelled are terminal for channel messages, so late or
+//! duplicated events are harmless. Only a REST reconcile can reopen an order.
+//!
+//! # Trade States
+//! MATCHED → MINED → CONFIRMED, with RETRYING after MINED and FAILED as the
+//! other terminal state. Each (trade, order) pair we own becomes one `Fill`;
+//! a trade can fill our taker order, one or more of our maker orders, or both.
+//!
+//! # Reconciliation
+//! Messages sent while the user channel is down are lost. `OrderKeeper`
+//! (httpws backend) reloads `GET /data/orders` whenever the user client's
+//! connection counter changes. Fills missed during the outage are not recovered.
+//!
+//! # Source
+//! - User Channel: https://docs.polymarket.com/developers/CLOB/websocket/user-channel
+//! - Orders: https://docs.polymarket.com/developers/CLOB/orders/get-active-order
+
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "httpws")]
+use std::sync::atomic::{AtomicU64, Ordering};
+#[cfg(feature = "httpws")]
+use std::sync::Arc;
+
+#[cfg(feature = "httpws")]
+use tracing::info;
+use tracing::{debug, warn};
+
+#[cfg(feature = "httpws")]
+use crate::error::AdapterResult;
+#[cfg(feature = "httpws")]
+use crate::httpws::{OrderArgs, OrderBuilder, RestClient};
+#[cfg(feature = "httpws")]
+use crate::types::{CancelResponse, OrderType, PostOrderResponse};
+use crate::types::{
+    OpenOrder, OrderMessage, OrderSide, TradeMessage, UserMessage, WsInboundMessage,
+};
+
+/// Lifecycle state of one order
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OrderStatus {
+    /// Posted, not yet acknowledged on the user channel
+    Pending,
+    /// Resting on the book (possibly partially matched)
+    Live,
+    /// Fully matched
+    Filled,
+    /// Cancelled or expired
+    Cancelled,
+}
+
+impl OrderStatus {
+    /// Pending or live
+    pub fn is_open(&self) -> bool {
+        matches!(self, OrderStatus::Pending | OrderStatus::Live)
+    }
+}
+
+/// Settlement state of a trade
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TradeStatus {
+    /// Matched by the operator, sent for settlement
+    Matched,
+    /// Settlement transaction mined
+    Mined,
+    /// Settlement final
+    Confirmed,
+    /// Settlement transaction failed and is being retried
+    Retrying,
+    /// Settlement failed permanently
+    Failed,
+}
+
+impl TradeStatus {
+    /// Parse the `status` field of a trade message (case-insensitive)
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_uppercase().as_str() {
+            "MATCHED" => Some(TradeStatus::Matched),
+            "MINED" => Some(TradeStatus::Mined),
+            "CONFIRMED" => Some(TradeStatus::Confirmed),
+            "RETRYING" => Some(TradeStatus::Retrying),
+            "FAILED" => Some(TradeStatus::Failed),
+            _ => None,
+        }
+    }
+
+    /// Confirmed or failed
+    pub fn is_final(&self) -> bool {
+        matches!(self, TradeStatus::Confirmed | TradeStatus::Failed)
+    }
+
+    /// Whether `next` is a valid successor of this state
+    pub fn can_transition_to(&self, next: TradeStatus) -> bool {
+        use TradeStatus::*;
+
+        match (self, next) {
+            (current, next) if *current == next => false,
+            (Confirmed | Failed, _) => false,
+            (Matched, _) => true,
+            (Mined, Confirmed | Retrying | Failed) => true,
+            (Retrying, Mined | Confirmed | Failed) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Whether a fill provided or took liquidity
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Liquidity {
+    /// Our resting order was hit
+    Maker,
+    /// Our order crossed the book
+    Taker,
+}
+
+/// Tracked order
+#[derive(Clone, Debug, PartialEq)]
+pub struct ManagedOrder {
+    /// Order id (hash)
+    pub id: String,
+    /// Condition ID (empty until the first channel message for a pending order)
+    pub market: String,
+    /// Token identifier
+    pub asset_id: String,
+    /// Order side
+    pub side: OrderSide,
+    /// Limit price
+    pub price: String,
+    /// Initial order size
+    pub original_size: String,
+    /// Matched quantity
+    pub size_matched: String,
+    /// Lifecycle state
+    pub status: OrderStatus,
+    /// Timestamp of the last message applied
+    pub timestamp: Option<i64>,
+}
+
+impl ManagedOrder {
+    /// Initial size as f64
+    pub fn original_size_f64(&self) -> f64 {
+        self.original_size.parse().unwrap_or(0.0)
+    }
+
+    /// Matched size as f64
+    pub fn size_matched_f64(&self) -> f64 {
+        self.size_matched.parse().unwrap_or(0.0)
+    }
+
+    /// Unmatched size (never negative)
+    pub fn remaining_f64(&self) -> f64 {
+        (self.original_size_f64() - self.size_matched_f64()).max(0.0)
+    }
+
+    fn is_fully_matched(&self) -> bool {
+        self.original_size_f64() > 0.0 && self.remaining_f64() == 0.0
+    }
+}
+
+/// Our side of one trade
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fill {
+    /// Trade id
+    pub trade_id: String,
+    /// Our order that was matched
+    pub order_id: String,
+    /// Condition ID
+    pub market: String,
+    /// Token identifier
+    pub asset_id: String,
+    /// Side of our order
+    pub side: OrderSide,
+    /// Execution price
+    pub price: String,
+    /// Matched size
+    pub size: String,
+    /// Fee rate in basis points
+    pub fee_rate_bps: String,
+    /// Settlement state
+    pub status: TradeStatus,
+    /// Maker or taker
+    pub liquidity: Liquidity,
+    /// Timestamp of the last message applied
+    pub timestamp: Option<i64>,
+}
+
+impl Fill {
+    /// Matched size as f64
+    pub fn size_f64(&self) -> f64 {
+        self.size.parse().unwrap_or(0.0)
+    }
+}
+
+/// State change produced by one message
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OrderEvent {
+    /// Order acknowledged (PLACEMENT)
+    Placed(String),
+    /// Order partially matched (UPDATE)
+    Updated(String),
+    /// Order fully matched
+    Filled(String),
+    /// Order cancelled
+    Cancelled(String),
+    /// New fill, or a fill moved to a new settlement state
+    Fill { trade_id: String, order_id: String, status: TradeStatus },
+}
+
+/// Result of reconciling against REST open orders
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReconcileReport {
+    /// Open on the exchange but unknown locally
+    pub added: Vec<String>,
+    /// Known locally, state or matched size corrected
+    pub updated: Vec<String>,
+    /// Open locally but no longer on the exchange
+    pub closed: Vec<String>,
+}
+
+impl ReconcileReport {
+    /// Nothing changed
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty() && self.closed.is_empty()
+    }
+}
+
+/// Orders and fills built from user channel messages
+#[derive(Debug, Default)]
+pub struct OrderManager {
+    orders: HashMap<String, ManagedOrder>,
+    fills: Vec<Fill>,
+    fill_index: HashMap<(String, String), usize>,
+}
+
+impl OrderManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Track an order accepted by POST /order before the channel acknowledges it
+    ///
+    /// Returns false if the order is already known (the channel was faster).
+    pub fn track_pending(
+        &mut self,
+        order_id: &str,
+        asset_id: &str,
+        side: OrderSide,
+        price: &str,
+        size: &str,
+    ) -> bool {
+        if self.orders.contains_key(order_id) {
+            return false;
+        }
+        self.orders.insert(
+            order_id.to_string(),
+            ManagedOrder {
+                id: order_id.to_string(),
+                market: String::new(),
+                asset_id: asset_id.to_string(),
+                side,
+                price: price.to_string(),
+                original_size: size.to_string(),
+                size_matched: "0".to_string(),
+                status: OrderStatus::Pending,
+                timestamp: None,
+            },
+        );
+        true
+    }
+
+    /// Apply a user channel message; other messages are ignored
+    pub fn apply(&mut self, msg: &WsInboundMessage) -> Vec<OrderEvent> {
+        match msg {
+            WsInboundMessage::User(UserMessage::Order(order)) => {
+                self.apply_order(order).into_iter().collect()
+            }
+            WsInboundMessage::User(UserMessage::Trade(trade)) => self.apply_trade(trade),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Apply an order event (PLACEMENT / UPDATE / CANCELLATION)
+    pub fn apply_order(&mut self, msg: &OrderMessage) -> Option<OrderEvent> {
+        let kind = msg.order_type.to_ascii_uppercase();
+        if !matches!(kind.as_str(), "PLACEMENT" | "UPDATE" | "CANCELLATION") {
+            warn!("Unknown order event type {} for {}", msg.order_type, msg.id);
+            return None;
+        }
+        let Some(side) = OrderSide::parse(&msg.side) else {
+            warn!("Unknown side {} for order {}", msg.side, msg.id);
+            return None;
+        };
+
+        let order = self.orders.entry(msg.id.clone()).or_insert_with(|| ManagedOrder {
+            id: msg.id.clone(),
+            market: msg.market.clone(),
+            asset_id: msg.asset_id.clone(),
+            side,
+            price: msg.price.clone(),
+            original_size: msg.original_size.clone(),
+            size_matched: "0".to_string(),
+            status: OrderStatus::Pending,
+            timestamp: None,
+        });
+
+        if !order.status.is_open() {
+            debug!("Ignoring {} for closed order {} ({:?})", kind, msg.id, order.status);
+            return None;
+        }
+
+        // The channel is authoritative for order details (pending orders lack the market)
+        order.market = msg.market.clone();
+        order.asset_id = msg.asset_id.clone();
+        order.price = msg.price.clone();
+        order.original_size = msg.original_size.clone();
+        order.timestamp = msg.timestamp.or(order.timestamp);
+        if let Some(matched) = &msg.size_matched {
+            // Matched size only grows; a reordered UPDATE must not shrink it
+            if matched.parse::<f64>().unwrap_or(0.0) >= order.size_matched_f64() {
+                order.size_matched = matched.clone();
+            }
+        }
+
+        let id = msg.id.clone();
+        Some(match kind.as_str() {
+            "CANCELLATION" => {
+                order.status = OrderStatus::Cancelled;
+                OrderEvent::Cancelled(id)
+            }
+            _ if order.is_fully_matched() => {
+                order.status = OrderStatus::Filled;
+                OrderEvent::Filled(id)
+            }
+            "PLACEMENT" => {
+                order.status = OrderStatus::Live;
+                OrderEvent::Placed(id)
+            }
+            _ => {
+                order.status = OrderStatus::Live;
+                OrderEvent::Updated(id)
+            }
+        })
+    }
+
+    /// Apply a trade event, creating or advancing fills for our orders
+    pub fn apply_trade(&mut self, msg: &TradeMessage) -> Vec<OrderEvent> {
+        let Some(status) = TradeStatus::parse(&msg.status) else {
+            warn!("Unknown trade status {} for trade {}", msg.status, msg.id);
+            return Vec::new();
+        };
+        let legs = trade_fills(msg, |order_id| self.orders.get(order_id).map(|o| o.side));
+
+        let mut events = Vec::new();
+        for fill in legs {
+            let key = (fill.trade_id.clone(), fill.order_id.clone());
+            let event = OrderEvent::Fill {
+                trade_id: fill.trade_id.clone(),
+                order_id: fill.order_id.clone(),
+                status,
+            };
+
+            match self.fill_index.get(&key) {
+                Some(&i) => {
+                    let existing = &mut self.fills[i];
+                    if existing.status.can_transition_to(status) {
+                        existing.status = status;
+                        existing.timestamp = fill.timestamp.or(existing.timestamp);
+                        events.push(event);
+                    } else {
+                        debug!(
+                            "Ignoring trade {} status {:?} -> {:?}",
+                            fill.trade_id, existing.status, status
+                        );
+                    }
+                }
+                None => {
+                    self.fill_index.insert(key, self.fills.len());
+                    self.fills.push(fill);
+                    events.push(event);
+                }
+            }
+        }
+        events
+    }
+
+    /// Mark an order cancelled (after a successful REST cancel)
+    ///
+    /// Returns false if the order is unknown or already closed.
+    pub fn mark_cancelled(&mut self, order_id: &str) -> bool {
+        match self.orders.get_mut(order_id) {
+            Some(order) if order.status.is_open() => {
+                order.status = OrderStatus::Cancelled;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Reconcile against the complete list of open orders from REST
+    ///
+    /// REST is authoritative: listed orders become live (even if closed
+    /// locally), and open orders missing from the list are closed as filled
+    /// when their matched size (or non-failed fills) cover the order, else cancelled.
+    pub fn reconcile(&mut self, open: &[OpenOrder]) -> ReconcileReport {
+        let mut report = ReconcileReport::default();
+        let listed: HashSet<&str> = open.iter().map(|o| o.id.as_str()).collect();
+
+        for remote in open {
+            let size_matched = remote.size_matched.clone().unwrap_or_else(|| "0".to_string());
+            match self.orders.get_mut(&remote.id) {
+                Some(order) => {
+                    let changed =
+                        order.status != OrderStatus::Live || order.size_matched != size_matched;
+                    order.status = OrderStatus::Live;
+                    order.market = remote.market.clone();
+                    order.size_matched = size_matched;
+                    if changed {
+                        report.updated.push(remote.id.clone());
+                    }
+                }
+                None => {
+                    let Some(side) = OrderSide::parse(&remote.side) else {
+                        warn!("Unknown side {} for open order {}", remote.side, remote.id);
+                        continue;
+                    };
+                    self.orders.insert(
+                        remote.id.clone(),
+                        ManagedOrder {
+                            id: remote.id.clone(),
+                            market: remote.market.clone(),
+                            asset_id: remote.asset_id.clone(),
+                            side,
+                            price: remote.price.clone(),
+                            original_size: remote.original_size.clone(),
+                            size_matched,
+                            status: OrderStatus::Live,
+                            timestamp: remote.created_at,
+                        },
+                    );
+                    report.added.push(remote.id.clone());
+                }
+            }
+        }
+
+        let missing: Vec<String> = self
+            .orders
+            .values()
+            .filter(|o| o.status.is_open() && !listed.contains(o.id.as_str()))
+            .map(|o| o.id.clone())
+            .collect();
+        for id in missing {
+            let filled = self.filled_size(&id);
+            let order = self.orders.get_mut(&id).expect("order listed above");
+            order.status = if order.is_fully_matched() || filled >= order.original_size_f64() {
+                OrderStatus::Filled
+            } else {
+                OrderStatus::Cancelled
+            };
+            report.closed.push(id);
+        }
+
+        report.added.sort();
+        report.updated.sort();
+        report.closed.sort();
+        report
+    }
+
+    /// Order by id
+    pub fn get(&self, order_id: &str) -> Option<&ManagedOrder> {
+        self.orders.get(order_id)
+    }
+
+    /// Open (pending or live) orders, optionally for one market, sorted by id
+    pub fn open_orders(&self, market: Option<&str>) -> Vec<&ManagedOrder> {
+        let mut orders: Vec<&ManagedOrder> = self
+            .orders
+            .values()
+            .filter(|o| o.status.is_open())
+            .filter(|o| market.is_none_or(|m| o.market == m))
+            .collect();
+        orders.sort_by(|a, b| a.id.cmp(&b.id));
+        orders
+    }
+
+    /// Fills in arrival order, optionally for one market
+    pub fn fills(&self, market: Option<&str>) -> Vec<&Fill> {
+        self.fills.iter().filter(|f| market.is_none_or(|m| f.market == m)).collect()
+    }
+
+    /// Fills of one order
+    pub fn fills_for_order(&self, order_id: &str) -> Vec<&Fill> {
+        self.fills.iter().filter(|f| f.order_id == order_id).collect()
+    }
+
+    /// Total size of an order's fills, excluding failed trades
+    pub fn filled_size(&self, order_id: &str) -> f64 {
+        self.fills
+            .iter()
+            .filter(|f| f.order_id == order_id && f.status != TradeStatus::Failed)
+            .map(Fill::size_f64)
+            .sum()
+    }
+
+    /// Number of tracked orders (open and closed)
+    pub fn len(&self) -> usize {
+        self.orders.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.orders.is_empty()
+    }
+}
+
+/// Our legs of a trade message, one `Fill` per order we own
+///
+/// `tracked` returns the side of an order we already know. Maker legs are ours
+/// if tracked or their owner is the event owner. The taker leg is ours if
+/// tracked or `trader_side` says TAKER (or, without `trader_side`, when no
+/// maker leg is ours). Returns nothing for an unknown trade status.
+pub fn trade_fills(msg: &TradeMessage, tracked: impl Fn(&str) -> Option<OrderSide>) -> Vec<Fill> {
+    let Some(status) = TradeStatus::parse(&msg.status) else {
+        return Vec::new();
+    };
+    let taker_side = OrderSide::parse(&msg.side);
+
+    let mut legs = Vec::new();
+    for maker in &msg.maker_orders {
+        let Some(order_id) = maker.order_id() else {
+            continue;
+        };
+        let known_side = tracked(order_id);
+        let owned = known_side.is_some()
+            || (maker.owner().is_some() && maker.owner() == msg.owner.as_deref());
+        if !owned {
+            continue;
+        }
+
+        let side = known_side
+            .or_else(|| maker.side().and_then(OrderSide::parse))
+            .or_else(|| taker_side.map(|s| s.opposite()));
+        let Some(side) = side else {
+            warn!("Cannot determine side of maker order {} in trade {}", order_id, msg.id);
+            continue;
+        };
+        legs.push(Fill {
+            trade_id: msg.id.clone(),
+            order_id: order_id.to_string(),
+            market: msg.market.clone(),
+            asset_id: maker.asset_id().unwrap_or(&msg.asset_id).to_string(),
+            side,
+            price: maker.price().unwrap_or(&msg.price).to_string(),
+            size: maker.matched_amount().unwrap_or(&msg.size).to_string(),
+            fee_rate_bps: maker.fee_rate_bps().unwrap_or("0").to_string(),
+            status,
+            liquidity: Liquidity::Maker,
+            timestamp: msg.timestamp,
+        });
+    }
+
+    if let (Some(order_id), Some(side)) = (&msg.taker_order_id, taker_side) {
+        let trader_side = msg.extra.get("trader_side").and_then(|v| v.as_str());
+        let owned = tracked(order_id).is_some()
+            || match trader_side {
+                Some(s) => s.eq_ignore_ascii_case("TAKER"),
+                None => legs.is_empty(),
+            };
+        if owned {
+            legs.push(Fill {
+                trade_id: msg.id.clone(),
+                order_id: order_id.clone(),
+                market: msg.market.clone(),
+                asset_id: msg.asset_id.clone(),
+                side,
+                price: msg.price.clone(),
+                size: msg.size.clone(),
+                fee_rate_bps: msg.fee_rate_bps.clone().unwrap_or_else(|| "0".to_string()),
+                status,
+                liquidity: Liquidity::Taker,
+                timestamp: msg.timestamp,
+            });
+        }
+    }
+    legs
+}
+
+/// Order manager with REST placement, cancellation and reconcile-on-reconnect
+///
+/// Attach the user client's `connection_counter`; the first message after
+/// a (re)connect triggers a reconcile against `GET /data/orders`.
+#[cfg(feature = "httpws")]
+pub struct OrderKeeper {
+    manager: OrderManager,
+    rest: RestClient,
+    connections: Option<Arc<AtomicU64>>,
+    seen_connections: u64,
+    reconcile_count: u64,
+}
+
+#[cfg(feature = "httpws")]
+impl OrderKeeper {
+    /// Create a keeper around an authenticated REST client
+    pub fn new(rest: RestClient) -> Self {
+        Self {
+            manager: OrderManager::new(),
+            rest,
+            connections: None,
+            seen_connections: 0,
+            reconcile_count: 0,
+        }
+    }
+
+    /// Reconcile whenever this counter changes (see `UserWsClient::connection_counter`)
+    pub fn with_connection_counter(mut self, counter: Arc<AtomicU64>) -> Self {
+        self.connections = Some(counter);
+        self
+    }
+
+    /// Apply a message, reconciling first if the user channel reconnected
+    pub async fn apply(&mut self, msg: &WsInboundMessage) -> Vec<OrderEvent> {
+        if let Err(e) = self.reconcile_if_reconnected().await {
+            warn!("Order reconcile failed: {}", e);
+        }
+        self.manager.apply(msg)
+    }
+
+    /// Reconcile if the connection counter moved since the last reconcile
+    ///
+    /// Returns Ok(None) when there was nothing to do. On error the counter is
+    /// not consumed, so the next call retries.
+    pub async fn reconcile_if_reconnected(&mut self) -> AdapterResult<Option<ReconcileReport>> {
+        let Some(current) = self.connections.as_ref().map(|c| c.load(Ordering::SeqCst)) else {
+            return Ok(None);
+        };
+        if current == self.seen_connections {
+            return Ok(None);
+        }
+
+        let report = self.reconcile().await?;
+        self.seen_connections = current;
+        Ok(Some(report))
+    }
+
+    /// Reload open orders from REST and reconcile
+    pub async fn reconcile(&mut self) -> AdapterResult<ReconcileReport> {
+        let open = self.rest.get_open_orders(None, None).await?;
+        let report = self.manager.reconcile(&open);
+        self.reconcile_count += 1;
+        info!(
+            "Reconciled {} open orders: added={} updated={} closed={}",
+            open.len(),
+            report.added.len(),
+            report.updated.len(),
+            report.closed.len()
+        );
+        Ok(report)
+    }
+
+    /// Build, sign and post an order, tracking it as pending
+    pub async fn place(
+        &mut self,
+        builder: &OrderBuilder,
+        args: &OrderArgs,
+        order_type: OrderType,
+    ) -> AdapterResult<PostOrderResponse> {
+        let response = self.rest.create_and_post_order(builder, args, order_type).await?;
+        self.manager.track_pending(
+            &response.order_id,
+            &args.token_id,
+            args.side,
+            &args.price,
+            &args.size,
+        );
+        Ok(response)
+    }
+
+    /// Cancel one order, marking it cancelled on success
+    pub async fn cancel(&mut self, order_id: &str) -> AdapterResult<CancelResponse> {
+        let response = self.rest.cancel_order(order_id).await?;
+        self.mark_cancelled(&response);
+        Ok(response)
+    }
+
+    /// Cancel every open order, marking each confirmed one cancelled
+    pub async fn cancel_all(&mut self) -> AdapterResult<CancelResponse> {
+        let response = self.rest.cancel_all().await?;
+        self.mark_cancelled(&response);
+        Ok(response)
+    }
+
+    /// Mark every order confirmed by a cancel response as cancelled
+    pub(crate) fn mark_cancelled(&mut self, response: &CancelResponse) {
+        for id in &response.canceled {
+            self.manager.mark_cancelled(id);
+        }
+    }
+
+    /// REST client used for placement and cancellation
+    pub(crate) fn rest(&self) -> &RestClient {
+        &self.rest
+    }
+
+    /// Underlying order state
+    pub fn manager(&self) -> &OrderManager {
+        &self.manager
+    }
+
+    /// Number of completed REST reconciles
+    pub fn reconcile_count(&self) -> u64 {
+        self.reconcile_count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order_msg(id: &str, kind: &str, size_matched: &str) -> WsInboundMessage {
+        WsInboundMessage::parse(
+            &serde_json::json!({
+                "event_type": "order",
+                "id": id,
+                "asset_id": "tok",
+                "market": "0xm",
+                "original_size": "10",
+                "price": "0.55",
+                "side": "BUY",
+                "size_matched": size_matched,
+                "type": kind,
+                "timestamp": 1
+            })
+            .to_string(),
+        )
+    }
+
+    fn trade_msg(
+        status: &str,
+        taker_order_id: &str,
+        makers: serde_json::Value,
+    ) -> WsInboundMessage {
+        WsInboundMessage::parse(
+            &serde_json::json!({
+                "event_type": "trade",
+                "id": "t1",
+                "asset_id": "tok",
+                "market": "0xm",
+                "price": "0.55",
+                "side": "BUY",
+                "size": "4",
+                "status": status,
+                "taker_order_id": taker_order_id,
+                "owner": "key",
+                "maker_orders": makers
+            })
+            .to_string(),
+        )
+    }
+
+    #[test]
+    fn test_order_lifecycle() {
+        let mut manager = OrderManager::new();
+        assert!(manager.track_pending("o1", "tok", OrderSide::Buy, "0.55", "10"));
+        assert_eq!(manager.get("o1").unwrap().status, OrderStatus::Pending);
+        assert_eq!(manager.open_orders(Some("0xm")).len(), 0);
+
+        assert_eq!(
+            manager.apply(&order_msg("o1", "PLACEMENT", "0")),
+            vec![OrderEvent::Placed("o1".into())]
+        );
+        assert_eq!(manager.open_orders(Some("0xm")).len(), 1);
+
+        assert_eq!(
+            manager.apply(&order_msg("o1", "UPDATE", "4")),
+            vec![OrderEvent::Updated("o1".into())]
+        );
+        // A reordered, older UPDATE does not shrink the matched size
+        manager.apply(&order_msg("o1", "UPDATE", "2"));
+        assert_eq!(manager.get("o1").unwrap().remaining_f64(), 6.0);
+
+        assert_eq!(
+            manager.apply(&order_msg("o1", "UPDATE", "10")),
+            vec![OrderEvent::Filled("o1".into())]
+        );
+        assert!(manager.open_orders(None).is_empty());
+
+        // Terminal: late cancellation is ignored
+        assert!(manager.apply(&order_msg("o1", "CANCELLATION", "10")).is_empty());
+        assert_eq!(manager.get("o1").unwrap().status, OrderStatus::Filled);
+
+        assert_eq!(
+            manager.apply(&order_msg("o2", "CANCELLATION", "0")),
+            vec![OrderEvent::Cancelled("o2".into())]
+        );
+        assert!(!manager.track_pending("o2", "tok", OrderSide::Buy, "0.55", "10"));
+    }
+
+    #[test]
+    fn test_trade_status_transitions() {
+        use TradeStatus::*;
+
+        assert!(Matched.can_transition_to(Mined));
+        assert!(Mined.can_transition_to(Retrying));
+        assert!(Retrying.can_transition_to(Confirmed));
+        assert!(!Mined.can_transition_to(Matched));
+        assert!(!Confirmed.can_transition_to(Failed));
+        assert!(!Failed.can_transition_to(Mined));
+        assert_eq!(TradeStatus::parse("confirmed"), Some(Confirmed));
+    }
+
+    #[test]
+    fn test_taker_fill_progresses() {
+        let mut manager = OrderManager::new();
+        manager.apply(&order_msg("o1", "PLACEMENT", "0"));
+
+        let events = manager.apply(&trade_msg("MATCHED", "o1", serde_json::json!([])));
+        assert_eq!(
+            events,
+            vec![OrderEvent::Fill {
+                trade_id: "t1".into(),
+                order_id: "o1".into(),
+                status: TradeStatus::Matched
+            }]
+        );
+        assert_eq!(manager.apply(&trade_msg("MINED", "o1", serde_json::json!([]))).len(), 1);
+        assert_eq!(manager.apply(&trade_msg("CONFIRMED", "o1", serde_json::json!([]))).len(), 1);
+        // Duplicate and backwards transitions are ignored
+        assert!(manager.apply(&trade_msg("CONFIRMED", "o1", serde_json::json!([]))).is_empty());
+        assert!(manager.apply(&trade_msg("MINED", "o1", serde_json::json!([]))).is_empty());
+
+        let fills = manager.fills(Some("0xm"));
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].liquidity, Liquidity::Taker);
+        assert_eq!(fills[0].status, TradeStatus::Confirmed);
+        assert_eq!(manager.filled_size("o1"), 4.0);
+    }
+
+    #[test]
+    fn test_maker_fill_ownership() {
+        let mut manager = OrderManager::new();
+        let makers = serde_json::json!([
+            {"order_id": "m1", "owner": "key", "matched_amount": "3", "price": "0.55", "asset_id": "tok"},
+            {"order_id": "m2", "owner": "other", "matched_amount": "1", "price": "0.55", "asset_id": "tok"}
+        ]);
+
+        // Taker order belongs to someone else; only our maker leg becomes a fill
+        let events = manager.apply(&trade_msg("MATCHED", "theirs", makers));
+        assert_eq!(events.len(), 1);
+        let fill = &manager.fills_for_order("m1")[0];
+        assert_eq!(fill.liquidity, Liquidity::Maker);
+        assert_eq!(fill.side, OrderSide::Sell);
+        assert_eq!(fill.size, "3");
+        assert!(manager.fills_for_order("theirs").is_empty());
+        assert!(manager.fills_for_order("m2").is_empty());
+    }
+
+    fn open_order(id: &str, size_matched: &str) -> OpenOrder {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "status": "LIVE",
+            "market": "0xm",
+            "asset_id": "tok",
+            "side": "SELL",
+            "original_size": "5",
+            "size_matched": size_matched,
+            "price": "0.6"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_reconcile() {
+        let mut manager = OrderManager::new();
+        manager.apply(&order_msg("gone", "PLACEMENT", "0"));
+        manager.apply(&order_msg("filled", "PLACEMENT", "0"));
+        manager.apply(&order_msg("kept", "PLACEMENT", "0"));
+        manager.apply(&trade_msg("MATCHED", "filled", serde_json::json!([])));
+        manager.apply(&WsInboundMessage::parse(
+            &serde_json::json!({
+                "event_type": "trade", "id": "t2", "asset_id": "tok", "market": "0xm",
+                "price": "0.55", "side": "BUY", "size": "6", "status": "MINED",
+                "taker_order_id": "filled"
+            })
+            .to_string(),
+        ));
+
+        let report = manager.reconcile(&[open_order("kept", "1"), open_order("new", "0")]);
+        assert_eq!(report.added, vec!["new"]);
+        assert_eq!(report.updated, vec!["kept"]);
+        assert_eq!(report.closed, vec!["filled", "gone"]);
+
+        assert_eq!(manager.get("gone").unwrap().status, OrderStatus::Cancelled);
+        assert_eq!(manager.get("filled").unwrap().status, OrderStatus::Filled);
+        assert_eq!(manager.get("new").unwrap().side, OrderSide::Sell);
+        assert_eq!(manager.open_orders(None).len(), 2);
+
+        assert!(manager.reconcile(&[open_order("kept", "1"), open_order("new", "0")]).is_empty());
+    }
+}
+
+#[cfg(all(test, feature = "httpws"))]
+mod wiremock_tests {
+    use super::*;
+    use crate::httpws::rest::authed_client;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_reconcile_on_reconnect() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/data/orders"))
+            .and(query_param("next_cursor", "MA=="))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [{
+                    "id": "o1", "status": "LIVE", "market": "0xm", "asset_id": "tok",
+                    "side": "BUY", "original_size": "10", "size_matched": "0", "price": "0.5"
+                }],
+                "next_cursor": "MQ=="
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/data/orders"))
+            .and(query_param("next_cursor", "MQ=="))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [{
+                    "id": "o2", "status": "LIVE", "market": "0xm", "asset_id": "tok",
+                    "side": "SELL", "original_size": 3, "price": 0.7
+                }],
+                "next_cursor": "LTE="
+            })))
+            .mount(&server)
+            .await;
+
+        let rest = authed_client(&server.uri());
+
+        let counter = Arc::new(AtomicU64::new(0));
+        let mut keeper = OrderKeeper::new(rest).with_connection_counter(counter.clone());
+        let heartbeat = WsInboundMessage::parse("{}");
+
+        // Not connected yet: nothing to reconcile
+        keeper.apply(&heartbeat).await;
+        assert_eq!(keeper.reconcile_count(), 0);
+
+        counter.store(1, Ordering::SeqCst);
+        keeper.apply(&heartbeat).await;
+        assert_eq!(keeper.reconcile_count(), 1);
+        assert_eq!(keeper.manager().open_orders(Some("0xm")).len(), 2);
+        assert_eq!(keeper.manager().get("o2").unwrap().price, "0.7");
+
+        // Same connection: no second reconcile
+        keeper.apply(&heartbeat).await;
+        assert_eq!(keeper.reconcile_count(), 1);
+
+        counter.store(2, Ordering::SeqCst);
+        let report = keeper.reconcile_if_reconnected().await.unwrap().unwrap();
+        assert!(report.is_empty());
+        assert_eq!(keeper.reconcile_count(), 2);
+    }
+}
@@ -0,0 +1,879 @@
+//! Paper-trading execution simulator
+//!
+//! `PaperExchange` maintains local books from market channel messages (live
+//! `MarketWsClient` output or parsed JSONL recordings) and fills simulated
+//! orders against them. Results are emitted as synthetic user channel
+//! messages, so `OrderManager` and `PositionBook` consume them unchanged.
+//!
+//! # Fill Model
+//! - Taker: on submit, walks the opposite side up to the limit price, one
+//!   `trade` per level, consuming the level size in the local book
+//! - Maker: a resting order fills at its own price when
+//!   - the book crosses it (opposite best at or through the order price), or
+//!   - `last_trade_price` prints through the order price, or at the order
+//!     price once the size queued ahead at placement has traded
+//! - The next `book` / `price_change` from the feed overwrites consumed levels
+//!
+//! # Orders
+//! - Limit: GTC / GTD rest; FOK fills fully or is rejected; FAK cancels the rest
+//! - Market (no price): FOK or FAK only, walks the whole side
+//! - `from_post` accepts the signed CLOB payload (price and size from the amounts)
+//!
+//! # Events
+//! - `order` PLACEMENT on accept, UPDATE after fills, CANCELLATION on cancel/expiry
+//! - `trade` MATCHED only (settlement statuses are not simulated), taker fee from `PaperConfig`
+//! - Timestamps are the latest market message timestamp (ms), so replays are deterministic
+
+use serde_json::{json, Map};
+use tracing::debug;
+
+use crate::book::{BookSide, OrderBooks};
+use crate::error::{AdapterError, AdapterResult};
+use crate::types::{
+    CancelResponse, MakerOrderDetail, MarketMessage, OrderMessage, OrderSide, OrderType,
+    PostOrderRequest, PostOrderResponse, TradeMessage, UserMessage, WsInboundMessage,
+};
+
+/// Size/price tolerance
+const EPSILON: f64 = 1e-9;
+
+/// Decimals of signed order amounts
+const AMOUNT_SCALE: f64 = 1_000_000.0;
+
+/// Error message for an unfillable FOK order (as returned by the CLOB)
+const FOK_NOT_FILLED: &str =
+    "order couldn't be fully filled. FOK orders are fully filled or killed.";
+
+/// Paper exchange configuration
+#[derive(Clone, Debug)]
+pub struct PaperConfig {
+    /// Owner reported on orders and trades
+    pub owner: String,
+    /// Fee rate charged on taker fills (basis points)
+    pub taker_fee_rate_bps: u32,
+}
+
+impl Default for PaperConfig {
+    fn default() -> Self {
+        Self { owner: "paper".to_string(), taker_fee_rate_bps: 0 }
+    }
+}
+
+/// Simulated order request
+#[derive(Clone, Debug, PartialEq)]
+pub struct PaperOrderRequest {
+    /// Token identifier
+    pub token_id: String,
+    /// Order side
+    pub side: OrderSide,
+    /// Limit price (None = market order)
+    pub price: Option<f64>,
+    /// Size in shares
+    pub size: f64,
+    /// Time-in-force
+    pub order_type: OrderType,
+    /// Unix expiration (seconds) for GTD, 0 for none
+    pub expiration: u64,
+}
+
+impl PaperOrderRequest {
+    /// GTC limit order
+    pub fn limit(token_id: &str, side: OrderSide, price: f64, size: f64) -> Self {
+        Self {
+            token_id: token_id.to_string(),
+            side,
+            price: Some(price),
+            size,
+            order_type: OrderType::Gtc,
+            expiration: 0,
+        }
+    }
+
+    /// FOK market order
+    pub fn market(token_id: &str, side: OrderSide, size: f64) -> Self {
+        Self {
+            token_id: token_id.to_string(),
+            side,
+            price: None,
+            size,
+            order_type: OrderType::Fok,
+            expiration: 0,
+        }
+    }
+
+    /// Set time-in-force
+    pub fn with_order_type(mut self, order_type: OrderType) -> Self {
+        self.order_type = order_type;
+        self
+    }
+
+    /// GTD with expiration (unix seconds)
+    pub fn with_expiration(mut self, expiration: u64) -> Self {
+        self.order_type = OrderType::Gtd;
+        self.expiration = expiration;
+        self
+    }
+
+    /// From a POST /order payload
+    ///
+    /// BUY gives USDC (maker amount) for shares (taker amount); SELL the reverse.
+    pub fn from_post(request: &PostOrderRequest) -> AdapterResult<Self> {
+        let order = &request.order;
+        let amount = |field: &str, value: &str| -> AdapterResult<f64> {
+            match value.parse::<u128>() {
+                Ok(v) if v > 0 => Ok(v as f64 / AMOUNT_SCALE),
+                _ => Err(AdapterError::InvalidOrder(format!("Invalid {} {:?}", field, value))),
+            }
+        };
+        let maker = amount("makerAmount", &order.maker_amount)?;
+        let taker = amount("takerAmount", &order.taker_amount)?;
+        let (usdc, shares) = match order.side {
+            OrderSide::Buy => (maker, taker),
+            OrderSide::Sell => (taker, maker),
+        };
+        let expiration = order.expiration.parse().map_err(|_| {
+            AdapterError::InvalidOrder(format!("Invalid expiration {:?}", order.expiration))
+        })?;
+
+        Ok(Self {
+            token_id: order.token_id.clone(),
+            side: order.side,
+            price: Some((usdc / shares * AMOUNT_SCALE).round() / AMOUNT_SCALE),
+            size: shares,
+            order_type: request.order_type,
+            expiration,
+        })
+    }
+}
+
+/// Resting simulated order
+#[derive(Clone, Debug, PartialEq)]
+pub struct PaperOrder {
+    pub id: String,
+    pub market: String,
+    pub asset_id: String,
+    pub side: OrderSide,
+    pub price: f64,
+    pub original_size: f64,
+    pub size_matched: f64,
+    pub order_type: OrderType,
+    pub expiration: u64,
+    /// Size ahead of this order at its price level
+    pub queue_ahead: f64,
+}
+
+impl PaperOrder {
+    /// Size still open
+    pub fn remaining(&self) -> f64 {
+        (self.original_size - self.size_matched).max(0.0)
+    }
+
+    /// Whether `price` is at or better than this order for the opposite side
+    fn crosses(&self, price: f64) -> bool {
+        match self.side {
+            OrderSide::Buy => price <= self.price + EPSILON,
+            OrderSide::Sell => price >= self.price - EPSILON,
+        }
+    }
+}
+
+/// Format a size or price without trailing zeros
+fn fmt_decimal(value: f64) -> String {
+    let s = format!("{:.6}", value);
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    if s.is_empty() || s == "-0" {
+        "0".to_string()
+    } else {
+        s.to_string()
+    }
+}
+
+/// Side of the book an order takes liquidity from
+fn opposite_book_side(side: OrderSide) -> BookSide {
+    match side {
+        OrderSide::Buy => BookSide::Ask,
+        OrderSide::Sell => BookSide::Bid,
+    }
+}
+
+/// Side of the book an order rests on
+fn own_book_side(side: OrderSide) -> BookSide {
+    match side {
+        OrderSide::Buy => BookSide::Bid,
+        OrderSide::Sell => BookSide::Ask,
+    }
+}
+
+/// Paper-trading exchange fed by market channel messages
+#[derive(Debug, Default)]
+pub struct PaperExchange {
+    config: PaperConfig,
+    books: OrderBooks,
+    /// Resting orders in placement order
+    orders: Vec<PaperOrder>,
+    events: Vec<WsInboundMessage>,
+    next_order: u64,
+    next_trade: u64,
+    now_ms: i64,
+}
+
+impl PaperExchange {
+    pub fn new(config: PaperConfig) -> Self {
+        Self { config, ..Default::default() }
+    }
+
+    /// Local books (after simulated consumption)
+    pub fn books(&self) -> &OrderBooks {
+        &self.books
+    }
+
+    /// Resting orders
+    pub fn open_orders(&self) -> &[PaperOrder] {
+        &self.orders
+    }
+
+    /// Latest market timestamp seen (ms)
+    pub fn now_ms(&self) -> i64 {
+        self.now_ms
+    }
+
+    /// Drain emitted user channel messages
+    pub fn take_events(&mut self) -> Vec<WsInboundMessage> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Apply a market channel message (user channel and unknown messages are ignored)
+    pub fn apply(&mut self, msg: &WsInboundMessage) {
+        let WsInboundMessage::Market(market) = msg else {
+            if let WsInboundMessage::SnapshotArray(_) = msg {
+                self.apply_book_update(msg);
+            }
+            return;
+        };
+
+        if let Some(ts) = msg.timestamp_ms() {
+            self.now_ms = self.now_ms.max(ts);
+        }
+        self.expire_orders();
+
+        match market {
+            MarketMessage::Book(_) | MarketMessage::PriceChange(_) => self.apply_book_update(msg),
+            MarketMessage::LastTradePrice(trade) => {
+                let price = trade.price.parse::<f64>().unwrap_or(0.0);
+                let size = trade.size.parse::<f64>().unwrap_or(0.0);
+                let taker_side = OrderSide::parse(&trade.side);
+                self.match_last_trade(&trade.asset_id, price, size, taker_side);
+            }
+            _ => {}
+        }
+    }
+
+    /// Submit an order
+    ///
+    /// Rejections are returned as `success: false` with an error message, like POST /order.
+    pub fn submit(&mut self, request: &PaperOrderRequest) -> PostOrderResponse {
+        if let Err(msg) = validate(request) {
+            return rejected(msg);
+        }
+        if request.order_type == OrderType::Fok
+            && self.available(&request.token_id, request.side, request.price) + EPSILON
+                < request.size
+        {
+            return rejected(FOK_NOT_FILLED.to_string());
+        }
+
+        self.next_order += 1;
+        let mut order = PaperOrder {
+            id: format!("paper-order-{}", self.next_order),
+            market: self
+                .books
+                .get(&request.token_id)
+                .map(|b| b.market().to_string())
+                .unwrap_or_default(),
+            asset_id: request.token_id.clone(),
+            side: request.side,
+            price: request.price.unwrap_or(0.0),
+            original_size: request.size,
+            size_matched: 0.0,
+            order_type: request.order_type,
+            expiration: request.expiration,
+            queue_ahead: 0.0,
+        };
+        self.push_order_event(&order, "PLACEMENT");
+
+        // Taker leg
+        let fills =
+            self.take_liquidity(&order.asset_id, order.side, request.price, order.original_size);
+        let mut notional = 0.0;
+        for (price, size) in &fills {
+            notional += price * size;
+            order.size_matched += size;
+            self.push_taker_trade(&order, *price, *size);
+        }
+        if request.price.is_none() && order.size_matched > EPSILON {
+            // Market orders report the average fill price
+            order.price = notional / order.size_matched;
+        }
+        if !fills.is_empty() {
+            self.push_order_event(&order, "UPDATE");
+        }
+
+        let status = if order.remaining() <= EPSILON {
+            "matched"
+        } else if matches!(order.order_type, OrderType::Gtc | OrderType::Gtd) {
+            order.queue_ahead =
+                self.level_size(&order.asset_id, own_book_side(order.side), order.price);
+            "live"
+        } else {
+            self.push_order_event(&order, "CANCELLATION");
+            if fills.is_empty() {
+                "unmatched"
+            } else {
+                "matched"
+            }
+        };
+
+        let (making, taking) = match order.side {
+            OrderSide::Buy => (notional, order.size_matched),
+            OrderSide::Sell => (order.size_matched, notional),
+        };
+        let response = PostOrderResponse {
+            success: true,
+            error_msg: String::new(),
+            order_id: order.id.clone(),
+            status: status.to_string(),
+            making_amount: Some(fmt_decimal(making)),
+            taking_amount: Some(fmt_decimal(taking)),
+            transaction_hashes: Vec::new(),
+            extra: Map::new(),
+        };
+        if status == "live" {
+            self.orders.push(order);
+        }
+        response
+    }
+
+    /// Submit a signed POST /order payload
+    pub fn submit_post(&mut self, request: &PostOrderRequest) -> PostOrderResponse {
+        match PaperOrderRequest::from_post(request) {
+            Ok(order) => self.submit(&order),
+            Err(e) => rejected(e.to_string()),
+        }
+    }
+
+    /// Cancel one resting order
+    pub fn cancel(&mut self, order_id: &str) -> CancelResponse {
+        let mut response = CancelResponse {
+            canceled: Vec::new(),
+            not_canceled: Default::default(),
+            extra: Map::new(),
+        };
+        match self.orders.iter().position(|o| o.id == order_id) {
+            Some(idx) => {
+                let order = self.orders.remove(idx);
+                self.push_order_event(&order, "CANCELLATION");
+                response.canceled.push(order.id);
+            }
+            None => {
+                response.not_canceled.insert(order_id.to_string(), "order not found".to_string());
+            }
+        }
+        response
+    }
+
+    /// Cancel all resting orders
+    pub fn cancel_all(&mut self) -> CancelResponse {
+        let orders = std::mem::take(&mut self.orders);
+        for order in &orders {
+            self.push_order_event(order, "CANCELLATION");
+        }
+        CancelResponse {
+            canceled: orders.into_iter().map(|o| o.id).collect(),
+            not_canceled: Default::default(),
+            extra: Map::new(),
+        }
+    }
+
+    fn apply_book_update(&mut self, msg: &WsInboundMessage) {
+        let update = self.books.apply(msg);
+        for asset_id in &update.updated {
+            self.match_crossed(asset_id);
+
+            // Size ahead can only shrink (cancels and trades ahead of us)
+            for i in 0..self.orders.len() {
+                if self.orders[i].asset_id == *asset_id {
+                    let order = &self.orders[i];
+                    let level = self.level_size(asset_id, own_book_side(order.side), order.price);
+                    self.orders[i].queue_ahead = self.orders[i].queue_ahead.min(level);
+                }
+            }
+        }
+    }
+
+    /// Fill resting orders the book has crossed
+    fn match_crossed(&mut self, asset_id: &str) {
+        for idx in self.priority(asset_id, None) {
+            let order = &self.orders[idx];
+            let (side, price, remaining) = (order.side, order.price, order.remaining());
+            let fills = self.take_liquidity(asset_id, side, Some(price), remaining);
+            let size: f64 = fills.iter().map(|(_, s)| s).sum();
+            if size > EPSILON {
+                self.fill_resting(idx, size);
+            }
+        }
+        self.orders.retain(|o| o.remaining() > EPSILON);
+    }
+
+    /// Fill resting orders against a public trade
+    fn match_last_trade(
+        &mut self,
+        asset_id: &str,
+        price: f64,
+        size: f64,
+        taker_side: Option<OrderSide>,
+    ) {
+        let mut left = size;
+        for idx in self.priority(asset_id, taker_side.map(|s| s.opposite())) {
+            if left <= EPSILON {
+                break;
+            }
+            let order = &mut self.orders[idx];
+            if !order.crosses(price) {
+                continue;
+            }
+            if (price - order.price).abs() <= EPSILON {
+                // At our price: the queue ahead trades first
+                let ahead = order.queue_ahead.min(left);
+                order.queue_ahead -= ahead;
+                left -= ahead;
+            }
+            let fill = order.remaining().min(left);
+            if fill > EPSILON {
+                left -= fill;
+                self.fill_resting(idx, fill);
+            }
+        }
+        self.orders.retain(|o| o.remaining() > EPSILON);
+    }
+
+    /// Indices of resting orders on an asset, best price first, then by time
+    fn priority(&self, asset_id: &str, side: Option<OrderSide>) -> Vec<usize> {
+        let mut indices: Vec<usize> = (0..self.orders.len())
+            .filter(|&i| {
+                self.orders[i].asset_id == asset_id && side.is_none_or(|s| self.orders[i].side == s)
+            })
+            .collect();
+        indices.sort_by(|&a, &b| {
+            let key = |o: &PaperOrder| match o.side {
+                OrderSide::Buy => -o.price,
+                OrderSide::Sell => o.price,
+            };
+            key(&self.orders[a]).total_cmp(&key(&self.orders[b])).then(a.cmp(&b))
+        });
+        indices
+    }
+
+    fn fill_resting(&mut self, idx: usize, size: f64) {
+        self.orders[idx].size_matched += size;
+        let order = self.orders[idx].clone();
+        self.push_maker_trade(&order, size);
+        self.push_order_event(&order, "UPDATE");
+    }
+
+    /// Cancel GTD orders past their expiration
+    fn expire_orders(&mut self) {
+        let now_secs = (self.now_ms / 1000).max(0) as u64;
+        let (expired, live): (Vec<_>, Vec<_>) = std::mem::take(&mut self.orders)
+            .into_iter()
+            .partition(|o| o.expiration > 0 && now_secs >= o.expiration);
+        self.orders = live;
+        for order in &expired {
+            debug!("Paper order {} expired", order.id);
+            self.push_order_event(order, "CANCELLATION");
+        }
+    }
+
+    /// Consume liquidity crossing `limit` (None = any price); returns (price, size) per level
+    fn take_liquidity(
+        &mut self,
+        asset_id: &str,
+        side: OrderSide,
+        limit: Option<f64>,
+        max_size: f64,
+    ) -> Vec<(f64, f64)> {
+        let Some(book) = self.books.get_mut(asset_id) else {
+            return Vec::new();
+        };
+        let book_side = opposite_book_side(side);
+        let levels: Vec<_> = book.depth(book_side, usize::MAX).into_iter().cloned().collect();
+
+        let mut left = max_size;
+        let mut fills = Vec::new();
+        for level in levels {
+            let price = level.price_f64();
+            if left <= EPSILON || !crosses_limit(side, price, limit) {
+                break;
+            }
+            let size = level.size_f64().min(left);
+            left -= size;
+            book.set_level(book_side, &level.price, &fmt_decimal(level.size_f64() - size));
+            fills.push((price, size));
+        }
+        fills
+    }
+
+    /// Size available to a taker up to `limit`
+    fn available(&self, asset_id: &str, side: OrderSide, limit: Option<f64>) -> f64 {
+        self.books
+            .get(asset_id)
+            .map(|book| {
+                book.depth(opposite_book_side(side), usize::MAX)
+                    .into_iter()
+                    .take_while(|l| crosses_limit(side, l.price_f64(), limit))
+                    .map(|l| l.size_f64())
+                    .sum()
+            })
+            .unwrap_or(0.0)
+    }
+
+    /// Resting size at one price on one side
+    fn level_size(&self, asset_id: &str, side: BookSide, price: f64) -> f64 {
+        self.books
+            .get(asset_id)
+            .and_then(|book| {
+                book.depth(side, usize::MAX)
+                    .into_iter()
+                    .find(|l| (l.price_f64() - price).abs() <= EPSILON)
+                    .map(|l| l.size_f64())
+            })
+            .unwrap_or(0.0)
+    }
+
+    fn next_trade_id(&mut self) -> String {
+        self.next_trade += 1;
+        format!("paper-trade-{}", self.next_trade)
+    }
+
+    fn push_order_event(&mut self, order: &PaperOrder, kind: &str) {
+        self.events.push(WsInboundMessage::User(UserMessage::Order(OrderMessage {
+            id: order.id.clone(),
+            asset_id: order.asset_id.clone(),
+            market: order.market.clone(),
+            original_size: fmt_decimal(order.original_size),
+            outcome: None,
+            price: fmt_decimal(order.price),
+            side: order.side.as_str().to_string(),
+            size_matched: Some(fmt_decimal(order.size_matched)),
+            order_type: kind.to_string(),
+            owner: Some(self.config.owner.clone()),
+            timestamp: Some(self.now_ms),
+            extra: Map::new(),
+        })));
+    }
+
+    fn push_taker_trade(&mut self, order: &PaperOrder, price: f64, size: f64) {
+        let id = self.next_trade_id();
+        let mut extra = Map::new();
+        extra.insert("trader_side".to_string(), json!("TAKER"));
+        self.push_trade(TradeMessage {
+            id,
+            asset_id: order.asset_id.clone(),
+            market: order.market.clone(),
+            matchtime: None,
+            outcome: None,
+            price: fmt_decimal(price),
+            side: order.side.as_str().to_string(),
+            size: fmt_decimal(size),
+            status: "MATCHED".to_string(),
+            maker_orders: Vec::new(),
+            taker_order_id: Some(order.id.clone()),
+            fee_rate_bps: Some(self.config.taker_fee_rate_bps.to_string()),
+            owner: Some(self.config.owner.clone()),
+            timestamp: Some(self.now_ms),
+            extra,
+        });
+    }
+
+    fn push_maker_trade(&mut self, order: &PaperOrder, size: f64) {
+        let id = self.next_trade_id();
+        let maker = json!({
+            "order_id": order.id,
+            "owner": self.config.owner,
+            "asset_id": order.asset_id,
+            "price": fmt_decimal(order.price),
+            "matched_amount": fmt_decimal(size),
+            "side": order.side.as_str(),
+            "fee_rate_bps": "0",
+        });
+        let mut extra = Map::new();
+        extra.insert("trader_side".to_string(), json!("MAKER"));
+        self.push_trade(TradeMessage {
+            id,
+            asset_id: order.asset_id.clone(),
+            market: order.market.clone(),
+            matchtime: None,
+            outcome: None,
+            price: fmt_decimal(order.price),
+            side: order.side.opposite().as_str().to_string(),
+            size: fmt_decimal(size),
+            status: "MATCHED".to_string(),
+            maker_orders: vec![MakerOrderDetail {
+                data: maker.as_object().cloned().unwrap_or_default(),
+            }],
+            taker_order_id: None,
+            fee_rate_bps: None,
+            owner: Some(self.config.owner.clone()),
+            timestamp: Some(self.now_ms),
+            extra,
+        });
+    }
+
+    fn push_trade(&mut self, trade: TradeMessage) {
+        self.events.push(WsInboundMessage::User(UserMessage::Trade(trade)));
+    }
+}
+
+/// Whether a level price is within a taker limit
+fn crosses_limit(side: OrderSide, price: f64, limit: Option<f64>) -> bool {
+    match (side, limit) {
+        (_, None) => true,
+        (OrderSide::Buy, Some(limit)) => price <= limit + EPSILON,
+        (OrderSide::Sell, Some(limit)) => price >= limit - EPSILON,
+    }
+}
+
+fn validate(request: &PaperOrderRequest) -> Result<(), String> {
+    if !(request.size.is_finite() && request.size > 0.0) {
+        return Err(format!("invalid size {}", request.size));
+    }
+    match request.price {
+        Some(price) if !(price > 0.0 && price < 1.0) => {
+            Err(format!("invalid price {}, must be between 0 and 1", price))
+        }
+        None if !matches!(request.order_type, OrderType::Fok | OrderType::Fak) => {
+            Err("market orders must be FOK or FAK".to_string())
+        }
+        _ if request.order_type == OrderType::Gtd && request.expiration == 0 => {
+            Err("GTD order requires an expiration".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+fn rejected(error_msg: String) -> PostOrderResponse {
+    debug!("Paper order rejected: {}", error_msg);
+    PostOrderResponse {
+        success: false,
+        error_msg,
+        order_id: String::new(),
+        status: String::new(),
+        making_amount: None,
+        taking_amount: None,
+        transaction_hashes: Vec::new(),
+        extra: Map::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order_manager::{OrderManager, OrderStatus};
+    use crate::positions::PositionBook;
+
+    fn exchange() -> PaperExchange {
+        let mut exchange = PaperExchange::new(PaperConfig::default());
+        exchange.apply(&WsInboundMessage::parse(
+            r#"{"event_type":"book","asset_id":"up","market":"0xm","timestamp":"1700000000000",
+                "bids":[{"price":"0.48","size":"10"},{"price":"0.47","size":"20"}],
+                "asks":[{"price":"0.52","size":"10"},{"price":"0.53","size":"20"}]}"#,
+        ));
+        exchange
+    }
+
+    fn feed(exchange: &mut PaperExchange, manager: &mut OrderManager, json: &str) {
+        exchange.apply(&WsInboundMessage::parse(json));
+        for event in exchange.take_events() {
+            manager.apply(&event);
+        }
+    }
+
+    #[test]
+    fn test_fmt_decimal() {
+        assert_eq!(fmt_decimal(0.5), "0.5");
+        assert_eq!(fmt_decimal(10.0), "10");
+        assert_eq!(fmt_decimal(0.0), "0");
+        assert_eq!(fmt_decimal(1.0 / 3.0), "0.333333");
+    }
+
+    #[test]
+    fn test_taker_walks_book() {
+        let mut exchange = exchange();
+        let response = exchange.submit(&PaperOrderRequest::limit("up", OrderSide::Buy, 0.53, 15.0));
+        assert!(response.success);
+        assert_eq!(response.status, "matched");
+        assert_eq!(response.taking_amount.as_deref(), Some("15"));
+        assert_eq!(response.making_amount.as_deref(), Some("7.85"));
+
+        let book = exchange.books().get("up").unwrap();
+        assert_eq!(book.best_ask().unwrap().price, "0.53");
+        assert_eq!(book.best_ask().unwrap().size, "15");
+
+        let mut manager = OrderManager::new();
+        let mut positions = PositionBook::new();
+        for event in exchange.take_events() {
+            manager.apply(&event);
+            if let WsInboundMessage::User(UserMessage::Trade(trade)) = &event {
+                positions.apply_trade(trade);
+            }
+        }
+        let order = manager.get(&response.order_id).unwrap();
+        assert_eq!(order.status, OrderStatus::Filled);
+        assert_eq!(manager.filled_size(&response.order_id), 15.0);
+        let position = positions.get("up").unwrap();
+        assert_eq!(position.size, 15.0);
+        assert!((position.avg_price - 7.85 / 15.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_fok_fak_and_market_orders() {
+        let mut exchange = exchange();
+        let response = exchange.submit(
+            &PaperOrderRequest::limit("up", OrderSide::Sell, 0.48, 11.0)
+                .with_order_type(OrderType::Fok),
+        );
+        assert!(!response.success);
+        assert_eq!(response.error_msg, FOK_NOT_FILLED);
+        assert!(exchange.take_events().is_empty());
+
+        let response = exchange.submit(
+            &PaperOrderRequest::limit("up", OrderSide::Sell, 0.48, 11.0)
+                .with_order_type(OrderType::Fak),
+        );
+        assert_eq!(response.status, "matched");
+        assert_eq!(response.making_amount.as_deref(), Some("10"));
+        assert!(exchange.open_orders().is_empty());
+
+        // Market BUY walks both ask levels: 10 @ 0.52 + 5 @ 0.53
+        let response = exchange.submit(&PaperOrderRequest::market("up", OrderSide::Buy, 15.0));
+        assert_eq!(response.status, "matched");
+        assert_eq!(response.making_amount.as_deref(), Some("7.85"));
+
+        assert!(!exchange.submit(&PaperOrderRequest::market("up", OrderSide::Buy, 100.0)).success);
+        let gtc_market =
+            PaperOrderRequest::market("up", OrderSide::Buy, 1.0).with_order_type(OrderType::Gtc);
+        assert!(!exchange.submit(&gtc_market).success);
+        assert!(
+            !exchange.submit(&PaperOrderRequest::limit("up", OrderSide::Buy, 1.2, 1.0)).success
+        );
+    }
+
+    #[test]
+    fn test_resting_order_queue_and_trades() {
+        let mut exchange = exchange();
+        let mut manager = OrderManager::new();
+        let response = exchange.submit(&PaperOrderRequest::limit("up", OrderSide::Buy, 0.48, 5.0));
+        assert_eq!(response.status, "live");
+        for event in exchange.take_events() {
+            manager.apply(&event);
+        }
+        assert_eq!(exchange.open_orders()[0].queue_ahead, 10.0);
+
+        // 8 traded at our price: all ahead of us
+        feed(
+            &mut exchange,
+            &mut manager,
+            r#"{"event_type":"last_trade_price","asset_id":"up","market":"0xm","timestamp":"1700000001000",
+                "price":"0.48","size":"8","side":"SELL"}"#,
+        );
+        assert_eq!(manager.filled_size(&response.order_id), 0.0);
+
+        // 4 more: 2 left ahead, 2 fill us
+        feed(
+            &mut exchange,
+            &mut manager,
+            r#"{"event_type":"last_trade_price","asset_id":"up","market":"0xm","timestamp":"1700000002000",
+                "price":"0.48","size":"4","side":"SELL"}"#,
+        );
+        assert_eq!(manager.filled_size(&response.order_id), 2.0);
+
+        // Trade through our price fills the rest
+        feed(
+            &mut exchange,
+            &mut manager,
+            r#"{"event_type":"last_trade_price","asset_id":"up","market":"0xm","timestamp":"1700000003000",
+                "price":"0.47","size":"50","side":"SELL"}"#,
+        );
+        assert_eq!(manager.filled_size(&response.order_id), 5.0);
+        assert_eq!(manager.get(&response.order_id).unwrap().status, OrderStatus::Filled);
+        assert!(exchange.open_orders().is_empty());
+    }
+
+    #[test]
+    fn test_book_cross_fills_resting_order() {
+        let mut exchange = exchange();
+        let mut manager = OrderManager::new();
+        let response = exchange.submit(&PaperOrderRequest::limit("up", OrderSide::Sell, 0.55, 6.0));
+        for event in exchange.take_events() {
+            manager.apply(&event);
+        }
+
+        // A bid appears through our ask: we fill as maker at our price
+        feed(
+            &mut exchange,
+            &mut manager,
+            r#"{"event_type":"price_change","market":"0xm","timestamp":"1700000001000",
+                "price_changes":[{"asset_id":"up","price":"0.56","size":"4","side":"BUY"}]}"#,
+        );
+        let fills = manager.fills_for_order(&response.order_id);
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].price, "0.55");
+        assert_eq!(fills[0].size, "4");
+        assert_eq!(exchange.open_orders()[0].remaining(), 2.0);
+        assert!(exchange.books().get("up").unwrap().best_bid().unwrap().price == "0.48");
+    }
+
+    #[test]
+    fn test_cancel_and_expiry() {
+        let mut exchange = exchange();
+        let mut manager = OrderManager::new();
+        let a = exchange.submit(&PaperOrderRequest::limit("up", OrderSide::Buy, 0.40, 5.0));
+        let b = exchange.submit(
+            &PaperOrderRequest::limit("up", OrderSide::Buy, 0.41, 5.0).with_expiration(1700000060),
+        );
+        for event in exchange.take_events() {
+            manager.apply(&event);
+        }
+
+        let response = exchange.cancel(&a.order_id);
+        assert_eq!(response.canceled, vec![a.order_id.clone()]);
+        assert!(exchange.cancel("missing").not_canceled.contains_key("missing"));
+
+        feed(
+            &mut exchange,
+            &mut manager,
+            r#"{"event_type":"last_trade_price","asset_id":"up","market":"0xm","timestamp":"1700000060000",
+                "price":"0.5","size":"1","side":"BUY"}"#,
+        );
+        assert!(exchange.open_orders().is_empty());
+        assert_eq!(manager.get(&a.order_id).unwrap().status, OrderStatus::Cancelled);
+        assert_eq!(manager.get(&b.order_id).unwrap().status, OrderStatus::Cancelled);
+    }
+
+    #[test]
+    fn test_from_post() {
+        let order: crate::types::SignedOrder = serde_json::from_value(json!({
+            "salt": 1, "maker": "0x0", "signer": "0x0", "taker": "0x0", "tokenId": "up",
+            "makerAmount": "5500000", "takerAmount": "10000000", "expiration": "0",
+            "nonce": "0", "feeRateBps": "0", "side": "BUY", "signatureType": 0, "signature": "0x"
+        }))
+        .unwrap();
+        let request =
+            PostOrderRequest { order, owner: "key".to_string(), order_type: OrderType::Gtc };
+        let paper = PaperOrderRequest::from_post(&request).unwrap();
+        assert_eq!(paper, PaperOrderRequest::limit("up", OrderSide::Buy, 0.55, 10.0));
+
+        let mut exchange = exchange();
+        // Crosses the 0.52 ask
+        assert_eq!(exchange.submit_post(&request).status, "matched");
+    }
+}
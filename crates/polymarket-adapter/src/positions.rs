@@ -0,0 +1,495 @@
+//! Position and PnL tracking per token and per binary market
+//!
+//! # Accounting
+//! - Average cost: buys into a long (or sells into a short) move the average
+//!   price; trades against the position realize `(price - avg) * size`
+//! - A trade larger than the position flips it, and the remainder opens at the trade price
+//! - Fees are kept apart from realized PnL:
+//!   `fee = size * min(price, 1 - price) * fee_rate_bps / 10000` (USDC)
+//! - Unrealized PnL is marked against a midpoint set from `RestClient::get_midpoint`
+//!   or from a local `OrderBooks` kept from market channel messages
+//!
+//! # Trade Status
+//! A fill counts once, on its first non-FAILED message; later status updates
+//! are ignored. If the trade then FAILS, the token is rebuilt from its remaining
+//! fills, so the position is exact rather than approximately reversed.
+//!
+//! # Binary Markets
+//! Register a `ResolvedMarket` to view its Up/Down tokens as one position:
+//! one Up plus one Down share always redeems for $1, so only the net side
+//! carries price risk. A missing mark on one token is taken as `1 - mark` of the other.
+//!
+//! # Source
+//! - Fees: https://docs.polymarket.com/developers/CLOB/introduction
+//! - User Channel: https://docs.polymarket.com/developers/CLOB/websocket/user-channel
+
+use std::collections::{HashMap, HashSet};
+
+use tracing::debug;
+#[cfg(feature = "httpws")]
+use tracing::warn;
+
+use crate::book::OrderBooks;
+#[cfg(feature = "httpws")]
+use crate::httpws::RestClient;
+use crate::order_manager::{trade_fills, Fill, TradeStatus};
+use crate::types::{OrderSide, ResolvedMarket, TradeMessage};
+
+/// Sizes below this are treated as flat
+const SIZE_EPSILON: f64 = 1e-9;
+
+/// Position in one token
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Position {
+    /// Token identifier
+    pub asset_id: String,
+    /// Signed size (positive = long)
+    pub size: f64,
+    /// Average entry price of the open size (0 when flat)
+    pub avg_price: f64,
+    /// PnL realized by closing trades, before fees
+    pub realized_pnl: f64,
+    /// Fees paid (USDC)
+    pub fees: f64,
+    /// Last mark price
+    pub mark: Option<f64>,
+}
+
+impl Position {
+    fn new(asset_id: &str) -> Self {
+        Self { asset_id: asset_id.to_string(), ..Default::default() }
+    }
+
+    /// Apply one trade
+    pub fn apply(&mut self, side: OrderSide, price: f64, size: f64, fee: f64) {
+        let signed = match side {
+            OrderSide::Buy => size,
+            OrderSide::Sell => -size,
+        };
+        self.fees += fee;
+
+        if self.is_flat() || self.size.signum() == signed.signum() {
+            let open = self.size.abs();
+            self.avg_price = (self.avg_price * open + price * size) / (open + size);
+            self.size += signed;
+            return;
+        }
+
+        let closing = size.min(self.size.abs());
+        self.realized_pnl += closing * (price - self.avg_price) * self.size.signum();
+        self.size += signed;
+
+        if self.is_flat() {
+            self.size = 0.0;
+            self.avg_price = 0.0;
+        } else if self.size.signum() == signed.signum() {
+            // Flipped through zero: the remainder opens at the trade price
+            self.avg_price = price;
+        }
+    }
+
+    /// No open size
+    pub fn is_flat(&self) -> bool {
+        self.size.abs() < SIZE_EPSILON
+    }
+
+    /// Open size valued at the mark, minus its cost (None without a mark)
+    pub fn unrealized_pnl(&self) -> Option<f64> {
+        if self.is_flat() {
+            return Some(0.0);
+        }
+        self.mark.map(|mark| (mark - self.avg_price) * self.size)
+    }
+
+    /// Realized + unrealized - fees (unrealized counts as 0 without a mark)
+    pub fn total_pnl(&self) -> f64 {
+        self.realized_pnl + self.unrealized_pnl().unwrap_or(0.0) - self.fees
+    }
+}
+
+/// Up/Down token pair of one market viewed as a single position
+#[derive(Clone, Debug, PartialEq)]
+pub struct BinaryPosition {
+    /// Condition ID
+    pub condition_id: String,
+    /// Up (first outcome) token position
+    pub up: Position,
+    /// Down (second outcome) token position
+    pub down: Position,
+}
+
+impl BinaryPosition {
+    /// Net exposure in Up shares (long Down counts as short Up)
+    pub fn net_up(&self) -> f64 {
+        self.up.size - self.down.size
+    }
+
+    /// Up/Down pairs held, each worth $1 at resolution
+    pub fn paired(&self) -> f64 {
+        self.up.size.min(self.down.size).max(0.0)
+    }
+
+    /// Up mark, or `1 - down mark`
+    pub fn up_mark(&self) -> Option<f64> {
+        self.up.mark.or(self.down.mark.map(|m| 1.0 - m))
+    }
+
+    /// Down mark, or `1 - up mark`
+    pub fn down_mark(&self) -> Option<f64> {
+        self.down.mark.or(self.up.mark.map(|m| 1.0 - m))
+    }
+
+    /// Unrealized PnL of both legs (None if neither token has a mark)
+    pub fn unrealized_pnl(&self) -> Option<f64> {
+        let leg = |p: &Position, mark: Option<f64>| -> Option<f64> {
+            if p.is_flat() {
+                Some(0.0)
+            } else {
+                mark.map(|m| (m - p.avg_price) * p.size)
+            }
+        };
+        Some(leg(&self.up, self.up_mark())? + leg(&self.down, self.down_mark())?)
+    }
+
+    /// Realized PnL of both legs, before fees
+    pub fn realized_pnl(&self) -> f64 {
+        self.up.realized_pnl + self.down.realized_pnl
+    }
+
+    /// Fees of both legs
+    pub fn fees(&self) -> f64 {
+        self.up.fees + self.down.fees
+    }
+
+    /// Realized + unrealized - fees
+    pub fn total_pnl(&self) -> f64 {
+        self.realized_pnl() + self.unrealized_pnl().unwrap_or(0.0) - self.fees()
+    }
+}
+
+/// Fee in USDC for one fill
+pub fn fill_fee(price: f64, size: f64, fee_rate_bps: f64) -> f64 {
+    size * price.min(1.0 - price) * fee_rate_bps / 10_000.0
+}
+
+/// Positions for every traded token, with PnL
+#[derive(Debug, Default)]
+pub struct PositionBook {
+    positions: HashMap<String, Position>,
+    /// Counted fills per token, in order (replayed when a trade fails)
+    fills: HashMap<String, Vec<Fill>>,
+    applied: HashSet<(String, String)>,
+    markets: HashMap<String, [String; 2]>,
+}
+
+impl PositionBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the Up/Down tokens of a market for `binary_position`
+    pub fn register_market(&mut self, market: &ResolvedMarket) {
+        self.markets.insert(market.condition_id.clone(), market.clob_token_ids.clone());
+    }
+
+    /// Apply a user channel trade message; returns the number of fills that changed positions
+    pub fn apply_trade(&mut self, msg: &TradeMessage) -> usize {
+        trade_fills(msg, |_| None).iter().filter(|fill| self.apply_fill(fill)).count()
+    }
+
+    /// Apply one of our fills (e.g. from `OrderManager::fills`)
+    ///
+    /// Returns true if the position changed.
+    pub fn apply_fill(&mut self, fill: &Fill) -> bool {
+        let key = (fill.trade_id.clone(), fill.order_id.clone());
+        let applied = self.applied.contains(&key);
+
+        match (fill.status, applied) {
+            (TradeStatus::Failed, true) => {
+                debug!("Trade {} failed, rebuilding {}", fill.trade_id, fill.asset_id);
+                self.applied.remove(&key);
+                if let Some(fills) = self.fills.get_mut(&fill.asset_id) {
+                    fills.retain(|f| f.trade_id != key.0 || f.order_id != key.1);
+                }
+                self.rebuild(&fill.asset_id);
+                true
+            }
+            (TradeStatus::Failed, false) | (_, true) => false,
+            (_, false) => {
+                self.applied.insert(key);
+                self.fills.entry(fill.asset_id.clone()).or_default().push(fill.clone());
+                let position = self
+                    .positions
+                    .entry(fill.asset_id.clone())
+                    .or_insert_with(|| Position::new(&fill.asset_id));
+                apply_to(position, fill);
+                true
+            }
+        }
+    }
+
+    /// Recompute a token's position from its counted fills, keeping the mark
+    fn rebuild(&mut self, asset_id: &str) {
+        let mark = self.positions.get(asset_id).and_then(|p| p.mark);
+        let mut position = Position { mark, ..Position::new(asset_id) };
+        for fill in self.fills.get(asset_id).into_iter().flatten() {
+            apply_to(&mut position, fill);
+        }
+        self.positions.insert(asset_id.to_string(), position);
+    }
+
+    /// Set the mark price of a token
+    pub fn set_mark(&mut self, asset_id: &str, price: f64) {
+        self.positions
+            .entry(asset_id.to_string())
+            .or_insert_with(|| Position::new(asset_id))
+            .mark = Some(price);
+    }
+
+    /// Mark every position that has a two-sided book; returns the number marked
+    pub fn mark_from_books(&mut self, books: &OrderBooks) -> usize {
+        let mut marked = 0;
+        for position in self.positions.values_mut() {
+            if let Some(mid) = books.get(&position.asset_id).and_then(|b| b.mid()) {
+                position.mark = Some(mid);
+                marked += 1;
+            }
+        }
+        marked
+    }
+
+    /// Mark every position (and registered market token) from `GET /midpoint`
+    ///
+    /// Tokens whose midpoint cannot be fetched keep their previous mark.
+    #[cfg(feature = "httpws")]
+    pub async fn mark_from_rest(&mut self, rest: &RestClient) -> usize {
+        let mut asset_ids: Vec<String> = self.positions.keys().cloned().collect();
+        asset_ids.extend(self.markets.values().flatten().cloned());
+        asset_ids.sort();
+        asset_ids.dedup();
+
+        let mut marked = 0;
+        for asset_id in asset_ids {
+            match rest.get_midpoint(&asset_id).await {
+                Ok(response) => match response.mid.parse::<f64>() {
+                    Ok(mid) => {
+                        self.set_mark(&asset_id, mid);
+                        marked += 1;
+                    }
+                    Err(_) => warn!("Invalid midpoint {} for {}", response.mid, asset_id),
+                },
+                Err(e) => warn!("Midpoint fetch failed for {}: {}", asset_id, e),
+            }
+        }
+        marked
+    }
+
+    /// Position in one token
+    pub fn get(&self, asset_id: &str) -> Option<&Position> {
+        self.positions.get(asset_id)
+    }
+
+    /// All token positions, sorted by asset id
+    pub fn positions(&self) -> Vec<&Position> {
+        let mut positions: Vec<&Position> = self.positions.values().collect();
+        positions.sort_by(|a, b| a.asset_id.cmp(&b.asset_id));
+        positions
+    }
+
+    /// Up/Down view of a registered market
+    pub fn binary_position(&self, condition_id: &str) -> Option<BinaryPosition> {
+        let [up, down] = self.markets.get(condition_id)?;
+        let leg = |id: &str| self.positions.get(id).cloned().unwrap_or_else(|| Position::new(id));
+        Some(BinaryPosition {
+            condition_id: condition_id.to_string(),
+            up: leg(up),
+            down: leg(down),
+        })
+    }
+
+    /// Total PnL over all tokens
+    pub fn total_pnl(&self) -> f64 {
+        self.positions.values().map(Position::total_pnl).sum()
+    }
+}
+
+fn apply_to(position: &mut Position, fill: &Fill) {
+    let price = fill.price.parse().unwrap_or(0.0);
+    let size = fill.size_f64();
+    let fee = fill_fee(price, size, fill.fee_rate_bps.parse().unwrap_or(0.0));
+    position.apply(fill.side, price, size, fee);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order_manager::Liquidity;
+
+    const EPS: f64 = 1e-9;
+
+    fn fill(trade_id: &str, asset_id: &str, side: OrderSide, price: &str, size: &str) -> Fill {
+        Fill {
+            trade_id: trade_id.to_string(),
+            order_id: format!("o-{}", trade_id),
+            market: "0xm".to_string(),
+            asset_id: asset_id.to_string(),
+            side,
+            price: price.to_string(),
+            size: size.to_string(),
+            fee_rate_bps: "0".to_string(),
+            status: TradeStatus::Matched,
+            liquidity: Liquidity::Taker,
+            timestamp: None,
+        }
+    }
+
+    #[test]
+    fn test_average_cost_and_realized() {
+        let mut position = Position::new("tok");
+        position.apply(OrderSide::Buy, 0.40, 10.0, 0.0);
+        position.apply(OrderSide::Buy, 0.50, 10.0, 0.0);
+        assert!((position.avg_price - 0.45).abs() < EPS);
+
+        position.apply(OrderSide::Sell, 0.60, 5.0, 0.0);
+        assert!((position.realized_pnl - 0.75).abs() < EPS);
+        assert!((position.size - 15.0).abs() < EPS);
+        assert!((position.avg_price - 0.45).abs() < EPS);
+
+        // Flip through zero: 15 closed, 5 short opened at 0.30
+        position.apply(OrderSide::Sell, 0.30, 20.0, 0.0);
+        assert!((position.realized_pnl - (0.75 - 2.25)).abs() < EPS);
+        assert!((position.size + 5.0).abs() < EPS);
+        assert!((position.avg_price - 0.30).abs() < EPS);
+
+        position.mark = Some(0.20);
+        assert!((position.unrealized_pnl().unwrap() - 0.5).abs() < EPS);
+    }
+
+    #[test]
+    fn test_fee() {
+        // Fee uses min(price, 1 - price)
+        assert!((fill_fee(0.9, 100.0, 100.0) - 0.1).abs() < EPS);
+        assert!((fill_fee(0.2, 100.0, 100.0) - 0.2).abs() < EPS);
+    }
+
+    #[test]
+    fn test_failed_trade_rebuilds_position() {
+        let mut book = PositionBook::new();
+        assert!(book.apply_fill(&fill("t1", "up", OrderSide::Buy, "0.40", "10")));
+        assert!(book.apply_fill(&fill("t2", "up", OrderSide::Buy, "0.60", "10")));
+
+        // Later statuses of a counted trade do not double count
+        let mut mined = fill("t1", "up", OrderSide::Buy, "0.40", "10");
+        mined.status = TradeStatus::Mined;
+        assert!(!book.apply_fill(&mined));
+        book.set_mark("up", 0.5);
+
+        let mut failed = fill("t1", "up", OrderSide::Buy, "0.40", "10");
+        failed.status = TradeStatus::Failed;
+        assert!(book.apply_fill(&failed));
+        let position = book.get("up").unwrap();
+        assert!((position.size - 10.0).abs() < EPS);
+        assert!((position.avg_price - 0.60).abs() < EPS);
+        assert_eq!(position.mark, Some(0.5));
+
+        // A trade that fails before being counted is ignored
+        let mut failed = fill("t3", "up", OrderSide::Buy, "0.40", "10");
+        failed.status = TradeStatus::Failed;
+        assert!(!book.apply_fill(&failed));
+    }
+
+    #[test]
+    fn test_apply_trade_message_with_fee() {
+        let mut book = PositionBook::new();
+        let msg: TradeMessage = serde_json::from_value(serde_json::json!({
+            "id": "t1",
+            "asset_id": "up",
+            "market": "0xm",
+            "price": "0.5",
+            "side": "BUY",
+            "size": "100",
+            "status": "MATCHED",
+            "taker_order_id": "o1",
+            "fee_rate_bps": "20"
+        }))
+        .unwrap();
+        assert_eq!(book.apply_trade(&msg), 1);
+        assert_eq!(book.apply_trade(&msg), 0);
+
+        let position = book.get("up").unwrap();
+        assert!((position.size - 100.0).abs() < EPS);
+        assert!((position.fees - 0.1).abs() < EPS);
+        assert!((position.total_pnl() + 0.1).abs() < EPS);
+    }
+
+    #[test]
+    fn test_binary_position() {
+        let mut book = PositionBook::new();
+        book.markets.insert("0xm".to_string(), ["up".to_string(), "down".to_string()]);
+        book.apply_fill(&fill("t1", "up", OrderSide::Buy, "0.40", "10"));
+        book.apply_fill(&fill("t2", "down", OrderSide::Buy, "0.55", "4"));
+
+        let binary = book.binary_position("0xm").unwrap();
+        assert!((binary.net_up() - 6.0).abs() < EPS);
+        assert!((binary.paired() - 4.0).abs() < EPS);
+        assert_eq!(binary.unrealized_pnl(), None);
+
+        // Only the Up mark is known; Down is marked at its complement
+        book.set_mark("up", 0.5);
+        let binary = book.binary_position("0xm").unwrap();
+        assert!((binary.down_mark().unwrap() - 0.5).abs() < EPS);
+        let expected = 10.0 * (0.5 - 0.40) + 4.0 * (0.5 - 0.55);
+        assert!((binary.unrealized_pnl().unwrap() - expected).abs() < EPS);
+        assert!(book.binary_position("0xother").is_none());
+    }
+
+    #[test]
+    fn test_mark_from_books() {
+        let mut books = OrderBooks::new();
+        books.apply(&crate::types::WsInboundMessage::parse(
+            r#"{"event_type":"book","asset_id":"up","market":"0xm","timestamp":"1",
+                "bids":[{"price":"0.48","size":"10"}],"asks":[{"price":"0.52","size":"10"}]}"#,
+        ));
+
+        let mut book = PositionBook::new();
+        book.apply_fill(&fill("t1", "up", OrderSide::Buy, "0.40", "10"));
+        book.apply_fill(&fill("t2", "down", OrderSide::Buy, "0.55", "4"));
+        assert_eq!(book.mark_from_books(&books), 1);
+        assert_eq!(book.get("up").unwrap().mark, Some(0.5));
+        assert_eq!(book.get("down").unwrap().mark, None);
+    }
+}
+
+#[cfg(all(test, feature = "httpws"))]
+mod wiremock_tests {
+    use super::*;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_mark_from_rest() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/midpoint"))
+            .and(query_param("token_id", "up"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"mid": "0.61"})),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/midpoint"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let rest = RestClient::with_base_url(&server.uri()).unwrap();
+        let mut book = PositionBook::new();
+        book.markets.insert("0xm".to_string(), ["up".to_string(), "down".to_string()]);
+
+        assert_eq!(book.mark_from_rest(&rest).await, 1);
+        let binary = book.binary_position("0xm").unwrap();
+        assert_eq!(binary.up.mark, Some(0.61));
+        assert!((binary.down_mark().unwrap() - 0.39).abs() < 1e-9);
+    }
+}
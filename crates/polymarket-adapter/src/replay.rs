@@ -0,0 +1,633 @@
+//! Replay of recorded JSONL through the live consumer interface
+//!
+//! `ReplaySource` reads files written by the market/user clients (one raw
+//! frame per line) and delivers parsed `WsInboundMessage`s exactly like
+//! `MarketWsClient::run_with` / `spawn_stream`: same channel type, same
+//! `MessageStats`, same limit and shutdown handling.
+//!
+//! # Lifecycle
+//! Lifecycle events are derived from the recording, since the subscribe
+//! frames themselves are not recorded:
+//! - `Connected` before the first frame of each connection: on every change of
+//!   the envelope connection id, or once at the start of a plain file (id 0)
+//! - `Resynced` after the first `book` snapshot of an asset on a connection
+//! - `Disconnected` for the assets seen on a connection when the next one
+//!   starts and when the replay ends
+//!
+//! There is no `Subscribed` event.
+//!
+//! # Formats
+//! `RecordReader` accepts plain files (one raw frame per line) and enveloped
+//! files (`RecordEnvelope` per line: local receive time, connection id,
+//! reconnect epoch), even mixed within one file. Files ending in `.gz` / `.zst`
+//! are decompressed on the fly.
+//!
+//! # Sessions
+//! `ReplaySource::from_manifest` replays every segment of a rotating recording
+//! (`SessionManifest`) in order, as one continuous stream.
+//!
+//! # Modes
+//! - `AsFastAsPossible`: lines in file order, paced only by channel backpressure
+//! - `RealTime { speed }`: sleeps so timestamps advance at `speed` x wall clock;
+//!   enveloped lines use the receive time (true inter-arrival gaps), plain lines
+//!   the server timestamp; lines without either (or going backwards) are sent at once
+//!
+//! Both modes deliver the same messages in the same order.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_compression::tokio::bufread::{GzipDecoder, ZstdDecoder};
+use tokio::fs::File;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, BufReader, Lines};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tracing::{debug, info};
+
+use crate::error::{AdapterError, AdapterResult};
+use crate::types::{
+    LifecycleEvent, MarketMessage, MessageStats, RecordEnvelope, SessionManifest, WsInboundMessage,
+};
+
+/// One recorded line, plain or enveloped
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RecordedFrame {
+    /// Frame text as received
+    pub raw: String,
+    /// Local receive time (unix ns), enveloped lines only
+    pub recv_ts_ns: Option<u64>,
+    /// Connection id, enveloped lines only
+    pub conn_id: Option<u64>,
+    /// Reconnect epoch, enveloped lines only
+    pub epoch: Option<u64>,
+}
+
+impl RecordedFrame {
+    /// Parse a recorded line (plain lines are taken verbatim)
+    pub fn from_line(line: &str) -> Self {
+        match RecordEnvelope::parse_line(line) {
+            Some(e) => Self {
+                raw: e.raw,
+                recv_ts_ns: Some(e.recv_ts_ns),
+                conn_id: Some(e.conn_id),
+                epoch: Some(e.epoch),
+            },
+            None => Self { raw: line.to_string(), recv_ts_ns: None, conn_id: None, epoch: None },
+        }
+    }
+
+    /// Parse the frame
+    pub fn message(&self) -> WsInboundMessage {
+        WsInboundMessage::parse(&self.raw)
+    }
+
+    /// Feed latency: receive time minus server timestamp (ms)
+    pub fn latency_ms(&self, msg: &WsInboundMessage) -> Option<f64> {
+        let recv_ms = self.recv_ts_ns? as f64 / 1e6;
+        Some(recv_ms - msg.timestamp_ms()? as f64)
+    }
+}
+
+type LineSource = Box<dyn AsyncBufRead + Unpin + Send>;
+
+/// Line reader for plain and enveloped recordings
+pub struct RecordReader {
+    lines: Lines<LineSource>,
+}
+
+impl RecordReader {
+    /// Open a recording, decompressing `.gz` / `.zst` files
+    pub async fn open(path: &Path) -> AdapterResult<Self> {
+        let file = File::open(path).await.map_err(|e| {
+            std::io::Error::new(e.kind(), format!("Failed to open {}: {}", path.display(), e))
+        })?;
+        let file = BufReader::new(file);
+        let source: LineSource = match path.extension().and_then(|e| e.to_str()) {
+            Some("gz") => Box::new(BufReader::new(GzipDecoder::new(file))),
+            Some("zst") => Box::new(BufReader::new(ZstdDecoder::new(file))),
+            _ => Box::new(file),
+        };
+        Ok(Self { lines: source.lines() })
+    }
+
+    /// Next frame, skipping blank lines and PONG; None at end of file
+    pub async fn next_frame(&mut self) -> AdapterResult<Option<RecordedFrame>> {
+        while let Some(line) = self.lines.next_line().await? {
+            let line = line.trim();
+            if line.is_empty() || line == "PONG" {
+                continue;
+            }
+            return Ok(Some(RecordedFrame::from_line(line)));
+        }
+        Ok(None)
+    }
+}
+
+/// Replay pacing
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ReplayMode {
+    /// No delays
+    #[default]
+    AsFastAsPossible,
+    /// Follow message timestamps, `speed` times faster than recorded
+    RealTime { speed: f64 },
+}
+
+impl ReplayMode {
+    /// Real-time pacing at recorded speed
+    pub fn real_time() -> Self {
+        ReplayMode::RealTime { speed: 1.0 }
+    }
+}
+
+/// JSONL replay source
+#[derive(Clone, Debug)]
+pub struct ReplaySource {
+    paths: Vec<PathBuf>,
+    mode: ReplayMode,
+}
+
+impl ReplaySource {
+    /// Replay a file as fast as possible
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self { paths: vec![path.as_ref().to_path_buf()], mode: ReplayMode::default() }
+    }
+
+    /// Replay all segments of a recording session, in manifest order
+    ///
+    /// Segment names resolve relative to the manifest directory; a segment
+    /// listed uncompressed but since compressed (`.gz` / `.zst`) is picked up too.
+    pub async fn from_manifest(path: impl AsRef<Path>) -> AdapterResult<Self> {
+        let path = path.as_ref();
+        let content = tokio::fs::read(path).await.map_err(|e| {
+            std::io::Error::new(e.kind(), format!("Failed to open {}: {}", path.display(), e))
+        })?;
+        let manifest: SessionManifest = serde_json::from_slice(&content)
+            .map_err(|e| AdapterError::decode(path.display().to_string(), e))?;
+        let dir = path.parent().unwrap_or(Path::new("."));
+
+        let mut paths = Vec::with_capacity(manifest.segments.len());
+        for segment in &manifest.segments {
+            let listed = dir.join(&segment.file);
+            let found = [None, Some("gz"), Some("zst")].into_iter().find_map(|ext| {
+                let candidate = match ext {
+                    None => listed.clone(),
+                    Some(ext) => PathBuf::from(format!("{}.{}", listed.display(), ext)),
+                };
+                candidate.exists().then_some(candidate)
+            });
+            paths.push(found.ok_or_else(|| {
+                AdapterError::NotFound(format!(
+                    "Segment {} of session {} not found",
+                    segment.file, manifest.session
+                ))
+            })?);
+        }
+        Ok(Self { paths, mode: ReplayMode::default() })
+    }
+
+    /// Set pacing mode
+    pub fn with_mode(mut self, mode: ReplayMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Files replayed, in order
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    pub fn mode(&self) -> ReplayMode {
+        self.mode
+    }
+
+    /// Spawn the replay and stream parsed messages through a channel
+    ///
+    /// Mirrors `MarketWsClient::spawn_stream`; the join handle resolves to the
+    /// final stats once the file is exhausted, `shutdown` is set or the
+    /// receiver is dropped.
+    pub fn spawn_stream(
+        self,
+        buffer: usize,
+        shutdown: Arc<AtomicBool>,
+    ) -> (mpsc::Receiver<WsInboundMessage>, JoinHandle<AdapterResult<MessageStats>>) {
+        let (tx, rx) = mpsc::channel(buffer);
+        let handle = tokio::spawn(async move { self.run_with(Some(tx), 0, shutdown).await });
+        (rx, handle)
+    }
+
+    /// Replay the file(s) with optional channel output
+    ///
+    /// # Arguments
+    /// * `sender` - Optional channel receiving each parsed message
+    /// * `limit` - Maximum messages to replay (0 = everything)
+    /// * `shutdown` - Atomic flag to signal shutdown
+    pub async fn run_with(
+        &self,
+        sender: Option<mpsc::Sender<WsInboundMessage>>,
+        limit: u64,
+        shutdown: Arc<AtomicBool>,
+    ) -> AdapterResult<MessageStats> {
+        let mut stats = MessageStats::new();
+        let mut clock = ReplayClock::new(self.mode);
+        let mut lifecycle = ReplayLifecycle::default();
+        let mut receiver_alive = true;
+
+        'files: for path in &self.paths {
+            let mut reader = RecordReader::open(path).await?;
+            info!("Replaying {} ({:?})", path.display(), self.mode);
+
+            while let Some(frame) = reader.next_frame().await? {
+                if shutdown.load(Ordering::Relaxed) {
+                    break 'files;
+                }
+                if limit > 0 && stats.total_messages >= limit {
+                    info!("Reached message limit: {}", limit);
+                    break 'files;
+                }
+
+                let parsed = frame.message();
+                let timestamp_ns = match frame.recv_ts_ns {
+                    Some(ns) => i64::try_from(ns).ok(),
+                    None => parsed.timestamp_ms().map(|ms| ms.saturating_mul(1_000_000)),
+                };
+                clock.wait_for(timestamp_ns).await;
+                stats.record(&parsed);
+
+                let mut messages: Vec<WsInboundMessage> = lifecycle
+                    .on_frame(&frame)
+                    .into_iter()
+                    .map(WsInboundMessage::Lifecycle)
+                    .collect();
+                let resynced = lifecycle.on_message(&parsed);
+                messages.push(parsed);
+                messages.extend(resynced.into_iter().map(WsInboundMessage::Lifecycle));
+                if !send_all(&sender, messages).await {
+                    receiver_alive = false;
+                    break 'files;
+                }
+            }
+        }
+
+        if receiver_alive {
+            let end = lifecycle.finish("end of replay").map(WsInboundMessage::Lifecycle);
+            receiver_alive = send_all(&sender, end).await;
+        }
+        if !receiver_alive {
+            info!("Message receiver dropped, stopping replay");
+        }
+        debug!("Replay finished: {} messages", stats.total_messages);
+        Ok(stats)
+    }
+}
+
+/// Deliver messages in order; false once the receiver is dropped
+async fn send_all(
+    sender: &Option<mpsc::Sender<WsInboundMessage>>,
+    messages: impl IntoIterator<Item = WsInboundMessage>,
+) -> bool {
+    let Some(tx) = sender else {
+        return true;
+    };
+    for msg in messages {
+        if tx.send(msg).await.is_err() {
+            return false;
+        }
+    }
+    true
+}
+
+/// Lifecycle events derived from the recorded connection ids
+#[derive(Debug, Default)]
+struct ReplayLifecycle {
+    /// Connection being replayed
+    conn_id: Option<u64>,
+    /// Assets seen on it, in order
+    assets: Vec<String>,
+    /// Assets whose snapshot was seen on it
+    resynced: HashSet<String>,
+}
+
+impl ReplayLifecycle {
+    /// Events due before a frame: a new connection ends the previous one
+    ///
+    /// Plain frames belong to the current connection (id 0 if none yet).
+    fn on_frame(&mut self, frame: &RecordedFrame) -> Vec<LifecycleEvent> {
+        let conn_id = match (frame.conn_id, self.conn_id) {
+            (Some(id), Some(current)) if id == current => return Vec::new(),
+            (None, Some(_)) => return Vec::new(),
+            (id, _) => id.unwrap_or(0),
+        };
+        let mut events: Vec<LifecycleEvent> =
+            self.finish("connection ended in recording").into_iter().collect();
+        self.conn_id = Some(conn_id);
+        events.push(LifecycleEvent::Connected { conn_id, epoch: frame.epoch.unwrap_or(0) });
+        events
+    }
+
+    /// Track the message's assets; returns `Resynced` for first snapshots
+    fn on_message(&mut self, msg: &WsInboundMessage) -> Vec<LifecycleEvent> {
+        let conn_id = self.conn_id.unwrap_or(0);
+        let is_snapshot = matches!(
+            msg,
+            WsInboundMessage::Market(MarketMessage::Book(_)) | WsInboundMessage::SnapshotArray(_)
+        );
+        let mut events = Vec::new();
+        for asset_id in msg.asset_ids() {
+            if !self.assets.iter().any(|a| a == asset_id) {
+                self.assets.push(asset_id.to_string());
+            }
+            if is_snapshot && self.resynced.insert(asset_id.to_string()) {
+                events.push(LifecycleEvent::Resynced { conn_id, asset_id: asset_id.to_string() });
+            }
+        }
+        events
+    }
+
+    /// `Disconnected` for the current connection, if any
+    fn finish(&mut self, reason: &str) -> Option<LifecycleEvent> {
+        let conn_id = self.conn_id.take()?;
+        self.resynced.clear();
+        Some(LifecycleEvent::Disconnected {
+            conn_id,
+            ids: std::mem::take(&mut self.assets),
+            reason: reason.to_string(),
+        })
+    }
+}
+
+/// Maps recorded timestamps onto the wall clock
+struct ReplayClock {
+    speed: Option<f64>,
+    /// (first recorded timestamp in ns, wall clock at that message)
+    origin: Option<(i64, Instant)>,
+}
+
+impl ReplayClock {
+    fn new(mode: ReplayMode) -> Self {
+        let speed = match mode {
+            ReplayMode::AsFastAsPossible => None,
+            ReplayMode::RealTime { speed } if speed > 0.0 => Some(speed),
+            ReplayMode::RealTime { .. } => None,
+        };
+        Self { speed, origin: None }
+    }
+
+    /// Sleep until the wall-clock time of a recorded timestamp
+    async fn wait_for(&mut self, timestamp_ns: Option<i64>) {
+        let (Some(speed), Some(ts)) = (self.speed, timestamp_ns) else {
+            return;
+        };
+        let (first_ts, start) = *self.origin.get_or_insert((ts, Instant::now()));
+        let offset_secs = (ts - first_ts) as f64 / speed / 1e9;
+        if offset_secs > 0.0 {
+            tokio::time::sleep_until(start + Duration::from_secs_f64(offset_secs)).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SegmentInfo;
+
+    const LINES: &str = r#"{"event_type":"book","asset_id":"up","market":"0xm","timestamp":"1000","bids":[],"asks":[]}
+
+{"event_type":"last_trade_price","asset_id":"up","market":"0xm","timestamp":"3000","price":"0.5","size":"1","side":"BUY"}
+not json
+{"event_type":"price_change","market":"0xm","timestamp":"2000","price_changes":[]}
+"#;
+
+    async fn write_fixture(name: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("replay_{}_{}.jsonl", name, std::process::id()));
+        tokio::fs::write(&path, LINES).await.unwrap();
+        path
+    }
+
+    async fn collect(source: ReplaySource) -> (Vec<WsInboundMessage>, MessageStats) {
+        let (mut rx, handle) = source.spawn_stream(4, Arc::new(AtomicBool::new(false)));
+        let mut messages = Vec::new();
+        while let Some(msg) = rx.recv().await {
+            messages.push(msg);
+        }
+        (messages, handle.await.unwrap().unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_replay_as_fast_as_possible() {
+        let path = write_fixture("fast").await;
+        let (messages, stats) = collect(ReplaySource::new(&path)).await;
+
+        // Plain file: one connection framing the whole replay
+        let types: Vec<_> = messages.iter().map(|m| m.event_type()).collect();
+        assert_eq!(
+            types,
+            [
+                Some("connected"),
+                Some("book"),
+                Some("resynced"),
+                Some("last_trade_price"),
+                None,
+                Some("price_change"),
+                Some("disconnected")
+            ]
+        );
+        assert_eq!(
+            messages[6].lifecycle(),
+            Some(&LifecycleEvent::Disconnected {
+                conn_id: 0,
+                ids: vec!["up".to_string()],
+                reason: "end of replay".to_string()
+            })
+        );
+        assert_eq!(stats.total_messages, 4);
+        assert_eq!(stats.unknown_type_count, 1);
+
+        let stats = ReplaySource::new(&path)
+            .run_with(None, 2, Arc::new(AtomicBool::new(false)))
+            .await
+            .unwrap();
+        assert_eq!(stats.total_messages, 2);
+
+        tokio::fs::remove_file(&path).await.ok();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_replay_real_time() {
+        let path = write_fixture("paced").await;
+        let start = Instant::now();
+        let source = ReplaySource::new(&path).with_mode(ReplayMode::RealTime { speed: 2.0 });
+        let (mut rx, handle) = source.spawn_stream(4, Arc::new(AtomicBool::new(false)));
+
+        let mut arrivals = Vec::new();
+        while let Some(msg) = rx.recv().await {
+            if msg.lifecycle().is_none() {
+                arrivals.push((msg.timestamp_ms(), start.elapsed().as_millis()));
+            }
+        }
+        handle.await.unwrap().unwrap();
+
+        // 2x speed: +2000ms recorded = +1000ms wall; backwards timestamp is not delayed
+        assert_eq!(
+            arrivals,
+            [(Some(1000), 0), (Some(3000), 1000), (None, 1000), (Some(2000), 1000)]
+        );
+
+        tokio::fs::remove_file(&path).await.ok();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_enveloped_replay_uses_receive_time() {
+        let envelope = |recv_ms: u64, raw: &str| {
+            serde_json::to_string(&RecordEnvelope {
+                recv_ts_ns: recv_ms * 1_000_000,
+                conn_id: 1,
+                epoch: 0,
+                raw: raw.to_string(),
+            })
+            .unwrap()
+        };
+        // Server timestamps 1ms apart, received 500ms apart; plain line mixed in
+        let content = [
+            envelope(10_000, r#"{"event_type":"book","asset_id":"up","market":"0xm","timestamp":"9900","bids":[],"asks":[]}"#),
+            envelope(10_500, r#"{"event_type":"price_change","market":"0xm","timestamp":"9901","price_changes":[]}"#),
+            r#"{"event_type":"tick_size_change","asset_id":"up","market":"0xm","timestamp":"bad","old_tick_size":"0.01","new_tick_size":"0.001"}"#.to_string(),
+        ]
+        .join("\n");
+        let path = std::env::temp_dir().join(format!("replay_env_{}.jsonl", std::process::id()));
+        tokio::fs::write(&path, content).await.unwrap();
+
+        let mut reader = RecordReader::open(&path).await.unwrap();
+        let frame = reader.next_frame().await.unwrap().unwrap();
+        assert_eq!((frame.conn_id, frame.epoch), (Some(1), Some(0)));
+        assert_eq!(frame.latency_ms(&frame.message()), Some(100.0));
+        reader.next_frame().await.unwrap().unwrap();
+        let plain = reader.next_frame().await.unwrap().unwrap();
+        assert_eq!(plain.recv_ts_ns, None);
+        assert_eq!(plain.message().event_type(), Some("tick_size_change"));
+        assert!(reader.next_frame().await.unwrap().is_none());
+
+        let start = Instant::now();
+        let source = ReplaySource::new(&path).with_mode(ReplayMode::real_time());
+        let (mut rx, handle) = source.spawn_stream(4, Arc::new(AtomicBool::new(false)));
+        let mut arrivals = Vec::new();
+        while let Some(msg) = rx.recv().await {
+            if msg.lifecycle().is_none() {
+                let event_type = msg.event_type().unwrap_or_default().to_string();
+                arrivals.push((event_type, start.elapsed().as_millis()));
+            }
+        }
+        handle.await.unwrap().unwrap();
+        assert_eq!(
+            arrivals,
+            [
+                ("book".to_string(), 0),
+                ("price_change".to_string(), 500),
+                ("tick_size_change".to_string(), 500)
+            ]
+        );
+
+        tokio::fs::remove_file(&path).await.ok();
+    }
+
+    #[tokio::test]
+    async fn test_replay_lifecycle_follows_envelope_connections() {
+        let envelope = |conn_id: u64, epoch: u64, raw: &str| {
+            serde_json::to_string(&RecordEnvelope {
+                recv_ts_ns: 0,
+                conn_id,
+                epoch,
+                raw: raw.to_string(),
+            })
+            .unwrap()
+        };
+        let book = r#"{"event_type":"book","asset_id":"up","market":"0xm","timestamp":"1","bids":[],"asks":[]}"#;
+        let trade = r#"{"event_type":"last_trade_price","asset_id":"down","market":"0xm","timestamp":"2","price":"0.5","size":"1","side":"BUY"}"#;
+        let content =
+            [envelope(7, 0, book), envelope(7, 0, trade), envelope(8, 1, book)].join("\n");
+        let path = std::env::temp_dir().join(format!("replay_conn_{}.jsonl", std::process::id()));
+        tokio::fs::write(&path, content).await.unwrap();
+
+        let (messages, stats) = collect(ReplaySource::new(&path)).await;
+        let lifecycle: Vec<LifecycleEvent> =
+            messages.iter().filter_map(|m| m.lifecycle().cloned()).collect();
+        let disconnected =
+            |conn_id: u64, ids: &[&str], reason: &str| LifecycleEvent::Disconnected {
+                conn_id,
+                ids: ids.iter().map(|s| s.to_string()).collect(),
+                reason: reason.to_string(),
+            };
+        assert_eq!(
+            lifecycle,
+            [
+                LifecycleEvent::Connected { conn_id: 7, epoch: 0 },
+                LifecycleEvent::Resynced { conn_id: 7, asset_id: "up".to_string() },
+                disconnected(7, &["up", "down"], "connection ended in recording"),
+                LifecycleEvent::Connected { conn_id: 8, epoch: 1 },
+                LifecycleEvent::Resynced { conn_id: 8, asset_id: "up".to_string() },
+                disconnected(8, &["up"], "end of replay"),
+            ]
+        );
+        assert_eq!(stats.total_messages, 3);
+
+        tokio::fs::remove_file(&path).await.ok();
+    }
+
+    #[tokio::test]
+    async fn test_replay_manifest_with_compressed_segments() {
+        use async_compression::tokio::write::{GzipEncoder, ZstdEncoder};
+        use tokio::io::AsyncWriteExt;
+
+        let dir = std::env::temp_dir().join(format!("replay_session_{}", std::process::id()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        let (first, second) = LINES.split_at(LINES.find("not json").unwrap());
+
+        let mut gz = GzipEncoder::new(Vec::new());
+        gz.write_all(first.as_bytes()).await.unwrap();
+        gz.shutdown().await.unwrap();
+        tokio::fs::write(dir.join("s.0001.jsonl.gz"), gz.into_inner()).await.unwrap();
+        let mut zst = ZstdEncoder::new(Vec::new());
+        zst.write_all(second.as_bytes()).await.unwrap();
+        zst.shutdown().await.unwrap();
+        tokio::fs::write(dir.join("s.0002.jsonl.zst"), zst.into_inner()).await.unwrap();
+
+        // First segment still listed under its uncompressed name (session in progress)
+        let manifest = SessionManifest {
+            session: "s".to_string(),
+            format: "raw".to_string(),
+            segments: ["s.0001.jsonl", "s.0002.jsonl.zst"]
+                .iter()
+                .map(|f| SegmentInfo { file: f.to_string(), ..Default::default() })
+                .collect(),
+            ..Default::default()
+        };
+        let manifest_path = dir.join("s.manifest.json");
+        tokio::fs::write(&manifest_path, serde_json::to_vec(&manifest).unwrap()).await.unwrap();
+
+        let source = ReplaySource::from_manifest(&manifest_path).await.unwrap();
+        assert_eq!(source.paths(), [dir.join("s.0001.jsonl.gz"), dir.join("s.0002.jsonl.zst")]);
+        let (messages, stats) = collect(source).await;
+        let types: Vec<_> =
+            messages.iter().filter(|m| m.lifecycle().is_none()).map(|m| m.event_type()).collect();
+        assert_eq!(types, [Some("book"), Some("last_trade_price"), None, Some("price_change")]);
+        assert_eq!(stats.total_messages, 4);
+
+        tokio::fs::remove_file(dir.join("s.0002.jsonl.zst")).await.unwrap();
+        let result = ReplaySource::from_manifest(&manifest_path).await;
+        assert!(matches!(result, Err(crate::error::AdapterError::NotFound(_))));
+
+        tokio::fs::remove_dir_all(&dir).await.ok();
+    }
+
+    #[tokio::test]
+    async fn test_replay_missing_file() {
+        let source = ReplaySource::new("/nonexistent/replay.jsonl");
+        let result = source.run_with(None, 0, Arc::new(AtomicBool::new(false))).await;
+        assert!(matches!(result, Err(crate::error::AdapterError::Io(_))));
+    }
+}
@@ -0,0 +1,700 @@
+//! Pre-trade risk limits and kill switch
+//!
+//! # Checks (in order)
+//! 1. Kill switch: once tripped, nothing passes until `reset`
+//! 2. Series freeze: tokens of a frozen series are blocked
+//! 3. Max open orders (all markets)
+//! 4. Max notional per market: remaining notional of open orders plus the new order
+//! 5. Max position per token: the position if every open order on the token on
+//!    the same side fills, plus the new order; rejected only if that grows
+//!    |position| past the limit
+//! 6. Price band: BUY at most `band` above the best ask, SELL at most `band`
+//!    below the best bid (the opposite best is used if that side is empty)
+//!
+//! Unset limits are not checked.
+//!
+//! # Shared Controls
+//! `RiskControls` is a cloneable handle (kill switch + frozen series).
+//! `SwitchController::set_risk_controls` freezes a series on
+//! `SwitchAction::Freeze` and lifts the freeze when the next market is subscribed.
+//!
+//! # Order Submission
+//! `OrderGateway` (httpws backend) runs the checks before `OrderKeeper::place`.
+//! It owns a task that cancels all orders as soon as the kill switch is tripped,
+//! wherever it was tripped from, retrying until the cancel-all succeeds.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::watch;
+use tracing::{info, warn};
+
+use crate::book::OrderBook;
+use crate::order_manager::OrderManager;
+use crate::positions::PositionBook;
+use crate::types::OrderSide;
+#[cfg(feature = "httpws")]
+use std::time::Duration;
+#[cfg(feature = "httpws")]
+use tokio::{sync::mpsc, task::JoinHandle};
+
+#[cfg(feature = "httpws")]
+use crate::{
+    error::{AdapterError, AdapterResult},
+    httpws::{OrderArgs, OrderBuilder, RestClient},
+    order_manager::{OrderEvent, OrderKeeper},
+    types::{CancelResponse, OrderType, PostOrderResponse, WsInboundMessage},
+};
+
+/// Risk limits (None = unchecked)
+#[derive(Clone, Debug, Default)]
+pub struct RiskLimits {
+    /// Max notional (price * size, USDC) of open orders per market
+    pub max_notional_per_market: Option<f64>,
+    /// Max open orders across all markets
+    pub max_open_orders: Option<usize>,
+    /// Max absolute position per token (shares)
+    pub max_position_per_token: Option<f64>,
+    /// Max price distance through the opposite best price
+    pub price_band: Option<f64>,
+}
+
+/// Order to be checked
+#[derive(Clone, Debug, PartialEq)]
+pub struct OrderIntent {
+    /// Condition ID
+    pub market: String,
+    /// Token identifier
+    pub token_id: String,
+    /// Order side
+    pub side: OrderSide,
+    /// Limit price
+    pub price: f64,
+    /// Size in shares
+    pub size: f64,
+}
+
+impl OrderIntent {
+    /// Price * size (USDC)
+    pub fn notional(&self) -> f64 {
+        self.price * self.size
+    }
+
+    /// From order arguments (httpws submission path)
+    #[cfg(feature = "httpws")]
+    pub fn from_args(market: &str, args: &OrderArgs) -> AdapterResult<Self> {
+        let parse = |field: &str, value: &str| {
+            value
+                .parse::<f64>()
+                .map_err(|_| AdapterError::InvalidOrder(format!("Invalid {} {:?}", field, value)))
+        };
+        Ok(Self {
+            market: market.to_string(),
+            token_id: args.token_id.clone(),
+            side: args.side,
+            price: parse("price", &args.price)?,
+            size: parse("size", &args.size)?,
+        })
+    }
+}
+
+/// Reason an order was blocked
+#[derive(Clone, Debug, PartialEq, thiserror::Error)]
+pub enum RiskViolation {
+    /// Kill switch is tripped
+    #[error("Kill switch tripped: {0}")]
+    KillSwitch(String),
+
+    /// Token belongs to a frozen series
+    #[error("Series {series} frozen: {reason}")]
+    SeriesFrozen { series: String, reason: String },
+
+    /// Too many open orders
+    #[error("{open} open orders, limit {limit}")]
+    MaxOpenOrders { open: usize, limit: usize },
+
+    /// Market notional would exceed the limit
+    #[error("Notional {notional:.2} in market {market} exceeds {limit:.2}")]
+    MaxNotional { market: String, notional: f64, limit: f64 },
+
+    /// Token position would exceed the limit
+    #[error("Position {position:.2} in {token_id} exceeds {limit:.2}")]
+    MaxPosition { token_id: String, position: f64, limit: f64 },
+
+    /// Price too far through the book
+    #[error("Price {price} beyond band {band} of reference {reference}")]
+    PriceBand { price: f64, reference: f64, band: f64 },
+
+    /// Price band configured but no book to compare against
+    #[error("No book for {0} to check the price band")]
+    NoBook(String),
+}
+
+#[derive(Debug, Default)]
+struct ControlState {
+    kill_reason: Option<String>,
+    frozen: HashMap<String, String>,
+    token_series: HashMap<String, String>,
+}
+
+/// Shared kill switch and series freezes
+#[derive(Clone, Debug, Default)]
+pub struct RiskControls {
+    state: Arc<Mutex<ControlState>>,
+    /// Bumped on every `trip`
+    trips: watch::Sender<u64>,
+}
+
+impl RiskControls {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trip the kill switch and signal a cancel-all (first reason wins)
+    pub fn trip(&self, reason: &str) {
+        {
+            let mut state = self.state.lock().unwrap();
+            if state.kill_reason.is_none() {
+                warn!("KILL SWITCH tripped: {}", reason);
+                state.kill_reason = Some(reason.to_string());
+            }
+        }
+        self.trips.send_modify(|trips| *trips += 1);
+    }
+
+    /// Receiver that changes on every `trip`
+    pub fn subscribe_trips(&self) -> watch::Receiver<u64> {
+        self.trips.subscribe()
+    }
+
+    /// Re-arm after a kill
+    pub fn reset(&self) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(reason) = state.kill_reason.take() {
+            info!("Kill switch reset (was: {})", reason);
+        }
+    }
+
+    /// Reason the kill switch was tripped, if it is
+    pub fn kill_reason(&self) -> Option<String> {
+        self.state.lock().unwrap().kill_reason.clone()
+    }
+
+    pub fn is_killed(&self) -> bool {
+        self.state.lock().unwrap().kill_reason.is_some()
+    }
+
+    /// Block new orders for a series
+    pub fn freeze_series(&self, series: &str, reason: &str) {
+        let mut state = self.state.lock().unwrap();
+        if state.frozen.insert(series.to_string(), reason.to_string()).is_none() {
+            warn!("Series {} frozen for new orders: {}", series, reason);
+        }
+    }
+
+    /// Allow new orders for a series again; returns false if it was not frozen
+    pub fn unfreeze_series(&self, series: &str) -> bool {
+        let removed = self.state.lock().unwrap().frozen.remove(series).is_some();
+        if removed {
+            info!("Series {} unfrozen", series);
+        }
+        removed
+    }
+
+    /// Freeze reason of a series, if frozen
+    pub fn frozen_reason(&self, series: &str) -> Option<String> {
+        self.state.lock().unwrap().frozen.get(series).cloned()
+    }
+
+    /// Map tokens to a series so series freezes apply to them
+    pub fn assign_tokens(&self, series: &str, tokens: &[String]) {
+        let mut state = self.state.lock().unwrap();
+        for token in tokens {
+            state.token_series.insert(token.clone(), series.to_string());
+        }
+    }
+
+    /// Series a token was assigned to
+    pub fn series_of(&self, token_id: &str) -> Option<String> {
+        self.state.lock().unwrap().token_series.get(token_id).cloned()
+    }
+
+    /// Kill switch and series freeze checks for one token
+    pub fn check(&self, token_id: &str) -> Result<(), RiskViolation> {
+        let state = self.state.lock().unwrap();
+        if let Some(reason) = &state.kill_reason {
+            return Err(RiskViolation::KillSwitch(reason.clone()));
+        }
+        if let Some(series) = state.token_series.get(token_id) {
+            if let Some(reason) = state.frozen.get(series) {
+                return Err(RiskViolation::SeriesFrozen {
+                    series: series.clone(),
+                    reason: reason.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Pre-trade checks against limits and shared controls
+#[derive(Clone, Debug, Default)]
+pub struct RiskManager {
+    limits: RiskLimits,
+    controls: RiskControls,
+}
+
+impl RiskManager {
+    /// Create with fresh controls
+    pub fn new(limits: RiskLimits) -> Self {
+        Self::with_controls(limits, RiskControls::new())
+    }
+
+    /// Create sharing existing controls
+    pub fn with_controls(limits: RiskLimits, controls: RiskControls) -> Self {
+        Self { limits, controls }
+    }
+
+    pub fn limits(&self) -> &RiskLimits {
+        &self.limits
+    }
+
+    /// Shared controls handle
+    pub fn controls(&self) -> &RiskControls {
+        &self.controls
+    }
+
+    /// Run every check for a new order
+    ///
+    /// Open orders without a known market (pending) count toward the market
+    /// of the intent if they are on the same token.
+    pub fn check(
+        &self,
+        intent: &OrderIntent,
+        orders: &OrderManager,
+        positions: &PositionBook,
+        book: Option<&OrderBook>,
+    ) -> Result<(), RiskViolation> {
+        self.controls.check(&intent.token_id)?;
+
+        if let Some(limit) = self.limits.max_open_orders {
+            let open = orders.open_orders(None).len();
+            if open >= limit {
+                return Err(RiskViolation::MaxOpenOrders { open, limit });
+            }
+        }
+
+        if let Some(limit) = self.limits.max_notional_per_market {
+            let resting: f64 = orders
+                .open_orders(None)
+                .into_iter()
+                .filter(|o| o.market == intent.market || o.asset_id == intent.token_id)
+                .map(|o| o.price.parse::<f64>().unwrap_or(0.0) * o.remaining_f64())
+                .sum();
+            let notional = resting + intent.notional();
+            if notional > limit {
+                return Err(RiskViolation::MaxNotional {
+                    market: intent.market.clone(),
+                    notional,
+                    limit,
+                });
+            }
+        }
+
+        if let Some(limit) = self.limits.max_position_per_token {
+            let current = positions.get(&intent.token_id).map(|p| p.size).unwrap_or(0.0);
+            // Resting orders on the same side could all fill before this one
+            let resting: f64 = orders
+                .open_orders(None)
+                .into_iter()
+                .filter(|o| o.asset_id == intent.token_id && o.side == intent.side)
+                .map(|o| o.remaining_f64())
+                .sum();
+            let position = match intent.side {
+                OrderSide::Buy => current + resting + intent.size,
+                OrderSide::Sell => current - resting - intent.size,
+            };
+            if position.abs() > limit && position.abs() > current.abs() {
+                return Err(RiskViolation::MaxPosition {
+                    token_id: intent.token_id.clone(),
+                    position,
+                    limit,
+                });
+            }
+        }
+
+        if let Some(band) = self.limits.price_band {
+            let book = book.filter(|b| !b.is_empty());
+            let (ask, bid) = (
+                book.and_then(|b| b.best_ask()).map(|l| l.price_f64()),
+                book.and_then(|b| b.best_bid()).map(|l| l.price_f64()),
+            );
+            let reference = match intent.side {
+                OrderSide::Buy => ask.or(bid),
+                OrderSide::Sell => bid.or(ask),
+            }
+            .ok_or_else(|| RiskViolation::NoBook(intent.token_id.clone()))?;
+
+            let through = match intent.side {
+                OrderSide::Buy => intent.price - reference,
+                OrderSide::Sell => reference - intent.price,
+            };
+            if through > band + 1e-9 {
+                return Err(RiskViolation::PriceBand { price: intent.price, reference, band });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Delay between cancel-all attempts while the kill switch stays tripped
+#[cfg(feature = "httpws")]
+const CANCEL_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Risk-checked order submission path
+///
+/// Every `submit` passes `RiskManager::check` first. A background task cancels
+/// all orders as soon as the kill switch is tripped; its results are applied
+/// to the keeper on the next `submit`, `apply`, `poll_cancels` or `next_cancel`.
+/// Must be created inside a Tokio runtime.
+#[cfg(feature = "httpws")]
+pub struct OrderGateway {
+    keeper: OrderKeeper,
+    builder: OrderBuilder,
+    risk: RiskManager,
+    cancels: mpsc::UnboundedReceiver<AdapterResult<CancelResponse>>,
+    canceller: JoinHandle<()>,
+}
+
+#[cfg(feature = "httpws")]
+impl OrderGateway {
+    pub fn new(keeper: OrderKeeper, builder: OrderBuilder, risk: RiskManager) -> Self {
+        let (tx, cancels) = mpsc::unbounded_channel();
+        let canceller =
+            tokio::spawn(cancel_on_trip(keeper.rest().clone(), risk.controls().clone(), tx));
+        Self { keeper, builder, risk, cancels, canceller }
+    }
+
+    /// Check and place an order
+    ///
+    /// A blocked order fails with `AdapterError::RiskRejected` without any request.
+    pub async fn submit(
+        &mut self,
+        market: &str,
+        args: &OrderArgs,
+        order_type: OrderType,
+        positions: &PositionBook,
+        book: Option<&OrderBook>,
+    ) -> AdapterResult<PostOrderResponse> {
+        self.poll_cancels();
+
+        let intent = OrderIntent::from_args(market, args)?;
+        if let Err(violation) = self.risk.check(&intent, self.keeper.manager(), positions, book) {
+            warn!("Order on {} blocked: {}", intent.token_id, violation);
+            return Err(violation.into());
+        }
+        self.keeper.place(&self.builder, args, order_type).await
+    }
+
+    /// Trip the kill switch and wait for the resulting cancel-all
+    pub async fn kill(&mut self, reason: &str) -> AdapterResult<CancelResponse> {
+        self.poll_cancels();
+        self.risk.controls().trip(reason);
+        self.next_cancel()
+            .await
+            .unwrap_or_else(|| Err(AdapterError::Connection("Kill switch task stopped".into())))
+    }
+
+    /// Apply finished cancel-alls; returns the latest successful one
+    pub fn poll_cancels(&mut self) -> Option<CancelResponse> {
+        let mut latest = None;
+        while let Ok(result) = self.cancels.try_recv() {
+            if let Ok(response) = result {
+                self.keeper.mark_cancelled(&response);
+                latest = Some(response);
+            }
+        }
+        latest
+    }
+
+    /// Wait for the next cancel-all attempt and apply it
+    ///
+    /// Returns None if the background task has stopped.
+    pub async fn next_cancel(&mut self) -> Option<AdapterResult<CancelResponse>> {
+        let result = self.cancels.recv().await?;
+        if let Ok(response) = &result {
+            self.keeper.mark_cancelled(response);
+        }
+        Some(result)
+    }
+
+    /// Apply a user channel message (applying finished cancel-alls first)
+    pub async fn apply(&mut self, msg: &WsInboundMessage) -> Vec<OrderEvent> {
+        self.poll_cancels();
+        self.keeper.apply(msg).await
+    }
+
+    pub fn keeper(&self) -> &OrderKeeper {
+        &self.keeper
+    }
+
+    pub fn risk(&self) -> &RiskManager {
+        &self.risk
+    }
+}
+
+#[cfg(feature = "httpws")]
+impl Drop for OrderGateway {
+    fn drop(&mut self) {
+        self.canceller.abort();
+    }
+}
+
+/// Cancel all orders on every trip, retrying while the switch stays tripped
+#[cfg(feature = "httpws")]
+async fn cancel_on_trip(
+    rest: RestClient,
+    controls: RiskControls,
+    tx: mpsc::UnboundedSender<AdapterResult<CancelResponse>>,
+) {
+    let mut trips = controls.subscribe_trips();
+    if controls.is_killed() {
+        trips.mark_changed();
+    }
+    while trips.changed().await.is_ok() {
+        trips.borrow_and_update();
+        while controls.is_killed() {
+            let result = rest.cancel_all().await;
+            let done = match &result {
+                Ok(response) => {
+                    info!("Kill switch cancelled {} orders", response.canceled.len());
+                    true
+                }
+                Err(e) => {
+                    warn!("Kill switch cancel-all failed: {}", e);
+                    false
+                }
+            };
+            if tx.send(result).is_err() {
+                return;
+            }
+            if done {
+                break;
+            }
+            tokio::time::sleep(CANCEL_RETRY_DELAY).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::WsInboundMessage;
+
+    fn intent(side: OrderSide, price: f64, size: f64) -> OrderIntent {
+        OrderIntent { market: "0xm".to_string(), token_id: "up".to_string(), side, price, size }
+    }
+
+    fn book() -> OrderBook {
+        let mut books = crate::book::OrderBooks::new();
+        books.apply(&WsInboundMessage::parse(
+            r#"{"event_type":"book","asset_id":"up","market":"0xm","timestamp":"1",
+                "bids":[{"price":"0.48","size":"10"}],"asks":[{"price":"0.52","size":"10"}]}"#,
+        ));
+        books.get("up").unwrap().clone()
+    }
+
+    #[test]
+    fn test_kill_switch_and_freeze() {
+        let risk = RiskManager::new(RiskLimits::default());
+        let (orders, positions) = (OrderManager::new(), PositionBook::new());
+        let order = intent(OrderSide::Buy, 0.5, 10.0);
+        assert!(risk.check(&order, &orders, &positions, None).is_ok());
+
+        let controls = risk.controls().clone();
+        controls.assign_tokens("btc15m", &["up".to_string(), "down".to_string()]);
+        controls.freeze_series("btc15m", "MonotonicityViolation");
+        assert!(matches!(
+            risk.check(&order, &orders, &positions, None),
+            Err(RiskViolation::SeriesFrozen { .. })
+        ));
+        assert!(controls.unfreeze_series("btc15m"));
+
+        let trips = controls.subscribe_trips();
+        controls.trip("manual");
+        controls.trip("second reason");
+        assert_eq!(
+            risk.check(&order, &orders, &positions, None),
+            Err(RiskViolation::KillSwitch("manual".to_string()))
+        );
+        // Every trip signals a cancel-all, the first reason is kept
+        assert_eq!(*trips.borrow(), 2);
+
+        controls.reset();
+        assert!(risk.check(&order, &orders, &positions, None).is_ok());
+    }
+
+    #[test]
+    fn test_order_count_and_notional_limits() {
+        let mut orders = OrderManager::new();
+        orders.track_pending("o1", "up", OrderSide::Buy, "0.5", "10");
+        let positions = PositionBook::new();
+
+        let risk = RiskManager::new(RiskLimits { max_open_orders: Some(1), ..Default::default() });
+        assert!(matches!(
+            risk.check(&intent(OrderSide::Buy, 0.5, 1.0), &orders, &positions, None),
+            Err(RiskViolation::MaxOpenOrders { open: 1, limit: 1 })
+        ));
+
+        // 5 resting + 4.5 new > 9
+        let risk = RiskManager::new(RiskLimits {
+            max_notional_per_market: Some(9.0),
+            ..Default::default()
+        });
+        assert!(risk.check(&intent(OrderSide::Buy, 0.5, 8.0), &orders, &positions, None).is_ok());
+        assert!(matches!(
+            risk.check(&intent(OrderSide::Buy, 0.5, 9.0), &orders, &positions, None),
+            Err(RiskViolation::MaxNotional { .. })
+        ));
+    }
+
+    #[test]
+    fn test_position_limit_allows_reducing() {
+        use crate::order_manager::{Fill, Liquidity, TradeStatus};
+
+        let mut positions = PositionBook::new();
+        positions.apply_fill(&Fill {
+            trade_id: "t1".to_string(),
+            order_id: "o1".to_string(),
+            market: "0xm".to_string(),
+            asset_id: "up".to_string(),
+            side: OrderSide::Buy,
+            price: "0.5".to_string(),
+            size: "100".to_string(),
+            fee_rate_bps: "0".to_string(),
+            status: TradeStatus::Matched,
+            liquidity: Liquidity::Taker,
+            timestamp: None,
+        });
+        let orders = OrderManager::new();
+        let risk = RiskManager::new(RiskLimits {
+            max_position_per_token: Some(50.0),
+            ..Default::default()
+        });
+
+        assert!(matches!(
+            risk.check(&intent(OrderSide::Buy, 0.5, 1.0), &orders, &positions, None),
+            Err(RiskViolation::MaxPosition { .. })
+        ));
+        assert!(risk.check(&intent(OrderSide::Sell, 0.5, 10.0), &orders, &positions, None).is_ok());
+    }
+
+    #[test]
+    fn test_position_limit_counts_open_orders() {
+        let mut orders = OrderManager::new();
+        orders.track_pending("o1", "up", OrderSide::Buy, "0.5", "20");
+        orders.track_pending("o2", "up", OrderSide::Buy, "0.5", "20");
+        orders.track_pending("o3", "down", OrderSide::Buy, "0.5", "40");
+        let positions = PositionBook::new();
+        let risk = RiskManager::new(RiskLimits {
+            max_position_per_token: Some(50.0),
+            ..Default::default()
+        });
+
+        // 40 resting on "up" + 10 fits, + 11 does not; "down" and sells are separate
+        assert!(risk.check(&intent(OrderSide::Buy, 0.5, 10.0), &orders, &positions, None).is_ok());
+        assert!(matches!(
+            risk.check(&intent(OrderSide::Buy, 0.5, 11.0), &orders, &positions, None),
+            Err(RiskViolation::MaxPosition { position, .. }) if position == 51.0
+        ));
+        assert!(risk.check(&intent(OrderSide::Sell, 0.5, 50.0), &orders, &positions, None).is_ok());
+    }
+
+    #[test]
+    fn test_price_band() {
+        let (orders, positions) = (OrderManager::new(), PositionBook::new());
+        let book = book();
+        let risk = RiskManager::new(RiskLimits { price_band: Some(0.02), ..Default::default() });
+
+        assert!(risk
+            .check(&intent(OrderSide::Buy, 0.54, 1.0), &orders, &positions, Some(&book))
+            .is_ok());
+        assert!(matches!(
+            risk.check(&intent(OrderSide::Buy, 0.55, 1.0), &orders, &positions, Some(&book)),
+            Err(RiskViolation::PriceBand { .. })
+        ));
+        assert!(risk
+            .check(&intent(OrderSide::Sell, 0.46, 1.0), &orders, &positions, Some(&book))
+            .is_ok());
+        assert!(risk
+            .check(&intent(OrderSide::Sell, 0.45, 1.0), &orders, &positions, Some(&book))
+            .is_err());
+        assert_eq!(
+            risk.check(&intent(OrderSide::Buy, 0.5, 1.0), &orders, &positions, None),
+            Err(RiskViolation::NoBook("up".to_string()))
+        );
+    }
+}
+
+#[cfg(all(test, feature = "httpws"))]
+mod wiremock_tests {
+    use super::*;
+    use crate::httpws::rest::authed_client;
+    use crate::httpws::WalletSigner;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_gateway_blocks_and_kill_cancels() {
+        let server = MockServer::start().await;
+        Mock::given(method("DELETE"))
+            .and(path("/cancel-all"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"canceled": ["o1"]})),
+            )
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let rest = authed_client(&server.uri());
+        let signer = WalletSigner::from_hex(
+            "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+        )
+        .unwrap();
+        let risk = RiskManager::new(RiskLimits { price_band: Some(0.02), ..Default::default() });
+        let controls = risk.controls().clone();
+        let mut gateway = OrderGateway::new(
+            OrderKeeper::new(rest),
+            OrderBuilder::new(signer, crate::POLYGON_CHAIN_ID),
+            risk,
+        );
+
+        // Blocked locally: no book for the band check, nothing sent
+        let args = OrderArgs::new("up", "0.5", "10", OrderSide::Buy);
+        let err = gateway
+            .submit("0xm", &args, OrderType::Gtc, &PositionBook::new(), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AdapterError::RiskRejected(RiskViolation::NoBook(_))));
+
+        let response = gateway.kill("drawdown").await.unwrap();
+        assert_eq!(response.canceled, vec!["o1"]);
+        assert!(gateway.poll_cancels().is_none());
+
+        // Tripped from elsewhere: cancelled right away, without any gateway call
+        controls.reset();
+        controls.trip("feed stale");
+        let cancelled = tokio::time::timeout(Duration::from_secs(5), gateway.next_cancel())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(cancelled.canceled, vec!["o1"]);
+        let err = gateway
+            .submit("0xm", &args, OrderType::Gtc, &PositionBook::new(), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AdapterError::RiskRejected(RiskViolation::KillSwitch(_))));
+    }
+}
@@ -19,6 +19,14 @@
 //! # Priority
 //! For trading operations (order placement, cancellation), prefer using
 //! this official client over custom implementation.
+//!
+//! # Example
+//! ```ignore
+//! let mut client = RsClobClient::new();
+//! let book = client.get_book(token_id).await?;
+//! client.authenticate(&private_key, SignatureType::Eoa).await?;
+//! client.place_limit_order(token_id, Side::Buy, price, size, OrderType::GTC).await?;
+//! ```
 
 pub mod client;
 
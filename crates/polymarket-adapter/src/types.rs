@@ -122,6 +122,30 @@ pub struct SubscriptionChange {
     pub extra: HashMap<String, Value>,
 }
 
+impl SubscriptionChange {
+    /// Subscribe to additional market channel assets on a live connection
+    pub fn subscribe_assets(asset_ids: Vec<String>, enable_features: bool) -> Self {
+        Self {
+            asset_ids: Some(asset_ids),
+            markets: None,
+            operation: "subscribe".to_string(),
+            custom_feature_enabled: if enable_features { Some(true) } else { None },
+            extra: HashMap::new(),
+        }
+    }
+
+    /// Unsubscribe market channel assets on a live connection
+    pub fn unsubscribe_assets(asset_ids: Vec<String>) -> Self {
+        Self {
+            asset_ids: Some(asset_ids),
+            markets: None,
+            operation: "unsubscribe".to_string(),
+            custom_feature_enabled: None,
+            extra: HashMap::new(),
+        }
+    }
+}
+
 // ============================================================================
 // WebSocket Inbound Messages (from server)
 // ============================================================================
@@ -139,6 +163,56 @@ pub enum WsInboundMessage {
     SnapshotArray(Vec<Value>),
     /// Unknown or unparseable message - raw JSON preserved
     Unknown(UnknownMessage),
+    /// Connection lifecycle event injected by the client (never parsed from the wire)
+    #[serde(skip_deserializing)]
+    Lifecycle(LifecycleEvent),
+}
+
+/// Connection lifecycle event in a client message stream
+///
+/// Lets consumers invalidate derived state on `Disconnected` and trust it again
+/// once the asset is `Resynced` (first snapshot after the new subscribe).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "lifecycle", rename_all = "snake_case")]
+pub enum LifecycleEvent {
+    /// Socket connected; `epoch` counts the reconnects before this connection
+    Connected { conn_id: u64, epoch: u64 },
+    /// Subscribe sent (asset ids on the market channel, condition ids on the user channel)
+    Subscribed { conn_id: u64, ids: Vec<String> },
+    /// Connection ended; updates for `ids` may be missing until resync
+    Disconnected { conn_id: u64, ids: Vec<String>, reason: String },
+    /// Fresh snapshot received for the asset on this connection
+    Resynced { conn_id: u64, asset_id: String },
+    /// Asset silent for `silent_for_ms` on a live connection (stale-feed watchdog)
+    Stale { conn_id: u64, asset_id: String, silent_for_ms: u64 },
+    /// Messages for a stale asset resumed after `silent_for_ms`
+    Recovered { conn_id: u64, asset_id: String, silent_for_ms: u64 },
+}
+
+impl LifecycleEvent {
+    /// Event name ("connected", "subscribed", "disconnected", "resynced", "stale", "recovered")
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LifecycleEvent::Connected { .. } => "connected",
+            LifecycleEvent::Subscribed { .. } => "subscribed",
+            LifecycleEvent::Disconnected { .. } => "disconnected",
+            LifecycleEvent::Resynced { .. } => "resynced",
+            LifecycleEvent::Stale { .. } => "stale",
+            LifecycleEvent::Recovered { .. } => "recovered",
+        }
+    }
+
+    /// Connection the event belongs to
+    pub fn conn_id(&self) -> u64 {
+        match self {
+            LifecycleEvent::Connected { conn_id, .. }
+            | LifecycleEvent::Subscribed { conn_id, .. }
+            | LifecycleEvent::Disconnected { conn_id, .. }
+            | LifecycleEvent::Resynced { conn_id, .. }
+            | LifecycleEvent::Stale { conn_id, .. }
+            | LifecycleEvent::Recovered { conn_id, .. } => *conn_id,
+        }
+    }
 }
 
 /// Unknown message container - preserves raw JSON
@@ -348,6 +422,47 @@ pub struct MakerOrderDetail {
     pub data: Map<String, Value>,
 }
 
+impl MakerOrderDetail {
+    fn str_field(&self, key: &str) -> Option<&str> {
+        self.data.get(key).and_then(|v| v.as_str())
+    }
+
+    /// Maker order id
+    pub fn order_id(&self) -> Option<&str> {
+        self.str_field("order_id")
+    }
+
+    /// Owner (API key) of the maker order
+    pub fn owner(&self) -> Option<&str> {
+        self.str_field("owner")
+    }
+
+    /// Token the maker order rests on
+    pub fn asset_id(&self) -> Option<&str> {
+        self.str_field("asset_id")
+    }
+
+    /// Maker order price
+    pub fn price(&self) -> Option<&str> {
+        self.str_field("price")
+    }
+
+    /// Size matched against this maker order
+    pub fn matched_amount(&self) -> Option<&str> {
+        self.str_field("matched_amount")
+    }
+
+    /// Maker order side (not always present)
+    pub fn side(&self) -> Option<&str> {
+        self.str_field("side")
+    }
+
+    /// Maker order fee rate in basis points (not always present)
+    pub fn fee_rate_bps(&self) -> Option<&str> {
+        self.str_field("fee_rate_bps")
+    }
+}
+
 /// Trade message
 /// Source: https://docs.polymarket.com/developers/CLOB/websocket/user-channel
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -378,6 +493,13 @@ pub struct TradeMessage {
     /// Associated taker order ID
     #[serde(skip_serializing_if = "Option::is_none")]
     pub taker_order_id: Option<String>,
+    /// Fee rate of the taker order in basis points
+    #[serde(
+        default,
+        deserialize_with = "deserialize_decimal_string_opt",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub fee_rate_bps: Option<String>,
     /// Owner identifier
     #[serde(skip_serializing_if = "Option::is_none")]
     pub owner: Option<String>,
@@ -490,6 +612,116 @@ impl WsInboundMessage {
             }),
             WsInboundMessage::SnapshotArray(_) => Some("snapshot_array"),
             WsInboundMessage::Unknown(u) => u.raw.get("event_type").and_then(|v| v.as_str()),
+            WsInboundMessage::Lifecycle(e) => Some(e.as_str()),
+        }
+    }
+
+    /// Get the asset (token) id this message refers to, if any
+    ///
+    /// For `price_change` and snapshot arrays this is the first entry's asset_id.
+    pub fn asset_id(&self) -> Option<&str> {
+        match self {
+            WsInboundMessage::Market(m) => match m {
+                MarketMessage::Book(b) => Some(&b.asset_id),
+                MarketMessage::PriceChange(p) => {
+                    p.price_changes.first().map(|e| e.asset_id.as_str())
+                }
+                MarketMessage::TickSizeChange(t) => Some(&t.asset_id),
+                MarketMessage::LastTradePrice(l) => Some(&l.asset_id),
+                MarketMessage::BestBidAsk(b) => Some(&b.asset_id),
+                MarketMessage::NewMarket(_) | MarketMessage::MarketResolved(_) => None,
+            },
+            WsInboundMessage::User(u) => Some(match u {
+                UserMessage::Trade(t) => &t.asset_id,
+                UserMessage::Order(o) => &o.asset_id,
+            }),
+            WsInboundMessage::SnapshotArray(items) => {
+                items.first().and_then(|v| v.get("asset_id")).and_then(|v| v.as_str())
+            }
+            WsInboundMessage::Unknown(u) => u.raw.get("asset_id").and_then(|v| v.as_str()),
+            WsInboundMessage::Lifecycle(e) => match e {
+                LifecycleEvent::Resynced { asset_id, .. } => Some(asset_id),
+                _ => None,
+            },
+        }
+    }
+
+    /// All asset ids this message carries (every `price_change` entry and snapshot item)
+    pub fn asset_ids(&self) -> Vec<&str> {
+        match self {
+            WsInboundMessage::Market(MarketMessage::PriceChange(p)) => {
+                let mut ids: Vec<&str> = Vec::new();
+                for entry in &p.price_changes {
+                    if !ids.contains(&entry.asset_id.as_str()) {
+                        ids.push(&entry.asset_id);
+                    }
+                }
+                ids
+            }
+            WsInboundMessage::SnapshotArray(items) => {
+                items.iter().filter_map(|v| v.get("asset_id").and_then(|v| v.as_str())).collect()
+            }
+            _ => self.asset_id().into_iter().collect(),
+        }
+    }
+
+    /// Get the condition id (`market` field) this message refers to, if any
+    pub fn market_id(&self) -> Option<&str> {
+        match self {
+            WsInboundMessage::Market(m) => match m {
+                MarketMessage::Book(b) => Some(&b.market),
+                MarketMessage::PriceChange(p) => Some(&p.market),
+                MarketMessage::TickSizeChange(t) => Some(&t.market),
+                MarketMessage::LastTradePrice(l) => Some(&l.market),
+                MarketMessage::BestBidAsk(b) => Some(&b.market),
+                MarketMessage::NewMarket(n) => n.data.get("market").and_then(|v| v.as_str()),
+                MarketMessage::MarketResolved(r) => r.data.get("market").and_then(|v| v.as_str()),
+            },
+            WsInboundMessage::User(u) => Some(match u {
+                UserMessage::Trade(t) => &t.market,
+                UserMessage::Order(o) => &o.market,
+            }),
+            WsInboundMessage::SnapshotArray(items) => {
+                items.first().and_then(|v| v.get("market")).and_then(|v| v.as_str())
+            }
+            WsInboundMessage::Unknown(u) => u.raw.get("market").and_then(|v| v.as_str()),
+            WsInboundMessage::Lifecycle(_) => None,
+        }
+    }
+
+    /// Get the server timestamp in milliseconds, if any
+    ///
+    /// Market channel timestamps are ms strings; user channel timestamps
+    /// below 1e11 are taken as seconds.
+    pub fn timestamp_ms(&self) -> Option<i64> {
+        fn from_value(v: Option<&Value>) -> Option<i64> {
+            match v? {
+                Value::String(s) => s.parse().ok(),
+                v => v.as_i64(),
+            }
+        }
+        let seconds_to_ms = |ts: i64| if ts < 100_000_000_000 { ts * 1000 } else { ts };
+
+        match self {
+            WsInboundMessage::Market(m) => match m {
+                MarketMessage::Book(b) => b.timestamp.parse().ok(),
+                MarketMessage::PriceChange(p) => p.timestamp.parse().ok(),
+                MarketMessage::TickSizeChange(t) => t.timestamp.parse().ok(),
+                MarketMessage::LastTradePrice(l) => l.timestamp.parse().ok(),
+                MarketMessage::BestBidAsk(b) => b.timestamp.parse().ok(),
+                MarketMessage::NewMarket(n) => from_value(n.data.get("timestamp")),
+                MarketMessage::MarketResolved(r) => from_value(r.data.get("timestamp")),
+            },
+            WsInboundMessage::User(u) => match u {
+                UserMessage::Trade(t) => t.timestamp,
+                UserMessage::Order(o) => o.timestamp,
+            }
+            .map(seconds_to_ms),
+            WsInboundMessage::SnapshotArray(items) => {
+                from_value(items.first().and_then(|v| v.get("timestamp")))
+            }
+            WsInboundMessage::Unknown(u) => from_value(u.raw.get("timestamp")),
+            WsInboundMessage::Lifecycle(_) => None,
         }
     }
 
@@ -502,6 +734,14 @@ impl WsInboundMessage {
     pub fn is_snapshot_array(&self) -> bool {
         matches!(self, WsInboundMessage::SnapshotArray(_))
     }
+
+    /// Lifecycle event, if this is one
+    pub fn lifecycle(&self) -> Option<&LifecycleEvent> {
+        match self {
+            WsInboundMessage::Lifecycle(e) => Some(e),
+            _ => None,
+        }
+    }
 }
 
 // ============================================================================
@@ -648,6 +888,368 @@ impl GammaMarket {
     }
 }
 
+// ============================================================================
+// CLOB REST Types
+// Source: https://docs.polymarket.com/developers/CLOB/markets/get-markets
+// ============================================================================
+
+/// Deserialize a decimal that the API sends either as a string or a JSON number
+fn deserialize_decimal_string_opt<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de::Error;
+
+    match Option::<Value>::deserialize(deserializer)? {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(s)) => Ok(Some(s)),
+        Some(Value::Number(n)) => Ok(Some(n.to_string())),
+        Some(other) => Err(D::Error::custom(format!("expected decimal, got {}", other))),
+    }
+}
+
+/// Deserialize a required decimal sent either as a string or a JSON number
+fn deserialize_decimal_string<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de::Error;
+
+    deserialize_decimal_string_opt(deserializer)?
+        .ok_or_else(|| D::Error::custom("expected decimal, got null"))
+}
+
+/// Order side for price queries and order placement
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum OrderSide {
+    Buy,
+    Sell,
+}
+
+impl OrderSide {
+    /// Wire representation ("BUY" / "SELL")
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderSide::Buy => "BUY",
+            OrderSide::Sell => "SELL",
+        }
+    }
+
+    /// Parse a side string (case-insensitive)
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_uppercase().as_str() {
+            "BUY" => Some(OrderSide::Buy),
+            "SELL" => Some(OrderSide::Sell),
+            _ => None,
+        }
+    }
+
+    /// The other side
+    pub fn opposite(&self) -> Self {
+        match self {
+            OrderSide::Buy => OrderSide::Sell,
+            OrderSide::Sell => OrderSide::Buy,
+        }
+    }
+}
+
+/// CLOB market outcome token
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ClobToken {
+    /// Token identifier (asset_id)
+    pub token_id: String,
+    /// Outcome label (e.g., "Up")
+    #[serde(default)]
+    pub outcome: String,
+    /// Last price
+    #[serde(default, deserialize_with = "deserialize_decimal_string_opt")]
+    pub price: Option<String>,
+    /// Whether this outcome won (resolved markets)
+    #[serde(default)]
+    pub winner: bool,
+    /// Extra fields
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// CLOB market from GET /markets/{condition_id}
+/// Source: https://docs.polymarket.com/developers/CLOB/markets/get-markets
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ClobMarket {
+    /// Condition ID
+    pub condition_id: String,
+    /// Question ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub question_id: Option<String>,
+    /// Market question
+    #[serde(default)]
+    pub question: String,
+    /// Market slug
+    #[serde(default)]
+    pub market_slug: String,
+    /// Outcome tokens
+    #[serde(default)]
+    pub tokens: Vec<ClobToken>,
+    /// Whether market is active
+    #[serde(default)]
+    pub active: bool,
+    /// Whether market is closed
+    #[serde(default)]
+    pub closed: bool,
+    /// Whether market is archived
+    #[serde(default)]
+    pub archived: bool,
+    /// Whether the book accepts orders
+    #[serde(default)]
+    pub accepting_orders: bool,
+    /// Whether order book is enabled
+    #[serde(default)]
+    pub enable_order_book: bool,
+    /// Minimum order size
+    #[serde(default, deserialize_with = "deserialize_decimal_string_opt")]
+    pub minimum_order_size: Option<String>,
+    /// Minimum tick size
+    #[serde(default, deserialize_with = "deserialize_decimal_string_opt")]
+    pub minimum_tick_size: Option<String>,
+    /// Negative-risk market (uses the neg-risk exchange)
+    #[serde(default)]
+    pub neg_risk: bool,
+    /// Market end time (ISO 8601)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_date_iso: Option<String>,
+    /// Extra fields
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl ClobMarket {
+    /// Find the token for an outcome label (case-insensitive)
+    pub fn token_for_outcome(&self, outcome: &str) -> Option<&ClobToken> {
+        self.tokens.iter().find(|t| t.outcome.eq_ignore_ascii_case(outcome))
+    }
+}
+
+/// Response from GET /price
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PriceResponse {
+    /// Best price on the requested side
+    #[serde(deserialize_with = "deserialize_decimal_string")]
+    pub price: String,
+    /// Extra fields
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Response from GET /midpoint
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MidpointResponse {
+    /// Midpoint between best bid and best ask
+    #[serde(deserialize_with = "deserialize_decimal_string")]
+    pub mid: String,
+    /// Extra fields
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Response from GET /spread
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SpreadResponse {
+    /// Best ask minus best bid
+    #[serde(deserialize_with = "deserialize_decimal_string")]
+    pub spread: String,
+    /// Extra fields
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Response from GET /tick-size
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TickSizeResponse {
+    /// Minimum price increment
+    #[serde(deserialize_with = "deserialize_decimal_string")]
+    pub minimum_tick_size: String,
+    /// Extra fields
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Response from GET /neg-risk
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NegRiskResponse {
+    /// Whether the token trades on the neg-risk exchange
+    pub neg_risk: bool,
+    /// Extra fields
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+// ============================================================================
+// Order Types
+// Source: https://docs.polymarket.com/developers/CLOB/orders/create-order
+// ============================================================================
+
+/// Deserialize `null` as the type's default
+fn deserialize_null_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+/// Order time-in-force
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum OrderType {
+    /// Good-till-cancelled
+    #[default]
+    Gtc,
+    /// Fill-or-kill
+    Fok,
+    /// Good-till-date (requires expiration)
+    Gtd,
+    /// Fill-and-kill (partial fill allowed, rest cancelled)
+    Fak,
+}
+
+/// Signed order as sent to POST /order
+///
+/// All uint256 fields except `salt` are decimal strings; `salt` is a JSON number.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedOrder {
+    /// Random salt (fits in an IEEE 754 double)
+    pub salt: u64,
+    /// Funder address (holds the funds)
+    pub maker: String,
+    /// Signing address
+    pub signer: String,
+    /// Taker address (zero address = public order)
+    pub taker: String,
+    /// Outcome token id
+    pub token_id: String,
+    /// Amount given, 6 decimals
+    pub maker_amount: String,
+    /// Amount received, 6 decimals
+    pub taker_amount: String,
+    /// Unix expiration (seconds), "0" for none
+    pub expiration: String,
+    /// Exchange nonce
+    pub nonce: String,
+    /// Fee rate in basis points
+    pub fee_rate_bps: String,
+    /// Order side
+    pub side: OrderSide,
+    /// Signature type (0 = EOA, 1 = Poly proxy, 2 = Gnosis safe)
+    pub signature_type: u8,
+    /// EIP-712 signature (0x-prefixed hex)
+    pub signature: String,
+}
+
+/// Body of POST /order
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostOrderRequest {
+    /// Signed order
+    pub order: SignedOrder,
+    /// API key of the order owner
+    pub owner: String,
+    /// Time-in-force
+    pub order_type: OrderType,
+}
+
+/// Response from POST /order
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostOrderResponse {
+    /// Whether the order was accepted
+    pub success: bool,
+    /// Rejection reason (empty on success)
+    #[serde(default, deserialize_with = "deserialize_null_default")]
+    pub error_msg: String,
+    /// Order id (hash)
+    #[serde(rename = "orderID", default)]
+    pub order_id: String,
+    /// Order status ("live", "matched", "delayed", "unmatched")
+    #[serde(default)]
+    pub status: String,
+    /// Amount given so far
+    #[serde(default, deserialize_with = "deserialize_decimal_string_opt")]
+    pub making_amount: Option<String>,
+    /// Amount received so far
+    #[serde(default, deserialize_with = "deserialize_decimal_string_opt")]
+    pub taking_amount: Option<String>,
+    /// Settlement transaction hashes (matched orders)
+    #[serde(default, alias = "transactionsHashes", deserialize_with = "deserialize_null_default")]
+    pub transaction_hashes: Vec<String>,
+    /// Extra fields
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Response from the cancel endpoints
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CancelResponse {
+    /// Ids of cancelled orders
+    #[serde(default, deserialize_with = "deserialize_null_default")]
+    pub canceled: Vec<String>,
+    /// Ids that could not be cancelled, with the reason
+    #[serde(default, alias = "notCanceled", deserialize_with = "deserialize_null_default")]
+    pub not_canceled: HashMap<String, String>,
+    /// Extra fields
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Open order as returned by GET /data/orders
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OpenOrder {
+    /// Order id (hash)
+    pub id: String,
+    /// Order status (e.g. "LIVE")
+    #[serde(default)]
+    pub status: String,
+    /// Condition ID
+    pub market: String,
+    /// Token identifier
+    pub asset_id: String,
+    /// Order side (BUY/SELL)
+    pub side: String,
+    /// Initial order size
+    #[serde(deserialize_with = "deserialize_decimal_string")]
+    pub original_size: String,
+    /// Matched quantity
+    #[serde(default, deserialize_with = "deserialize_decimal_string_opt")]
+    pub size_matched: Option<String>,
+    /// Order price
+    #[serde(deserialize_with = "deserialize_decimal_string")]
+    pub price: String,
+    /// Market outcome
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outcome: Option<String>,
+    /// Creation time (unix seconds)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<i64>,
+    /// Extra fields
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// One page of GET /data/orders
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OpenOrdersPage {
+    /// Orders on this page
+    #[serde(default, deserialize_with = "deserialize_null_default")]
+    pub data: Vec<OpenOrder>,
+    /// Cursor for the next page ("LTE=" when done)
+    #[serde(default)]
+    pub next_cursor: String,
+    /// Extra fields
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
 // ============================================================================
 // Market Resolver Types
 // ============================================================================
@@ -722,6 +1324,7 @@ pub struct ResolvedMarket {
 /// Result of market resolution attempt
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(tag = "status", rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
 pub enum ResolveResult {
     /// Successfully resolved to a unique market
     Ok(ResolvedMarket),
@@ -833,6 +1436,66 @@ pub struct SwitchStats {
     pub last_switch_latency_ms: Option<u64>,
 }
 
+// ============================================================================
+// Recording Types
+// ============================================================================
+
+/// Recorded frame with local receive metadata (one line of an enveloped JSONL file)
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordEnvelope {
+    /// Local receive time (unix nanoseconds)
+    pub recv_ts_ns: u64,
+    /// Connection id (unique per WebSocket connection within a process)
+    pub conn_id: u64,
+    /// Reconnect epoch of the client (0 = first connection)
+    pub epoch: u64,
+    /// Text frame exactly as received
+    pub raw: String,
+}
+
+impl RecordEnvelope {
+    /// Parse an enveloped line; None for plain lines (raw frames)
+    pub fn parse_line(line: &str) -> Option<Self> {
+        if !line.trim_start().starts_with('{') || !line.contains("\"recv_ts_ns\"") {
+            return None;
+        }
+        serde_json::from_str(line).ok()
+    }
+}
+
+/// Session manifest of a rotating recording (`<prefix>_<ts>.manifest.json`)
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionManifest {
+    /// Session name (manifest file stem)
+    pub session: String,
+    /// Line format ("raw" or "envelope")
+    pub format: String,
+    /// Session start (RFC 3339)
+    pub started_at: String,
+    /// Session end (RFC 3339), None while recording or after a crash
+    #[serde(default)]
+    pub ended_at: Option<String>,
+    /// Segments in recording order
+    #[serde(default)]
+    pub segments: Vec<SegmentInfo>,
+}
+
+/// One segment file of a rotating recording
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SegmentInfo {
+    /// File name relative to the manifest (".gz" / ".zst" once compressed)
+    pub file: String,
+    /// Segment open time (RFC 3339)
+    pub opened_at: String,
+    /// Segment close time (RFC 3339), None for the active segment
+    #[serde(default)]
+    pub closed_at: Option<String>,
+    /// Lines written
+    pub lines: u64,
+    /// Uncompressed bytes written
+    pub bytes: u64,
+}
+
 // ============================================================================
 // Statistics Tracking
 // ============================================================================
@@ -855,6 +1518,10 @@ impl MessageStats {
     }
 
     pub fn record(&mut self, msg: &WsInboundMessage) {
+        // Injected by the client, not received
+        if let WsInboundMessage::Lifecycle(_) = msg {
+            return;
+        }
         self.total_messages += 1;
 
         match msg {
@@ -907,6 +1574,37 @@ mod tests {
         assert_eq!(msg.event_type(), Some("book"));
     }
 
+    #[test]
+    fn test_message_asset_and_market_ids() {
+        let json = r#"{
+            "event_type": "price_change",
+            "market": "condition456",
+            "timestamp": "1704067200000",
+            "price_changes": [{"asset_id": "token123", "price": "0.5", "size": "1", "side": "BUY"}]
+        }"#;
+        let msg = WsInboundMessage::parse(json);
+        assert_eq!(msg.asset_id(), Some("token123"));
+        assert_eq!(msg.market_id(), Some("condition456"));
+        assert_eq!(msg.timestamp_ms(), Some(1704067200000));
+
+        assert_eq!(msg.asset_ids(), ["token123"]);
+
+        let snapshot = WsInboundMessage::parse(
+            r#"[{"asset_id": "token9", "market": "cond9"}, {"asset_id": "token10"}]"#,
+        );
+        assert_eq!(snapshot.asset_id(), Some("token9"));
+        assert_eq!(snapshot.asset_ids(), ["token9", "token10"]);
+        assert_eq!(snapshot.market_id(), Some("cond9"));
+        assert_eq!(snapshot.timestamp_ms(), None);
+
+        let order = WsInboundMessage::parse(
+            r#"{"event_type": "order", "id": "0x1", "asset_id": "t", "market": "m",
+                "original_size": "1", "price": "0.5", "side": "BUY", "type": "PLACEMENT",
+                "timestamp": 1704067200}"#,
+        );
+        assert_eq!(order.timestamp_ms(), Some(1704067200000));
+    }
+
     #[test]
     fn test_parse_unknown_message() {
         let json = r#"{"event_type": "some_future_type", "data": "test"}"#;
@@ -915,6 +1613,25 @@ mod tests {
         assert_eq!(msg.event_type(), Some("some_future_type"));
     }
 
+    #[test]
+    fn test_lifecycle_never_parsed_from_wire() {
+        let event = LifecycleEvent::Resynced { conn_id: 3, asset_id: "token123".to_string() };
+        let json = serde_json::to_string(&event).unwrap();
+        assert_eq!(json, r#"{"lifecycle":"resynced","conn_id":3,"asset_id":"token123"}"#);
+
+        // A server message with the same shape stays Unknown
+        assert!(WsInboundMessage::parse(&json).is_unknown());
+
+        let msg = WsInboundMessage::Lifecycle(event);
+        assert_eq!(msg.event_type(), Some("resynced"));
+        assert_eq!(msg.asset_id(), Some("token123"));
+        assert_eq!(msg.lifecycle().map(|e| e.conn_id()), Some(3));
+
+        let mut stats = MessageStats::new();
+        stats.record(&msg);
+        assert_eq!(stats.total_messages, 0);
+    }
+
     #[test]
     fn test_parse_invalid_json() {
         let msg = WsInboundMessage::parse("not valid json");
@@ -944,4 +1661,104 @@ mod tests {
         assert!(json.contains("\"type\":\"user\""));
         assert!(json.contains("apiKey"));
     }
+
+    #[test]
+    fn test_subscription_change_serialization() {
+        let sub = SubscriptionChange::subscribe_assets(vec!["asset1".to_string()], true);
+        let json = serde_json::to_string(&sub).unwrap();
+        assert!(json.contains("\"operation\":\"subscribe\""));
+        assert!(json.contains("\"asset_ids\":[\"asset1\"]"));
+        assert!(json.contains("custom_feature_enabled"));
+
+        let unsub = SubscriptionChange::unsubscribe_assets(vec!["asset1".to_string()]);
+        let json = serde_json::to_string(&unsub).unwrap();
+        assert!(json.contains("\"operation\":\"unsubscribe\""));
+        assert!(!json.contains("markets"));
+    }
+
+    #[test]
+    fn test_clob_market_numeric_decimals() {
+        let json = r#"{
+            "condition_id": "0xabc",
+            "question": "Up or down?",
+            "tokens": [
+                {"token_id": "1", "outcome": "Up", "price": 0.505, "winner": false},
+                {"token_id": "2", "outcome": "Down", "price": "0.495"}
+            ],
+            "active": true,
+            "minimum_tick_size": 0.01,
+            "neg_risk": false,
+            "rewards": {"rates": null}
+        }"#;
+        let market: ClobMarket = serde_json::from_str(json).unwrap();
+        assert_eq!(market.tokens.len(), 2);
+        assert_eq!(market.token_for_outcome("up").unwrap().price.as_deref(), Some("0.505"));
+        assert_eq!(market.tokens[1].price.as_deref(), Some("0.495"));
+        assert_eq!(market.minimum_tick_size.as_deref(), Some("0.01"));
+        assert!(market.extra.contains_key("rewards"));
+    }
+
+    #[test]
+    fn test_rest_decimal_responses() {
+        let price: PriceResponse = serde_json::from_str(r#"{"price": "0.55"}"#).unwrap();
+        assert_eq!(price.price, "0.55");
+
+        let mid: MidpointResponse = serde_json::from_str(r#"{"mid": 0.5, "x": 1}"#).unwrap();
+        assert_eq!(mid.mid, "0.5");
+        assert!(mid.extra.contains_key("x"));
+
+        assert!(serde_json::from_str::<SpreadResponse>(r#"{"spread": null}"#).is_err());
+        assert!(serde_json::from_str::<TickSizeResponse>(r#"{"minimum_tick_size": {}}"#).is_err());
+    }
+
+    #[test]
+    fn test_order_responses() {
+        let json = r#"{
+            "success": true,
+            "errorMsg": "",
+            "orderID": "0xabc",
+            "status": "matched",
+            "makingAmount": "10.5",
+            "takingAmount": "",
+            "transactionsHashes": ["0x1"]
+        }"#;
+        let response: PostOrderResponse = serde_json::from_str(json).unwrap();
+        assert!(response.success);
+        assert_eq!(response.order_id, "0xabc");
+        assert_eq!(response.making_amount.as_deref(), Some("10.5"));
+        assert_eq!(response.transaction_hashes, vec!["0x1"]);
+
+        let json = r#"{"canceled": ["0x1"], "not_canceled": {"0x2": "order not found"}}"#;
+        let cancel: CancelResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(cancel.canceled, vec!["0x1"]);
+        assert_eq!(cancel.not_canceled["0x2"], "order not found");
+
+        let cancel: CancelResponse =
+            serde_json::from_str(r#"{"canceled": null, "not_canceled": null}"#).unwrap();
+        assert!(cancel.canceled.is_empty());
+    }
+
+    #[test]
+    fn test_order_side() {
+        assert_eq!(OrderSide::parse("buy"), Some(OrderSide::Buy));
+        assert_eq!(OrderSide::Buy.opposite(), OrderSide::Sell);
+        assert_eq!(serde_json::to_string(&OrderSide::Sell).unwrap(), "\"SELL\"");
+    }
+
+    #[test]
+    fn test_record_envelope_parse_line() {
+        let envelope = RecordEnvelope {
+            recv_ts_ns: 1_704_067_200_123_000_000,
+            conn_id: 3,
+            epoch: 1,
+            raw: r#"{"event_type":"book","recv_ts_ns":"in payload"}"#.to_string(),
+        };
+        let line = serde_json::to_string(&envelope).unwrap();
+        assert_eq!(RecordEnvelope::parse_line(&line), Some(envelope));
+
+        // Plain frames, even ones mentioning the key inside a payload string
+        assert_eq!(RecordEnvelope::parse_line(r#"{"event_type":"book","timestamp":"1"}"#), None);
+        assert_eq!(RecordEnvelope::parse_line(r#"[{"recv_ts_ns":"x"}]"#), None);
+        assert_eq!(RecordEnvelope::parse_line(r#"{"note":"recv_ts_ns"}"#), None);
+    }
 }